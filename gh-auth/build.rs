@@ -4,24 +4,22 @@ use std::path::{Path, PathBuf};
 
 fn main() -> anyhow::Result<()> {
     let success_html = fs::read_to_string("success.html")?;
-    let (client_id, client_secret) = {
+    let error_html = fs::read_to_string("error.html")?;
+    let client_id = {
         let client_id = std::env::var("GH_OAUTH_CLIENT_ID");
-        let client_secret = std::env::var("GH_OAUTH_CLIENT_SECRET");
         match std::env::var("PROFILE").unwrap().as_ref() {
-            "release" => (
-                client_id.expect("Required to set env $GH_OAUTH_CLIENT_ID"),
-                client_secret.expect("Required to set env $GH_OAUTH_CLIENT_SECRET"),
-            ),
-            _ => (
-                client_id.unwrap_or_default(),
-                client_secret.unwrap_or_default(),
-            ),
+            "release" => client_id.expect("Required to set env $GH_OAUTH_CLIENT_ID"),
+            _ => client_id.unwrap_or_default(),
         }
     };
+    // GitHub exchanges the code of the browser flow only with the secret of the OAuth app, even
+    // with PKCE, builds without it log in with the device flow.
+    let client_secret = std::env::var("GH_OAUTH_CLIENT_SECRET").unwrap_or_default();
 
     let generated_code = quote::quote! {
       pub const OAUTH_HOST: &str = "github.com";
       const SUCCESS_HTML: &str = #success_html;
+      const ERROR_HTML: &str = #error_html;
       pub const OAUTH_CLIENT_ID: &str = #client_id;
       const OAUTH_CLIENT_SECRET: &str = #client_secret;
     };
@@ -32,6 +30,7 @@ fn main() -> anyhow::Result<()> {
     println!("cargo:rerun-if-env-changed=GH_OAUTH_CLIENT_ID");
     println!("cargo:rerun-if-env-changed=GH_OAUTH_CLIENT_SECRET");
     println!("cargo:rerun-if-changed=success.html");
+    println!("cargo:rerun-if-changed=error.html");
     println!("cargo:rerun-if-changed=build.rs");
     Ok(())
 }
@@ -0,0 +1,308 @@
+#![cfg(feature = "config")]
+//! Accounts of several hosts and logins live side by side in the credentials file. The token of a
+//! command, unless given explicitly, is the one of the credential helper, else the one of the
+//! GitHub App installation, else the one of the first rule of the config file matching the
+//! current directory or the repository owner, else the one of the active account.
+use crate::config::{self, AccountRule, Config};
+use crate::credential_helper::CredentialHelper;
+use crate::credentials::{self, CredentialStore as _, Credentials, FileStore, Identity};
+use crate::output::Stylize;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use gh_lib::core::users::{UserRequest, Users as _};
+use gh_lib::token::TokenProvider;
+use once_cell::sync::{Lazy, OnceCell};
+use std::path::{Path, PathBuf};
+
+/// Host the API requests go to, unless `$GH_HOST` or the git remote is another one.
+pub(crate) const DEFAULT_HOST: &str = gh_auth::OAUTH_HOST;
+
+static ACCOUNTS: Lazy<AsyncOnce<Accounts>> = Lazy::new(AsyncOnce::new);
+static HELPER_TOKEN: Lazy<AsyncOnce<Option<String>>> = Lazy::new(AsyncOnce::new);
+
+/// Value computed by the first request needing it, the concurrent ones waiting for it, eg. so
+/// that the passphrase is asked for once.
+pub(crate) struct AsyncOnce<T> {
+    value: OnceCell<T>,
+    init: tokio::sync::Mutex<()>,
+}
+
+impl<T> AsyncOnce<T> {
+    pub fn new() -> Self {
+        AsyncOnce {
+            value: OnceCell::new(),
+            init: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub async fn get_or_try_init<F, Fut>(&self, init: F) -> Result<&T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+        let _init = self.init.lock().await;
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+        let value = init().await?;
+        Ok(self.value.get_or_init(|| value))
+    }
+}
+
+/// Where the token of a command given none comes from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TokenSource {
+    Helper,
+    Env,
+    #[cfg(feature = "app")]
+    App,
+    Store,
+}
+
+pub(crate) struct Accounts {
+    pub store: FileStore,
+    pub credentials: Credentials,
+    pub config: Config,
+}
+
+impl Accounts {
+    pub async fn load() -> Result<Self> {
+        let store = FileStore::open()?;
+        let credentials = credentials::load_credentials(&store).await?;
+        let config = config::get_config().await.unwrap_or_default();
+        Ok(Accounts {
+            store,
+            credentials,
+            config,
+        })
+    }
+
+    /// The chosen account of the host, or its first one.
+    pub fn active(&self, host: &str) -> Option<&Identity> {
+        self.config
+            .active_login(host)
+            .and_then(|login| self.credentials.find(host, login))
+            .or_else(|| {
+                let identities = &self.credentials.identities;
+                identities.iter().find(|identity| identity.host == host)
+            })
+    }
+
+    /// Account for the repositories of `owner`, when known, in `directory`.
+    pub fn select(&self, host: &str, owner: Option<&str>, directory: &Path) -> Option<&Identity> {
+        self.config
+            .rules
+            .iter()
+            .filter(|rule| rule.host == host && rule_matches(rule, owner, directory))
+            .find_map(|rule| self.credentials.find(&rule.host, &rule.login))
+            .or_else(|| self.active(host))
+    }
+}
+
+fn rule_matches(rule: &AccountRule, owner: Option<&str>, directory: &Path) -> bool {
+    let owner_matches = match (&rule.owner, owner) {
+        (None, _) => true,
+        (Some(rule_owner), Some(owner)) => rule_owner.eq_ignore_ascii_case(owner),
+        (Some(_), None) => false,
+    };
+    let directory_matches = match &rule.directory {
+        None => true,
+        Some(rule_directory) => directory.starts_with(expand_home(rule_directory)),
+    };
+    (rule.owner.is_some() || rule.directory.is_some()) && owner_matches && directory_matches
+}
+
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path, dirs::home_dir()) {
+        ("~", Some(home)) => home,
+        (path, Some(home)) if path.starts_with("~/") => home.join(&path[2..]),
+        (path, _) => PathBuf::from(path),
+    }
+}
+
+/// The stored accounts, loaded and decrypted by the first command needing them.
+pub(crate) async fn get() -> Result<&'static Accounts> {
+    ACCOUNTS
+        .get_or_try_init(|| async {
+            Accounts::load()
+                .await
+                .map_err(|err| anyhow!("Unable to load the stored token, {}", err))
+        })
+        .await
+}
+
+/// Token of the account selected for the repositories of `owner`, when known, if any.
+pub(crate) async fn stored_token(owner: Option<&str>) -> Result<Option<String>> {
+    let directory = std::env::current_dir().unwrap_or_default();
+    Ok(get()
+        .await?
+        .select(&crate::TARGET_HOST, owner, &directory)
+        .map(|identity| identity.access_token.to_owned()))
+}
+
+/// Token of the credential helper for the host of the requests, its failures being warnings.
+async fn helper_token() -> Option<&'static String> {
+    let token = HELPER_TOKEN.get_or_try_init(|| async {
+        let helper = match CredentialHelper::from_config().await {
+            Some(helper) => helper,
+            None => return Ok(None),
+        };
+        match helper.get(&crate::TARGET_HOST).await {
+            Ok(credential) => Ok(credential.map(|credential| credential.token)),
+            Err(err) => {
+                eprintln!(
+                    "{} {}",
+                    "warning: ".bold().yellow(),
+                    err.to_string().yellow()
+                );
+                Ok(None)
+            }
+        }
+    });
+    token.await.ok().and_then(Option::as_ref)
+}
+
+/// Token of a command given none, for the repositories of `owner` when known: the one of the
+/// credential helper, else `$GH_ACCESS_TOKEN`, else the one of the GitHub App installation of the
+/// env, else the one of the stored account selected.
+pub(crate) async fn resolve(owner: Option<&str>) -> Result<Option<(String, TokenSource)>> {
+    if let Some(token) = helper_token().await {
+        return Ok(Some((token.to_owned(), TokenSource::Helper)));
+    }
+    if let Some(token) = crate::env_token() {
+        return Ok(Some((token, TokenSource::Env)));
+    }
+    #[cfg(feature = "app")]
+    if let Some(installation) = crate::app::installation_token().await {
+        return Ok(Some((installation.get().await?, TokenSource::App)));
+    }
+    Ok(stored_token(owner)
+        .await?
+        .map(|token| (token, TokenSource::Store)))
+}
+
+/// Token of the commands given none, resolved by their first request.
+pub(crate) struct LazyToken {
+    owner: Option<String>,
+}
+
+impl LazyToken {
+    pub fn new(owner: Option<&str>) -> Self {
+        LazyToken {
+            owner: owner.map(String::from),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for LazyToken {
+    async fn token(&self) -> Result<String> {
+        resolve(self.owner.as_deref())
+            .await?
+            .map(|(token, _)| token)
+            .ok_or_else(|| anyhow!("Not logged in, run `gh-cli login` or set GH_ACCESS_TOKEN"))
+    }
+}
+
+/// API root of `host`: its `api_url` in the config file, else the one of github.com or of a
+/// GitHub Enterprise Server.
+pub(crate) fn api_url(host: &str) -> String {
+    match config::current().and_then(|config| config.api_url(host)) {
+        Some(api_url) => api_url.to_owned(),
+        None if host == DEFAULT_HOST => gh_lib::BASE_URL.to_owned(),
+        None => format!("https://{}/api/v3", host),
+    }
+}
+
+/// Sends the following requests to `host`.
+pub(crate) fn connect(host: &str) {
+    gh_lib::set_api_url(&api_url(host));
+}
+
+/// Stores the token of `host` under the login it belongs to and makes it the active account.
+pub(crate) async fn add_identity(
+    store: &FileStore,
+    host: &str,
+    access_token: &str,
+) -> Result<Identity> {
+    connect(host);
+    let user = UserRequest::try_from(access_token)?
+        .get_authenticated_user()
+        .await?
+        .user;
+    let identity = Identity {
+        host: host.to_owned(),
+        login: user.login,
+        access_token: access_token.to_owned(),
+    };
+    let mut credentials = credentials::load_credentials(store).await?;
+    credentials.upsert(identity.clone());
+    store.save(&credentials).await?;
+
+    let mut config = config::get_config().await.unwrap_or_default();
+    config.set_active_login(&identity.host, identity.login.to_owned());
+    config::save_config(config).await?;
+    Ok(identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(login: &str) -> Identity {
+        Identity {
+            host: "github.com".into(),
+            login: login.into(),
+            access_token: format!("token_of_{}", login),
+        }
+    }
+
+    fn rule(directory: Option<&str>, owner: Option<&str>, login: &str) -> AccountRule {
+        AccountRule {
+            directory: directory.map(String::from),
+            owner: owner.map(String::from),
+            host: "github.com".into(),
+            login: login.into(),
+        }
+    }
+
+    #[test]
+    fn select_account() {
+        let mut accounts = Accounts {
+            store: FileStore::at(PathBuf::from("/nonexistent/credentials.toml")),
+            credentials: Credentials {
+                identities: vec![identity("personal"), identity("work"), identity("oss")],
+            },
+            config: Config::default(),
+        };
+        let home = Path::new("/home/octocat");
+        let work = Path::new("/src/work/api");
+        let login = |accounts: &Accounts, owner, directory| {
+            accounts
+                .select("github.com", owner, directory)
+                .map(|identity| identity.login.to_owned())
+        };
+
+        assert_eq!(login(&accounts, None, home), Some("personal".into()));
+        accounts.config.set_active_login("github.com", "oss".into());
+        accounts.config.rules = vec![
+            rule(Some("/src/work"), Some("rust-lang"), "oss"),
+            rule(Some("/src/work"), None, "work"),
+            rule(None, Some("ACME"), "work"),
+            rule(None, Some("ghost"), "unknown"),
+        ];
+
+        assert_eq!(login(&accounts, None, home), Some("oss".into()));
+        assert_eq!(login(&accounts, None, work), Some("work".into()));
+        assert_eq!(
+            login(&accounts, Some("rust-lang"), work),
+            Some("oss".into())
+        );
+        assert_eq!(login(&accounts, Some("acme"), home), Some("work".into()));
+        assert_eq!(login(&accounts, Some("ghost"), home), Some("oss".into()));
+        assert_eq!(accounts.select("ghe.example.com", None, home), None);
+    }
+}
@@ -0,0 +1,218 @@
+#![cfg(feature = "config")]
+//! Aliases of the config file expand the first argument of the command line before it is parsed,
+//! `$1`, `$2`.. standing for the arguments following the alias, the others being appended.
+//! Aliases starting with `!` are run by the shell instead, the arguments as its positional ones.
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+
+pub(crate) enum Expansion {
+    /// Arguments to parse, expanded or not.
+    Args(Vec<String>),
+    /// Shell command of the `!` alias `name`, with the arguments following it.
+    Shell {
+        name: String,
+        command: String,
+        args: Vec<String>,
+    },
+}
+
+/// Expands the alias of `args`, if any, builtin commands never being expanded.
+pub(crate) fn expand(
+    args: Vec<String>,
+    aliases: &BTreeMap<String, String>,
+    builtins: &[String],
+) -> Result<Expansion> {
+    let (name, expansion) = match args.get(1) {
+        Some(name) if !builtins.contains(name) => match aliases.get(name) {
+            Some(expansion) => (name.to_owned(), expansion),
+            None => return Ok(Expansion::Args(args)),
+        },
+        _ => return Ok(Expansion::Args(args)),
+    };
+    let mut args = args.into_iter();
+    let mut expanded = args.next().into_iter().collect::<Vec<_>>();
+    let rest = args.skip(1).collect::<Vec<_>>();
+    if let Some(command) = expansion.strip_prefix('!') {
+        return Ok(Expansion::Shell {
+            name,
+            command: command.to_owned(),
+            args: rest,
+        });
+    }
+
+    let placeholder = regex::Regex::new(r"\$(\d+)").unwrap();
+    let mut used = 0;
+    for word in split_words(expansion)? {
+        let mut missing = None;
+        let word = placeholder.replace_all(&word, |caps: &regex::Captures| {
+            let index = caps[1].parse::<usize>().unwrap_or_default();
+            used = used.max(index);
+            match index.checked_sub(1).and_then(|index| rest.get(index)) {
+                Some(arg) => arg.to_owned(),
+                None => {
+                    missing = Some(index);
+                    String::new()
+                }
+            }
+        });
+        if let Some(index) = missing {
+            return Err(anyhow!(
+                "The alias `{}` expects {} argument(s), `${}` is missing",
+                name,
+                used.max(index),
+                index
+            ));
+        }
+        expanded.push(word.into_owned());
+    }
+    expanded.extend(rest.into_iter().skip(used));
+    Ok(Expansion::Args(expanded))
+}
+
+/// Checks that the alias `name` shadows no builtin command and expands to one.
+pub(crate) fn validate(name: &str, expansion: &str, builtins: &[String]) -> Result<()> {
+    if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+        return Err(anyhow!("Invalid alias name `{}`", name));
+    }
+    if builtins.iter().any(|builtin| builtin == name) {
+        return Err(anyhow!(
+            "`{}` is a builtin command, it can't be an alias",
+            name
+        ));
+    }
+    if expansion.starts_with('!') {
+        return Ok(());
+    }
+    match split_words(expansion)?.first() {
+        Some(command) if builtins.contains(command) => Ok(()),
+        _ => Err(anyhow!(
+            "The expansion must start with a command of gh-cli, eg. `actions runs list`, \
+             or with `!` for a shell command"
+        )),
+    }
+}
+
+/// Runs the shell command of an alias, returning its exit code.
+pub(crate) async fn run_shell(name: &str, command: &str, args: &[String]) -> Result<i32> {
+    let status = if cfg!(windows) {
+        tokio::process::Command::new("cmd")
+            .arg("/C")
+            .arg(command)
+            .args(args)
+            .status()
+            .await
+    } else {
+        // `$0` is the alias, `$1`.. its arguments
+        tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .arg(name)
+            .args(args)
+            .status()
+            .await
+    }
+    .map_err(|err| anyhow!("Unable to run the alias `{}`, {}", name, err))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Words of `line`, split on whitespace outside of single or double quotes, a backslash escaping
+/// the next character outside of single quotes.
+fn split_words(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                let escaped = chars
+                    .next()
+                    .ok_or_else(|| anyhow!("Trailing backslash in `{}`", line))?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'') | (None, '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(anyhow!("Unterminated quote in `{}`", line));
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split(' ').map(String::from).collect()
+    }
+
+    fn expanded(line: &str, aliases: &BTreeMap<String, String>) -> Result<Vec<String>> {
+        let builtins = vec!["actions".to_owned(), "alias".to_owned()];
+        match expand(args(line), aliases, &builtins)? {
+            Expansion::Args(args) => Ok(args),
+            Expansion::Shell { command, .. } => Err(anyhow!("shell alias {}", command)),
+        }
+    }
+
+    #[test]
+    fn expand_aliases() -> Result<()> {
+        let aliases = vec![
+            ("fails", "actions runs list --status failure --branch $1"),
+            (
+                "note",
+                r#"actions runs list --name "$2/$1" --title 'a \ b'"#,
+            ),
+            ("actions", "alias list"),
+            ("sh", "!echo $1"),
+        ]
+        .into_iter()
+        .map(|(name, expansion)| (name.to_owned(), expansion.to_owned()))
+        .collect();
+
+        assert_eq!(
+            expanded("gh-cli fails main --limit 5", &aliases)?,
+            args("gh-cli actions runs list --status failure --branch main --limit 5")
+        );
+        assert_eq!(
+            expanded("gh-cli note repo owner", &aliases)?,
+            vec![
+                "gh-cli",
+                "actions",
+                "runs",
+                "list",
+                "--name",
+                "owner/repo",
+                "--title",
+                "a \\ b"
+            ]
+        );
+        assert!(expanded("gh-cli fails", &aliases).is_err());
+        assert_eq!(
+            expanded("gh-cli actions runs", &aliases)?,
+            args("gh-cli actions runs")
+        );
+        assert_eq!(expanded("gh-cli other", &aliases)?, args("gh-cli other"));
+        assert!(expanded("gh-cli sh x", &aliases).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn validate_aliases() {
+        let builtins = vec!["actions".to_owned(), "alias".to_owned()];
+        assert!(validate("fails", "actions runs list", &builtins).is_ok());
+        assert!(validate("open", "!xdg-open https://github.com", &builtins).is_ok());
+        assert!(validate("actions", "actions runs list", &builtins).is_err());
+        assert!(validate("fails", "runs list", &builtins).is_err());
+        assert!(validate("fails", "actions 'runs", &builtins).is_err());
+        assert!(validate("--fails", "actions runs list", &builtins).is_err());
+    }
+}
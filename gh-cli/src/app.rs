@@ -0,0 +1,71 @@
+#![cfg(feature = "app")]
+//! With `GH_APP_ID`, `GH_APP_PRIVATE_KEY` and `GH_APP_INSTALLATION_ID` set, and no explicit access
+//! token, the commands authenticate as the installation of the GitHub App, their tokens renewed
+//! before they expire.
+use crate::accounts::{self, AsyncOnce};
+use crate::output::Stylize;
+use anyhow::{anyhow, Result};
+use gh_auth::{GitHubApp, InstallationTokens, TokenScope};
+use gh_lib::token::AuthToken;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+pub(crate) const APP_ID: &str = "GH_APP_ID";
+pub(crate) const APP_PRIVATE_KEY: &str = "GH_APP_PRIVATE_KEY";
+pub(crate) const APP_INSTALLATION_ID: &str = "GH_APP_INSTALLATION_ID";
+
+static INSTALLATION: Lazy<AsyncOnce<Option<AuthToken>>> = Lazy::new(AsyncOnce::new);
+
+/// The app `app_id` with the private key in the PEM file `private_key`.
+pub(crate) async fn load_app(app_id: u64, private_key: &str) -> Result<GitHubApp> {
+    let pem = tokio::fs::read_to_string(private_key)
+        .await
+        .map_err(|err| anyhow!("Unable to read the private key {:?}, {}", private_key, err))?;
+    let app = GitHubApp::from_pem(app_id, &pem)?;
+    Ok(app.with_base_url(&accounts::api_url(&crate::TARGET_HOST)))
+}
+
+/// Renewed tokens of the installation of the env, authenticated as by the first request needing
+/// them. `None` without an installation, or with a warning when unable to authenticate as it.
+pub(crate) async fn installation_token() -> Option<AuthToken> {
+    let installation = INSTALLATION.get_or_try_init(|| async {
+        Ok(from_env().await.unwrap_or_else(|err| {
+            eprintln!(
+                "{} {}",
+                "warning: ".bold().yellow(),
+                format!(
+                    "unable to authenticate as the GitHub App installation, {}",
+                    err
+                )
+                .yellow()
+            );
+            None
+        }))
+    });
+    installation.await.ok().cloned().flatten()
+}
+
+/// Tokens of the installation of the env, if any, checked by getting the first one.
+async fn from_env() -> Result<Option<AuthToken>> {
+    let var = |key| std::env::var(key).ok().filter(|value| !value.is_empty());
+    let (app_id, private_key, installation_id) =
+        match (var(APP_ID), var(APP_PRIVATE_KEY), var(APP_INSTALLATION_ID)) {
+            (Some(app_id), Some(private_key), Some(installation_id)) => {
+                (app_id, private_key, installation_id)
+            }
+            _ => return Ok(None),
+        };
+    let parse_id = |key, value: String| {
+        value
+            .parse::<u64>()
+            .map_err(|_| anyhow!("${} is not a number: {}", key, value))
+    };
+    let app = load_app(parse_id(APP_ID, app_id)?, &private_key).await?;
+    let tokens = InstallationTokens::new(
+        Arc::new(app),
+        parse_id(APP_INSTALLATION_ID, installation_id)?,
+        TokenScope::default(),
+    );
+    tokens.get().await?;
+    Ok(Some(AuthToken::from_provider(tokens)))
+}
@@ -1,52 +1,483 @@
 #![cfg(feature = "config")]
-use anyhow::Result;
+//! `config.toml` lives in `$GH_CLI_CONFIG_DIR`, else `$XDG_CONFIG_HOME/gh-cli`, else
+//! `~/.config/gh-cli`. Files written by older versions, without a `version`, are migrated to the
+//! current schema when read.
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tokio::fs::{self, File};
 use tokio::io::AsyncWriteExt;
 use tokio::task;
 
-#[derive(Serialize, Deserialize)]
+/// Version of the schema written by this version of gh-cli.
+pub(crate) const CONFIG_VERSION: u32 = 1;
+
+/// Directory of the config and credentials files, overriding the XDG one.
+pub(crate) const CONFIG_DIR: &str = "GH_CLI_CONFIG_DIR";
+
+static CURRENT: OnceCell<Config> = OnceCell::new();
+
+/// Setting known to `gh-cli config`.
+pub(crate) struct Setting {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub default: Option<&'static str>,
+    /// Accepted values, any when empty.
+    pub values: &'static [&'static str],
+}
+
+pub(crate) const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "output",
+        description: "Output format of the commands that can print JSON, eg. `actions stats`",
+        default: Some("table"),
+        values: &["table", "json"],
+    },
+    Setting {
+        key: "pager",
+        description: "Command paging the outputs longer than the terminal, else $PAGER",
+        default: None,
+        values: &[],
+    },
+    Setting {
+        key: "editor",
+        description: "Editor of `gh-cli config edit`, else $VISUAL or $EDITOR",
+        default: None,
+        values: &[],
+    },
+    Setting {
+        key: "color",
+        description:
+            "Whether to color the output, `auto` when it is a terminal and $NO_COLOR is unset",
+        default: Some("auto"),
+        values: &["auto", "always", "never"],
+    },
+    Setting {
+        key: "prompt",
+        description: "Whether to ask for confirmation, eg. before opening the browser",
+        default: Some("enabled"),
+        values: &["enabled", "disabled"],
+    },
+    Setting {
+        key: "remote",
+        description: "Git remote of the repository the commands default to",
+        default: Some("origin"),
+        values: &[],
+    },
+    Setting {
+        key: "credential_helper",
+        description: "Command handing out the tokens, like a git credential helper",
+        default: None,
+        values: &[],
+    },
+];
+
+/// Settings of each host, as `hosts.<host>.<key>`.
+pub(crate) const HOST_SETTINGS: &[Setting] = &[
+    Setting {
+        key: "api_url",
+        description: "API root of the host, eg. https://github.example.com/api/v3",
+        default: None,
+        values: &[],
+    },
+    Setting {
+        key: "user",
+        description: "Login of the active account, see `gh-cli auth switch`",
+        default: None,
+        values: &[],
+    },
+];
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
 pub(crate) struct Config {
-    pub user_name: String,
-    pub access_token: String,
+    /// Version of the schema, files of older versions have none.
+    #[serde(default)]
+    pub version: u32,
+    /// Only found in config files of older versions, tokens now live in the credentials file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    /// Command handing out the tokens, tried after `--auth_token` but before `$GH_ACCESS_TOKEN`
+    /// and the credentials file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_helper: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pager: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hosts: BTreeMap<String, HostConfig>,
+    /// Shortcuts of the command line, see `gh-cli alias`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// Accounts to use instead of the active one, the first matching rule wins.
+    #[serde(default, rename = "rule", skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<AccountRule>,
+    /// Placeholder of the first config files, unused.
+    #[serde(default, skip_serializing)]
+    user_name: Option<String>,
+    /// Login of the active account per host before version 1, now `hosts.<host>.user`.
+    #[serde(default, skip_serializing)]
+    active: BTreeMap<String, String>,
+}
+
+pub(crate) struct ListedSetting<'a> {
+    pub key: String,
+    pub value: Option<&'a str>,
+    pub is_default: bool,
+    pub setting: &'static Setting,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub(crate) struct HostConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+impl HostConfig {
+    fn is_empty(&self) -> bool {
+        self.api_url.is_none() && self.user.is_none()
+    }
+}
+
+/// Selects the account `login` of `host` in `directory` and below, or for the repositories of
+/// `owner`, or only where both apply when both are set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct AccountRule {
+    /// Absolute path, a leading `~` stands for the home directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub host: String,
+    pub login: String,
 }
 
 impl Config {
-    pub fn new(user_name: &str, access_token: &str) -> Self {
-        Self {
-            user_name: user_name.to_owned(),
-            access_token: access_token.to_owned(),
+    /// Brings a config of an older schema to the current one.
+    fn migrate(&mut self) {
+        if self.version < 1 {
+            self.user_name = None;
+            for (host, login) in std::mem::take(&mut self.active) {
+                self.hosts.entry(host).or_default().user = Some(login);
+            }
+        }
+        self.version = CONFIG_VERSION;
+    }
+
+    /// Login of the active account of `host`.
+    pub fn active_login(&self, host: &str) -> Option<&str> {
+        self.hosts.get(host).and_then(|x| x.user.as_deref())
+    }
+
+    pub fn set_active_login(&mut self, host: &str, login: String) {
+        self.hosts.entry(host.to_owned()).or_default().user = Some(login);
+    }
+
+    pub fn remove_active_login(&mut self, host: &str) {
+        if let Some(host_config) = self.hosts.get_mut(host) {
+            host_config.user = None;
+            if host_config.is_empty() {
+                self.hosts.remove(host);
+            }
+        }
+    }
+
+    pub fn api_url(&self, host: &str) -> Option<&str> {
+        self.hosts.get(host).and_then(|x| x.api_url.as_deref())
+    }
+
+    pub fn remote(&self) -> &str {
+        self.remote.as_deref().unwrap_or("origin")
+    }
+
+    pub fn prompt_enabled(&self) -> bool {
+        self.prompt.as_deref() != Some("disabled")
+    }
+
+    pub fn json_output(&self) -> bool {
+        self.output.as_deref() == Some("json")
+    }
+
+    pub fn pager(&self) -> Option<&str> {
+        self.pager.as_deref()
+    }
+
+    pub fn color(&self) -> &str {
+        self.color.as_deref().unwrap_or("auto")
+    }
+
+    /// Value of the setting `key`, else its default.
+    pub fn get(&self, key: &str) -> Result<Option<&str>> {
+        let (host, setting) = find_setting(key)?;
+        Ok(self.value(host, setting.key).or(setting.default))
+    }
+
+    /// Changes the setting `key`, an empty `value` resetting it to its default.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let (host, setting) = find_setting(key)?;
+        let value = value.trim();
+        if !value.is_empty() && !setting.values.is_empty() && !setting.values.contains(&value) {
+            return Err(anyhow!(
+                "Invalid value `{}` for {}, one of: {}",
+                value,
+                key,
+                setting.values.join(", ")
+            ));
+        }
+        if setting.key == "api_url"
+            && !value.is_empty()
+            && !(value.starts_with("https://") || value.starts_with("http://"))
+        {
+            return Err(anyhow!("Invalid value `{}` for {}, not a URL", value, key));
+        }
+        let value = Some(value.to_owned()).filter(|value| !value.is_empty());
+        match host {
+            Some(host) => {
+                let host_config = self.hosts.entry(host.to_owned()).or_default();
+                match setting.key {
+                    "api_url" => host_config.api_url = value,
+                    _ => host_config.user = value,
+                }
+                if host_config.is_empty() {
+                    self.hosts.remove(host);
+                }
+            }
+            None => {
+                let field = match setting.key {
+                    "output" => &mut self.output,
+                    "pager" => &mut self.pager,
+                    "editor" => &mut self.editor,
+                    "color" => &mut self.color,
+                    "prompt" => &mut self.prompt,
+                    "remote" => &mut self.remote,
+                    _ => &mut self.credential_helper,
+                };
+                *field = value;
+            }
         }
+        Ok(())
     }
+
+    /// Every setting with its value, unset ones with their default, and the settings of the hosts.
+    pub fn list(&self) -> Vec<ListedSetting<'_>> {
+        let mut settings = SETTINGS
+            .iter()
+            .map(|setting| {
+                let value = self.value(None, setting.key);
+                ListedSetting {
+                    key: setting.key.to_owned(),
+                    value: value.or(setting.default),
+                    is_default: value.is_none(),
+                    setting,
+                }
+            })
+            .collect::<Vec<_>>();
+        for host in self.hosts.keys() {
+            for setting in HOST_SETTINGS {
+                if let Some(value) = self.value(Some(host), setting.key) {
+                    settings.push(ListedSetting {
+                        key: format!("hosts.{}.{}", host, setting.key),
+                        value: Some(value),
+                        is_default: false,
+                        setting,
+                    });
+                }
+            }
+        }
+        settings
+    }
+
+    fn value(&self, host: Option<&str>, key: &str) -> Option<&str> {
+        match host {
+            Some(host) => match key {
+                "api_url" => self.api_url(host),
+                _ => self.active_login(host),
+            },
+            None => match key {
+                "output" => self.output.as_deref(),
+                "pager" => self.pager.as_deref(),
+                "editor" => self.editor.as_deref(),
+                "color" => self.color.as_deref(),
+                "prompt" => self.prompt.as_deref(),
+                "remote" => self.remote.as_deref(),
+                _ => self.credential_helper.as_deref(),
+            },
+        }
+    }
+}
+
+/// The setting of `key`, with the host of the per host ones.
+fn find_setting(key: &str) -> Result<(Option<&str>, &'static Setting)> {
+    let unknown = || {
+        anyhow!(
+            "Unknown setting `{}`, run `gh-cli config list` to see them",
+            key
+        )
+    };
+    let (host, name, settings) = match key.strip_prefix("hosts.") {
+        Some(host_key) => {
+            let (host, name) = host_key.rsplit_once('.').ok_or_else(unknown)?;
+            (
+                Some(host).filter(|host| !host.is_empty()),
+                name,
+                HOST_SETTINGS,
+            )
+        }
+        None => (None, key, SETTINGS),
+    };
+    if key.starts_with("hosts.") && host.is_none() {
+        return Err(unknown());
+    }
+    let setting = settings
+        .iter()
+        .find(|setting| setting.key == name)
+        .ok_or_else(unknown)?;
+    Ok((host, setting))
+}
+
+pub(crate) fn get_config_dir() -> Option<PathBuf> {
+    let var = |key| {
+        std::env::var_os(key)
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+    };
+    var(CONFIG_DIR)
+        .or_else(|| var("XDG_CONFIG_HOME").map(|dir| dir.join("gh-cli")))
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config/gh-cli")))
 }
 
 pub(crate) fn get_config_path() -> Option<PathBuf> {
-    dirs::home_dir().map(|x| x.join(".config/gh-cli/config.toml"))
+    get_config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Keeps the config read at startup for the rest of the command.
+pub(crate) fn init(config: Config) {
+    let _ = CURRENT.set(config);
+}
+
+pub(crate) fn current() -> Option<&'static Config> {
+    CURRENT.get()
 }
 
 pub(crate) async fn get_config() -> Option<Config> {
-    let path = get_config_path()?;
-    if fs::metadata(&path).await.is_ok() {
-        let content = fs::read(path).await.ok()?;
-        task::spawn_blocking(move || toml::from_slice::<Config>(content.as_slice()).ok())
-            .await
-            .ok()
-            .flatten()
-    } else {
-        None
+    load_config().await.ok().flatten()
+}
+
+/// Reads the config file, if any, migrating it when written by an older version.
+pub(crate) async fn load_config() -> Result<Option<Config>> {
+    let path = get_config_path().ok_or_else(|| anyhow!("Couldn't establish a config path!"))?;
+    if fs::metadata(&path).await.is_err() {
+        return Ok(None);
     }
+    let content = fs::read(&path).await?;
+    let mut config = task::spawn_blocking(move || toml::from_slice::<Config>(content.as_slice()))
+        .await?
+        .map_err(|err| anyhow!("Invalid config file {:?}, {}", path, err))?;
+    if config.version > CONFIG_VERSION {
+        return Err(anyhow!(
+            "The config file {:?} is of version {}, upgrade gh-cli to use it",
+            path,
+            config.version
+        ));
+    }
+    if config.version < CONFIG_VERSION {
+        config.migrate();
+        match save_config(config.clone()).await {
+            Ok(path) => eprintln!(
+                "# Config file {:?} migrated to version {}",
+                path, CONFIG_VERSION
+            ),
+            Err(err) => eprintln!("# Unable to save the migrated config file, {}", err),
+        }
+    }
+    Ok(Some(config))
 }
 
 pub(crate) async fn save_config(config: Config) -> Result<PathBuf> {
-    let err_fn = || anyhow::anyhow!("Couldn't establish a config path!");
+    let err_fn = || anyhow!("Couldn't establish a config path!");
     let path = get_config_path().ok_or_else(err_fn)?;
     if fs::metadata(&path).await.is_err() {
         let parent = path.parent().ok_or_else(err_fn)?;
         fs::create_dir_all(parent).await?;
     }
+    let config = Config {
+        version: CONFIG_VERSION,
+        ..config
+    };
     let toml = task::spawn_blocking(move || toml::to_string(&config)).await??;
     let mut file = File::create(&path).await?;
     file.write_all(toml.as_bytes()).await?;
     Ok(path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_flat_config() -> Result<()> {
+        let mut config = toml::from_str::<Config>(
+            r#"
+user_name = "_"
+access_token = "gho_legacy"
+
+[active]
+"github.com" = "octocat"
+"#,
+        )?;
+        assert_eq!(config.version, 0);
+        config.migrate();
+
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.active_login("github.com"), Some("octocat"));
+        assert_eq!(
+            toml::to_string(&config)?,
+            r#"version = 1
+access_token = "gho_legacy"
+[hosts."github.com"]
+user = "octocat"
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_and_set() -> Result<()> {
+        let mut config = Config::default();
+        assert_eq!(config.get("color")?, Some("auto"));
+        assert_eq!(config.get("pager")?, None);
+
+        config.set("color", "never")?;
+        config.set(
+            "hosts.ghe.example.com.api_url",
+            "https://ghe.example.com/api/v3",
+        )?;
+        assert_eq!(config.get("color")?, Some("never"));
+        assert_eq!(
+            config.api_url("ghe.example.com"),
+            Some("https://ghe.example.com/api/v3")
+        );
+        assert!(config.set("color", "sometimes").is_err());
+        assert!(config.set("hosts.ghe.example.com.api_url", "ghe").is_err());
+        assert!(config.set("colour", "never").is_err());
+        assert!(config.get("hosts..user").is_err());
+
+        config.set("color", "")?;
+        config.set("hosts.ghe.example.com.api_url", "")?;
+        assert_eq!(config.get("color")?, Some("auto"));
+        assert!(config.hosts.is_empty());
+        Ok(())
+    }
+}
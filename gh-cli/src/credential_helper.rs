@@ -0,0 +1,189 @@
+#![cfg(feature = "config")]
+//! Tokens handed out by the `credential_helper` command of the config file, run like the custom
+//! git credential helpers: with the action (`get`, `store` or `erase`) appended, reading and
+//! writing `key=value` lines, the token being the `password`.
+//! https://git-scm.com/docs/gitcredentials#_custom_helpers
+use crate::config;
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Mutex;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Answers of `get` per host, the helper is asked at most once per process.
+static CACHE: Lazy<Mutex<HashMap<String, Option<Credential>>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Credential {
+    pub host: String,
+    pub username: Option<String>,
+    pub token: String,
+}
+
+pub(crate) struct CredentialHelper {
+    command: String,
+}
+
+impl CredentialHelper {
+    pub fn new(command: String) -> Self {
+        CredentialHelper { command }
+    }
+
+    /// The helper of the config file, if any.
+    pub async fn from_config() -> Option<Self> {
+        config::get_config()
+            .await?
+            .credential_helper
+            .filter(|command| !command.trim().is_empty())
+            .map(Self::new)
+    }
+
+    /// Credential of `host`, `None` when the helper has none.
+    pub async fn get(&self, host: &str) -> Result<Option<Credential>> {
+        if let Some(cached) = CACHE.lock().unwrap().get(host) {
+            return Ok(cached.to_owned());
+        }
+        let output = self.run("get", &[("host", host)]).await?;
+        let mut username = None;
+        let mut token = None;
+        for (key, value) in output.lines().filter_map(|line| line.split_once('=')) {
+            match key {
+                "username" => username = Some(value.to_owned()),
+                "password" => token = Some(value.to_owned()),
+                _ => {}
+            }
+        }
+        let credential = token
+            .filter(|token| !token.is_empty())
+            .map(|token| Credential {
+                host: host.to_owned(),
+                username,
+                token,
+            });
+        CACHE
+            .lock()
+            .unwrap()
+            .insert(host.to_owned(), credential.clone());
+        Ok(credential)
+    }
+
+    /// Hands the token to the helper, for helpers keeping them.
+    pub async fn store(&self, credential: &Credential) -> Result<()> {
+        let mut attributes = vec![("host", credential.host.as_str())];
+        if let Some(username) = &credential.username {
+            attributes.push(("username", username));
+        }
+        attributes.push(("password", &credential.token));
+        self.run("store", &attributes).await?;
+        CACHE
+            .lock()
+            .unwrap()
+            .insert(credential.host.to_owned(), Some(credential.clone()));
+        Ok(())
+    }
+
+    /// Asks the helper to forget the token of `host`.
+    #[cfg_attr(not(feature = "auth"), allow(dead_code))]
+    pub async fn erase(&self, host: &str, username: Option<&str>) -> Result<()> {
+        let mut attributes = vec![("host", host)];
+        if let Some(username) = username {
+            attributes.push(("username", username));
+        }
+        self.run("erase", &attributes).await?;
+        CACHE.lock().unwrap().remove(host);
+        Ok(())
+    }
+
+    async fn run(&self, action: &str, attributes: &[(&str, &str)]) -> Result<String> {
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        // Stderr is left to the user, eg. for the prompts of the helper.
+        let mut child = Command::new(shell)
+            .arg(flag)
+            .arg(format!("{} {}", self.command, action))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| {
+                anyhow!(
+                    "Unable to run the credential helper `{}`, {}",
+                    self.command,
+                    err
+                )
+            })?;
+
+        let mut input = String::from("protocol=https\n");
+        for (key, value) in attributes {
+            if value.contains('\n') {
+                return Err(anyhow!("Invalid {} for the credential helper", key));
+            }
+            input.push_str(&format!("{}={}\n", key, value));
+        }
+        input.push('\n');
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input.as_bytes()).await?;
+        }
+
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "The credential helper `{}` failed with {}",
+                self.command,
+                output.status
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn helper_protocol() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("gh-cli-helper-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let log = dir.join("requests.log");
+        let script = dir.join("helper.sh");
+        std::fs::write(
+            &script,
+            format!(
+                r#"echo "$1" >> {log}
+cat >> {log}
+if [ "$1" = get ]; then
+  echo username=octocat
+  echo password=ghu_short_lived
+fi
+"#,
+                log = log.display()
+            ),
+        )?;
+        let helper = CredentialHelper::new(format!("sh {}", script.display()));
+
+        let credential = helper.get("helper.example.com").await?;
+        assert_eq!(
+            credential,
+            Some(Credential {
+                host: "helper.example.com".into(),
+                username: Some("octocat".into()),
+                token: "ghu_short_lived".into(),
+            })
+        );
+        assert_eq!(helper.get("helper.example.com").await?, credential);
+        helper.erase("helper.example.com", Some("octocat")).await?;
+
+        assert_eq!(
+            std::fs::read_to_string(&log)?,
+            "get\nprotocol=https\nhost=helper.example.com\n\n\
+             erase\nprotocol=https\nhost=helper.example.com\nusername=octocat\n\n"
+        );
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
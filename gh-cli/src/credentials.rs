@@ -0,0 +1,410 @@
+#![cfg(feature = "config")]
+//! Access tokens are kept apart from the settings in `config.toml`, in a credentials file only
+//! the current user can read, optionally encrypted with a key derived from a passphrase.
+use crate::config;
+use crate::output::Stylize;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::task;
+
+/// Passphrase of encrypted credentials, asked for on the terminal when unset.
+#[cfg(feature = "encryption")]
+const PASSPHRASE_ENV: &str = "GH_CLI_PASSPHRASE";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct Credentials {
+    #[serde(default, rename = "identity")]
+    pub identities: Vec<Identity>,
+}
+
+/// Account authenticated on a host, keyed by host and login.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Identity {
+    pub host: String,
+    /// Empty until known for tokens stored by older versions.
+    pub login: String,
+    pub access_token: String,
+}
+
+impl Credentials {
+    pub fn find(&self, host: &str, login: &str) -> Option<&Identity> {
+        self.identities
+            .iter()
+            .find(|identity| identity.host == host && identity.login.eq_ignore_ascii_case(login))
+    }
+
+    pub fn hosted<'a>(&'a self, host: &'a str) -> impl Iterator<Item = &'a Identity> {
+        self.identities
+            .iter()
+            .filter(move |identity| identity.host == host)
+    }
+
+    /// Adds the identity, replacing the one with the same host and login.
+    pub fn upsert(&mut self, identity: Identity) {
+        match self
+            .identities
+            .iter_mut()
+            .find(|x| x.host == identity.host && x.login.eq_ignore_ascii_case(&identity.login))
+        {
+            Some(existing) => *existing = identity,
+            None => self.identities.push(identity),
+        }
+    }
+
+    pub fn remove(&mut self, host: &str, login: &str) -> Option<Identity> {
+        let idx = self
+            .identities
+            .iter()
+            .position(|x| x.host == host && x.login.eq_ignore_ascii_case(login))?;
+        Some(self.identities.remove(idx))
+    }
+}
+
+/// Backend keeping the credentials, eg. a file or an OS keyring.
+#[async_trait]
+pub(crate) trait CredentialStore {
+    /// Where the credentials are kept, for messages.
+    fn location(&self) -> String;
+    async fn load(&self) -> Result<Option<Credentials>>;
+    async fn save(&self, credentials: &Credentials) -> Result<()>;
+    /// Returns whether there were credentials to delete.
+    async fn delete(&self) -> Result<bool>;
+}
+
+pub(crate) struct FileStore {
+    path: PathBuf,
+    /// Kept once asked for, to save the credentials again with it.
+    #[cfg(feature = "encryption")]
+    passphrase: std::sync::Mutex<Option<String>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum CredentialsFile {
+    Encrypted { encrypted: Encrypted },
+    Plain(PlainCredentials),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum PlainCredentials {
+    /// Single token of the default host written by older versions.
+    Single {
+        access_token: String,
+    },
+    Identities(Credentials),
+}
+
+impl From<PlainCredentials> for Credentials {
+    fn from(plain: PlainCredentials) -> Self {
+        match plain {
+            PlainCredentials::Single { access_token } => Credentials {
+                identities: vec![Identity {
+                    host: gh_auth::OAUTH_HOST.to_owned(),
+                    login: String::new(),
+                    access_token,
+                }],
+            },
+            PlainCredentials::Identities(credentials) => credentials,
+        }
+    }
+}
+
+/// XChaCha20-Poly1305 ciphertext of the TOML credentials, keyed with Argon2id, all base64.
+#[derive(Serialize, Deserialize)]
+struct Encrypted {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+pub(crate) fn get_credentials_path() -> Option<PathBuf> {
+    config::get_config_path().map(|x| x.with_file_name("credentials.toml"))
+}
+
+impl FileStore {
+    /// Store at the default path, encrypting with `$GH_CLI_PASSPHRASE` when set.
+    pub fn open() -> Result<Self> {
+        let path = get_credentials_path()
+            .ok_or_else(|| anyhow!("Couldn't establish a credentials path!"))?;
+        Ok(Self {
+            path,
+            #[cfg(feature = "encryption")]
+            passphrase: std::sync::Mutex::new(std::env::var(PASSPHRASE_ENV).ok()),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn at(path: PathBuf) -> Self {
+        Self {
+            path,
+            #[cfg(feature = "encryption")]
+            passphrase: Default::default(),
+        }
+    }
+
+    #[cfg(feature = "encryption")]
+    pub fn set_passphrase(&mut self, passphrase: String) {
+        *self.passphrase.get_mut().unwrap() = Some(passphrase);
+    }
+
+    #[cfg(feature = "encryption")]
+    fn passphrase(&self) -> Result<String> {
+        let mut passphrase = self.passphrase.lock().unwrap();
+        if passphrase.is_none() {
+            *passphrase = Some(rpassword::read_password_from_tty(Some(&format!(
+                "Passphrase for {}: ",
+                self.path.display()
+            )))?);
+        }
+        Ok(passphrase.to_owned().unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl CredentialStore for FileStore {
+    fn location(&self) -> String {
+        format!("{}", self.path.display())
+    }
+
+    async fn load(&self) -> Result<Option<Credentials>> {
+        if fs::metadata(&self.path).await.is_err() {
+            return Ok(None);
+        }
+        warn_if_exposed(&self.path).await;
+        let content = fs::read(&self.path).await?;
+        let file =
+            task::spawn_blocking(move || toml::from_slice::<CredentialsFile>(&content)).await??;
+        match file {
+            CredentialsFile::Plain(credentials) => Ok(Some(credentials.into())),
+            #[cfg(feature = "encryption")]
+            CredentialsFile::Encrypted { encrypted } => {
+                let passphrase = self.passphrase()?;
+                let plaintext =
+                    task::spawn_blocking(move || decrypt(&passphrase, &encrypted)).await??;
+                let credentials = toml::from_slice::<PlainCredentials>(&plaintext)?;
+                Ok(Some(credentials.into()))
+            }
+            #[cfg(not(feature = "encryption"))]
+            CredentialsFile::Encrypted { .. } => Err(anyhow!(
+                "{} is encrypted, but gh-cli was built without the `encryption` feature",
+                self.location()
+            )),
+        }
+    }
+
+    async fn save(&self, credentials: &Credentials) -> Result<()> {
+        let credentials = credentials.clone();
+        #[cfg(feature = "encryption")]
+        let passphrase = self.passphrase.lock().unwrap().to_owned();
+        #[cfg(feature = "encryption")]
+        let file = match passphrase {
+            Some(passphrase) => {
+                task::spawn_blocking(move || -> Result<_> {
+                    let plaintext = toml::to_vec(&PlainCredentials::Identities(credentials))?;
+                    Ok(CredentialsFile::Encrypted {
+                        encrypted: encrypt(&passphrase, &plaintext)?,
+                    })
+                })
+                .await??
+            }
+            None => CredentialsFile::Plain(PlainCredentials::Identities(credentials)),
+        };
+        #[cfg(not(feature = "encryption"))]
+        let file = CredentialsFile::Plain(PlainCredentials::Identities(credentials));
+
+        let content = toml::to_string(&file)?;
+        let path = self.path.to_owned();
+        task::spawn_blocking(move || write_private(&path, content.as_bytes())).await??;
+        Ok(())
+    }
+
+    async fn delete(&self) -> Result<bool> {
+        if fs::metadata(&self.path).await.is_err() {
+            return Ok(false);
+        }
+        fs::remove_file(&self.path).await?;
+        Ok(true)
+    }
+}
+
+/// Loads the stored identities, moving a token left in `config.toml` by older versions into the
+/// credentials file.
+pub(crate) async fn load_credentials(store: &FileStore) -> Result<Credentials> {
+    if let Some(credentials) = store.load().await? {
+        return Ok(credentials);
+    }
+    let mut config = match config::get_config().await {
+        Some(config) => config,
+        None => return Ok(Credentials::default()),
+    };
+    match config.access_token.take() {
+        Some(access_token) => {
+            let credentials = Credentials::from(PlainCredentials::Single { access_token });
+            store.save(&credentials).await?;
+            config::save_config(config).await?;
+            eprintln!(
+                "# Access token moved from the config file to {}",
+                store.location()
+            );
+            Ok(credentials)
+        }
+        None => Ok(Credentials::default()),
+    }
+}
+
+/// Asks for a new passphrase twice, unless `$GH_CLI_PASSPHRASE` is set.
+#[cfg(feature = "encryption")]
+pub(crate) fn read_new_passphrase() -> Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    let passphrase = rpassword::read_password_from_tty(Some("New passphrase: "))?;
+    if passphrase.is_empty() {
+        return Err(anyhow!("The passphrase can't be empty"));
+    }
+    if rpassword::read_password_from_tty(Some("Repeat passphrase: "))? != passphrase {
+        return Err(anyhow!("The passphrases don't match"));
+    }
+    Ok(passphrase)
+}
+
+/// Writes the file readable and writable by the current user only.
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    use std::io::Write;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // The mode only applies to new files
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(content)?;
+    Ok(())
+}
+
+/// Warns when group or other users have access to a file holding secrets.
+async fn warn_if_exposed(path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = fs::metadata(path).await {
+            if metadata.permissions().mode() & 0o077 != 0 {
+                eprint!(
+                    "{} {}\n{}\n",
+                    "warning: ".bold().yellow(),
+                    format!("{} is accessible by other users!", path.display()).yellow(),
+                    format!("run `chmod 600 {}` to restrict it.", path.display()).dark_yellow()
+                );
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(feature = "encryption")]
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<chacha20poly1305::Key> {
+    let mut key = chacha20poly1305::Key::default();
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| anyhow!("Couldn't derive the encryption key: {}", err))?;
+    Ok(key)
+}
+
+#[cfg(feature = "encryption")]
+fn encrypt(passphrase: &str, plaintext: &[u8]) -> Result<Encrypted> {
+    use chacha20poly1305::aead::{Aead, NewAead};
+    use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+    let salt = rand::random::<[u8; 16]>();
+    let nonce = rand::random::<[u8; 24]>();
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), plaintext)
+        .map_err(|_| anyhow!("Couldn't encrypt the credentials"))?;
+    Ok(Encrypted {
+        salt: base64::encode(salt),
+        nonce: base64::encode(nonce),
+        ciphertext: base64::encode(ciphertext),
+    })
+}
+
+#[cfg(feature = "encryption")]
+fn decrypt(passphrase: &str, encrypted: &Encrypted) -> Result<Vec<u8>> {
+    use chacha20poly1305::aead::{Aead, NewAead};
+    use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+    let nonce = base64::decode(&encrypted.nonce)?;
+    if nonce.len() != 24 {
+        return Err(anyhow!("Invalid nonce in the encrypted credentials"));
+    }
+    let cipher =
+        XChaCha20Poly1305::new(&derive_key(passphrase, &base64::decode(&encrypted.salt)?)?);
+    cipher
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            base64::decode(&encrypted.ciphertext)?.as_slice(),
+        )
+        .map_err(|_| anyhow!("Wrong passphrase or corrupted credentials"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encryption_round_trip() -> Result<()> {
+        let encrypted = encrypt("correct horse", b"access_token = \"gho_secret\"")?;
+
+        assert!(!encrypted.ciphertext.contains("gho_secret"));
+        assert_eq!(
+            decrypt("correct horse", &encrypted)?,
+            b"access_token = \"gho_secret\""
+        );
+        assert!(decrypt("battery staple", &encrypted).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn private_credentials_file() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("gh-cli-credentials-{}", std::process::id()));
+        let store = FileStore::at(dir.join("credentials.toml"));
+        let mut credentials = Credentials::default();
+        credentials.upsert(Identity {
+            host: "github.com".into(),
+            login: "octocat".into(),
+            access_token: "gho_secret".into(),
+        });
+
+        assert_eq!(store.load().await?, None);
+        store.save(&credentials).await?;
+        assert_eq!(store.load().await?, Some(credentials));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&store.path)?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::write(&store.path, "access_token = \"gho_legacy\"\n")?;
+        let legacy = store.load().await?.unwrap_or_default();
+        assert_eq!(
+            legacy
+                .find("github.com", "")
+                .map(|x| x.access_token.as_str()),
+            Some("gho_legacy")
+        );
+        assert!(store.delete().await?);
+        assert!(!store.delete().await?);
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,319 @@
+#![cfg(feature = "extensions")]
+//! A command that is not builtin runs the executable `gh-cli-<command>` instead, either of an
+//! extension installed in `~/.local/share/gh-cli/extensions`, or found on the `PATH`. Installed
+//! extensions are git clones, with the executable at the root of their repository.
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+pub(crate) const PREFIX: &str = "gh-cli-";
+
+/// Variables of the environment of the extensions.
+pub(crate) const REPO: &str = "GH_CLI_REPO";
+pub(crate) const HOST: &str = "GH_CLI_HOST";
+pub(crate) const TOKEN: &str = "GH_CLI_TOKEN";
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Extension {
+    /// Command of the extension, without the prefix.
+    pub name: String,
+    pub executable: PathBuf,
+    /// Whether it is a clone of the extensions directory, rather than found on the `PATH`.
+    pub installed: bool,
+}
+
+pub(crate) struct Extensions {
+    dir: PathBuf,
+    search_path: Vec<PathBuf>,
+}
+
+impl Extensions {
+    pub fn new(dir: PathBuf, search_path: Vec<PathBuf>) -> Self {
+        Extensions { dir, search_path }
+    }
+
+    /// Extensions of `$XDG_DATA_HOME/gh-cli/extensions`, else `~/.local/share/gh-cli/extensions`,
+    /// and of the `PATH`.
+    pub fn from_env() -> Result<Self> {
+        let data_dir = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")))
+            .ok_or_else(|| anyhow!("Couldn't establish the extensions directory!"))?;
+        let search_path = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect())
+            .unwrap_or_default();
+        Ok(Self::new(data_dir.join("gh-cli/extensions"), search_path))
+    }
+
+    /// The extension of the command `name`, installed ones first.
+    pub fn find(&self, name: &str) -> Option<Extension> {
+        let file_name = executable_name(name);
+        let installed = self
+            .dir
+            .join(format!("{}{}", PREFIX, name))
+            .join(&file_name);
+        if is_executable(&installed) {
+            return Some(Extension {
+                name: name.to_owned(),
+                executable: installed,
+                installed: true,
+            });
+        }
+        self.search_path
+            .iter()
+            .map(|dir| dir.join(&file_name))
+            .find(|path| is_executable(path))
+            .map(|executable| Extension {
+                name: name.to_owned(),
+                executable,
+                installed: false,
+            })
+    }
+
+    /// Every extension, by name, the ones on the `PATH` hidden by installed ones being skipped.
+    pub fn list(&self) -> Vec<Extension> {
+        let mut names = std::iter::once(&self.dir)
+            .chain(&self.search_path)
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+            .filter_map(|entry| {
+                let file_name = entry.file_name().into_string().ok()?;
+                let name = file_name.strip_prefix(PREFIX)?;
+                let name = name
+                    .strip_suffix(std::env::consts::EXE_SUFFIX)
+                    .unwrap_or(name);
+                Some(name.to_owned()).filter(|name| !name.is_empty())
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names.iter().filter_map(|name| self.find(name)).collect()
+    }
+
+    /// Clones the repository `url`, named `gh-cli-<command>`, into the extensions directory.
+    pub async fn install(&self, url: &str, builtins: &[String]) -> Result<Extension> {
+        let repo_name = url
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .rsplit(['/', ':', '\\'])
+            .next()
+            .unwrap_or_default();
+        let name = repo_name
+            .strip_prefix(PREFIX)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| {
+                anyhow!(
+                    "The repository of an extension must be named {}<command>, not {}",
+                    PREFIX,
+                    repo_name
+                )
+            })?;
+        if builtins.iter().any(|builtin| builtin == name) {
+            return Err(anyhow!(
+                "`{}` is a builtin command, it can't be an extension",
+                name
+            ));
+        }
+        let target = self.dir.join(repo_name);
+        if target.exists() {
+            return Err(anyhow!(
+                "The extension {} is already installed, run `gh-cli extension upgrade {}`",
+                name,
+                name
+            ));
+        }
+        tokio::fs::create_dir_all(&self.dir).await?;
+        git(Command::new("git")
+            .args(["clone", "--quiet", "--", url])
+            .arg(&target))
+        .await?;
+        match self.find(name).filter(|extension| extension.installed) {
+            Some(extension) => Ok(extension),
+            None => {
+                tokio::fs::remove_dir_all(&target).await?;
+                Err(anyhow!(
+                    "The repository has no executable {} at its root",
+                    executable_name(name)
+                ))
+            }
+        }
+    }
+
+    /// Deletes the clone of the installed extension `name`.
+    pub async fn remove(&self, name: &str) -> Result<()> {
+        let target = self.installed_dir(name)?;
+        tokio::fs::remove_dir_all(&target).await?;
+        Ok(())
+    }
+
+    /// Pulls the latest commits of the installed extension `name`, returning the head commit.
+    pub async fn upgrade(&self, name: &str) -> Result<String> {
+        let target = self.installed_dir(name)?;
+        git(Command::new("git")
+            .arg("-C")
+            .arg(&target)
+            .args(["pull", "--quiet", "--ff-only"]))
+        .await?;
+        let head =
+            git(Command::new("git")
+                .arg("-C")
+                .arg(&target)
+                .args(["rev-parse", "--short", "HEAD"]))
+            .await?;
+        Ok(head.trim().to_owned())
+    }
+
+    fn installed_dir(&self, name: &str) -> Result<PathBuf> {
+        let name = name.strip_prefix(PREFIX).unwrap_or(name);
+        let target = self.dir.join(format!("{}{}", PREFIX, name));
+        if name.is_empty() || !target.join(".git").exists() {
+            return Err(anyhow!("No installed extension {}", name));
+        }
+        Ok(target)
+    }
+}
+
+/// Runs the extension with the arguments following its command, returning its exit code.
+pub(crate) async fn run(
+    extension: &Extension,
+    args: &[String],
+    env: &[(&str, &str)],
+) -> Result<i32> {
+    let status = Command::new(&extension.executable)
+        .args(args)
+        .envs(env.iter().copied())
+        .status()
+        .await
+        .map_err(|err| {
+            anyhow!(
+                "Unable to run the extension {:?}, {}",
+                extension.executable,
+                err
+            )
+        })?;
+    Ok(status.code().unwrap_or(1))
+}
+
+fn executable_name(name: &str) -> String {
+    format!("{}{}{}", PREFIX, name, std::env::consts::EXE_SUFFIX)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt as _;
+    std::fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Output of the git command, its errors when it fails.
+async fn git(command: &mut Command) -> Result<String> {
+    let output = command
+        .output()
+        .await
+        .map_err(|err| anyhow!("Unable to run git, {}", err))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git failed with {}, {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt as _;
+
+    fn commit(repo: &Path, script: &str) -> Result<()> {
+        let executable = repo.join("gh-cli-hello");
+        std::fs::write(&executable, script)?;
+        std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755))?;
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args([
+                    "-c",
+                    "user.name=octocat",
+                    "-c",
+                    "user.email=octocat@example.com",
+                ])
+                .arg("-C")
+                .arg(repo)
+                .args(args)
+                .output()?
+                .status;
+            if status.success() {
+                Ok(())
+            } else {
+                Err(anyhow!("git {:?} failed", args))
+            }
+        };
+        git(&["add", "."])?;
+        git(&["commit", "--quiet", "-m", "hello"])
+    }
+
+    #[tokio::test]
+    async fn extensions_of_git_clones_and_path() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("gh-cli-extensions-{}", std::process::id()));
+        let repo = root.join("remote/gh-cli-hello");
+        let bin = root.join("bin");
+        std::fs::create_dir_all(&repo)?;
+        std::fs::create_dir_all(&bin)?;
+        git(Command::new("git").arg("init").arg("--quiet").arg(&repo)).await?;
+        commit(&repo, "#!/bin/sh\necho \"hello $1 from $GH_CLI_REPO\"\n")?;
+        let on_path = bin.join("gh-cli-world");
+        std::fs::write(&on_path, "#!/bin/sh\n")?;
+        std::fs::set_permissions(&on_path, std::fs::Permissions::from_mode(0o755))?;
+        std::fs::write(bin.join("gh-cli-data"), "not executable")?;
+
+        let extensions = Extensions::new(root.join("extensions"), vec![bin.to_owned()]);
+        let builtins = vec!["actions".to_owned()];
+        let url = format!("{}", repo.display());
+        assert!(extensions
+            .install("/tmp/gh-cli-actions", &builtins)
+            .await
+            .is_err());
+        assert!(extensions.install("/tmp/hello", &builtins).await.is_err());
+        let hello = extensions.install(&url, &builtins).await?;
+        assert!(extensions.install(&url, &builtins).await.is_err());
+
+        assert_eq!(
+            extensions.list(),
+            vec![
+                Extension {
+                    name: "hello".into(),
+                    executable: root.join("extensions/gh-cli-hello/gh-cli-hello"),
+                    installed: true,
+                },
+                Extension {
+                    name: "world".into(),
+                    executable: on_path,
+                    installed: false,
+                },
+            ]
+        );
+        assert_eq!(extensions.find("data"), None);
+        assert_eq!(
+            run(&hello, &["there".into()], &[(REPO, "octo/hello")]).await?,
+            0
+        );
+
+        commit(&repo, "#!/bin/sh\nexit 3\n")?;
+        extensions.upgrade("hello").await?;
+        assert_eq!(run(&hello, &[], &[]).await?, 3);
+        assert!(extensions.upgrade("world").await.is_err());
+
+        extensions.remove("gh-cli-hello").await?;
+        assert_eq!(extensions.find("hello"), None);
+        std::fs::remove_dir_all(root)?;
+        Ok(())
+    }
+}
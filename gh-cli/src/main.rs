@@ -1,21 +1,62 @@
-use clap::Clap;
-use crossterm::style::{Colorize, Styler};
+use crate::output::Stylize;
+use clap::{Clap, IntoApp as _};
+#[cfg(feature = "actions-permissions")]
+use gh_lib::core::actions_permissions::{
+    ActionsPermissionsSettings, ForkPrApproval, SelectedActions, WorkflowPermissions,
+};
 #[cfg(feature = "basic-info")]
 use gh_lib::core::basic_info::{basic_info_response, BasicInfo as _};
+#[cfg(feature = "caches")]
+use gh_lib::core::caches::{
+    pull_request_number, ActionsCache, CacheFilter, CacheQueryParams, Caches as _, OrgCaches as _,
+};
 #[cfg(feature = "collaborators")]
 use gh_lib::core::collaborators::Collaborators as _;
+#[cfg(any(
+    feature = "variables",
+    feature = "runners",
+    feature = "caches",
+    feature = "billing"
+))]
+use gh_lib::core::orgs::OrgRequest;
+#[cfg(feature = "variables")]
+use gh_lib::core::repos::EnvironmentRequest;
 use gh_lib::core::repos::RepoRequest;
+#[cfg(feature = "runners")]
+use gh_lib::core::runners::{
+    Runner, RunnerGroup, RunnerGroupList, RunnerGroups as _, RunnerLabel, RunnerList,
+};
 #[cfg(feature = "secrets")]
 use gh_lib::core::secrets::{Secret, SecretListResponse, Secrets as _};
+#[cfg(feature = "secrets-ledger")]
+use gh_lib::core::secrets_ledger::{SecretStatus, SecretsLedger};
+#[cfg(feature = "variables")]
+use gh_lib::core::variables::{Variable, VariableListResponse};
+#[cfg(all(feature = "workflows", feature = "chrono"))]
+use gh_lib::core::workflow_runs::{WorkflowRun, WorkflowRunList, WorkflowRunQueryParams};
 #[cfg(feature = "workflows")]
 use gh_lib::core::{
     workflow_jobs::WorkflowJobs as _,
-    workflow_runs::WorkflowRuns as _,
+    workflow_runs::{DeploymentReviewState, WorkflowRuns as _},
     workflows::{WorkflowList, WorkflowUsage, WorkflowUsageTiming, Workflows as _},
 };
+use gh_lib::token::AuthToken;
 
+#[cfg(feature = "config")]
+mod accounts;
+#[cfg(feature = "config")]
+mod aliases;
+#[cfg(feature = "app")]
+mod app;
 #[cfg(feature = "config")]
 mod config;
+#[cfg(feature = "config")]
+mod credential_helper;
+#[cfg(feature = "config")]
+mod credentials;
+#[cfg(feature = "extensions")]
+mod extensions;
+mod output;
 
 macro_rules! printmd {
     ($($arg:tt)*) => ({
@@ -40,30 +81,81 @@ fn printmd(md: &str) {
             skin
         };
     }
-    TERM_SKIN.print_text(md);
+    output::print_long(&TERM_SKIN.term_text(md).to_string());
 }
 
-fn get_git_addr_from_repo() -> anyhow::Result<String> {
+/// Host of the requests instead of the one of the git remote, eg. of a GitHub Enterprise Server.
+#[cfg(feature = "config")]
+const GH_HOST: &str = "GH_HOST";
+
+/// Host and `owner/name` of the git remote of the current directory.
+fn get_git_remote() -> anyhow::Result<(String, String)> {
+    #[cfg(feature = "config")]
+    let remote = config::current().map_or("origin", config::Config::remote);
+    #[cfg(not(feature = "config"))]
+    let remote = "origin";
     let output = std::process::Command::new("git")
-        .args(&["config", "--get", "remote.origin.url"])
+        .args(["config", "--get", &format!("remote.{}.url", remote)])
         .output()?
         .stdout;
-    let re = regex::Regex::new(r"github\.com[:/](\S+)/(\S+)\.git").unwrap();
+    let re = regex::Regex::new(
+        r"^(?:\w+://)?(?:[^@/\s]+@)?([^@/:\s]+)(?::\d+)?[:/]([^/\s]+)/([^/\s]+)\.git$",
+    )
+    .unwrap();
     let regex_cap_err = || anyhow::anyhow!("Unable to capture github git repo!");
-    re.captures(std::str::from_utf8(&output)?)
+    re.captures(std::str::from_utf8(&output)?.trim())
         .and_then(|caps| {
             match (
                 caps.get(1).map(|c| c.as_str()),
                 caps.get(2).map(|c| c.as_str()),
+                caps.get(3).map(|c| c.as_str()),
             ) {
-                (Some(owner), Some(name)) => Some(format!("{}/{}", owner, name)),
+                (Some(host), Some(owner), Some(name)) => {
+                    Some((host.to_owned(), format!("{}/{}", owner, name)))
+                }
                 _ => None,
             }
         })
         .ok_or_else(regex_cap_err)
 }
 
+/// `$GH_HOST`, else the host of the git remote when it is in the config file, else github.com.
+#[cfg(feature = "config")]
+fn get_target_host() -> String {
+    if let Some(host) = std::env::var(GH_HOST).ok().filter(|host| !host.is_empty()) {
+        return host;
+    }
+    match &*GIT_REMOTE {
+        Ok((host, _))
+            if config::current().is_some_and(|config| config.hosts.contains_key(host)) =>
+        {
+            host.to_owned()
+        }
+        _ => accounts::DEFAULT_HOST.to_owned(),
+    }
+}
+
+#[cfg(not(feature = "config"))]
+fn get_target_host() -> String {
+    "github.com".to_owned()
+}
+
+fn get_git_addr_from_repo() -> anyhow::Result<String> {
+    match &*GIT_REMOTE {
+        Ok((host, addr)) if *host == *TARGET_HOST => Ok(addr.to_owned()),
+        Ok((host, _)) => Err(anyhow::anyhow!(
+            "The git remote is on {}, not {}",
+            host,
+            *TARGET_HOST
+        )),
+        Err(err) => Err(anyhow::anyhow!("{}", err)),
+    }
+}
+
 lazy_static::lazy_static! {
+    static ref GIT_REMOTE: anyhow::Result<(String, String)> = get_git_remote();
+    /// Host the requests go to.
+    static ref TARGET_HOST: String = get_target_host();
     static ref GIT_REPO_ADDR_FROM_REPO: anyhow::Result<String> = get_git_addr_from_repo();
     static ref REPO_ADDR: &'static str = GIT_REPO_ADDR_FROM_REPO.as_ref().map_or_else(|_| "", |addr| addr);
     static ref IS_ADDR_REQUIRED: bool = GIT_REPO_ADDR_FROM_REPO.is_err();
@@ -87,19 +179,379 @@ struct Opts {
 #[derive(Clap)]
 enum SubCommand {
     #[cfg(feature = "login")]
-    #[clap(about = "Login using GitHub OAuth (requires web browser)")]
-    Login,
+    #[clap(about = "Login using GitHub OAuth")]
+    Login(Login),
+    #[cfg(feature = "auth")]
+    #[clap(about = "Inspect or remove the stored credentials")]
+    Auth(Auth),
+    #[cfg(feature = "app")]
+    #[clap(about = "Authenticate as a GitHub App")]
+    App(App),
+    #[cfg(feature = "config")]
+    #[clap(about = "Read or change the settings of the config file")]
+    Config(Config),
+    #[cfg(feature = "config")]
+    #[clap(about = "Shortcuts for longer commands")]
+    Alias(Alias),
+    #[cfg(feature = "extensions")]
+    #[clap(about = "Manage the extensions, run as `gh-cli <command>`")]
+    Extension(Extension),
     #[cfg(any(feature = "basic-info", feature = "collaborators"))]
     #[clap(about = "Repository operations")]
     Repo(Repo),
     #[cfg(feature = "secrets")]
     #[clap(about = "Actions secrets")]
     Secrets(Secrets),
-    #[cfg(any(feature = "secrets", feature = "workflows"))]
+    #[cfg(any(
+        feature = "secrets",
+        feature = "variables",
+        feature = "runners",
+        feature = "caches",
+        feature = "actions-permissions",
+        feature = "analytics",
+        feature = "workflows"
+    ))]
     #[clap(about = "GitHub Actions operations")]
     Actions(Actions),
 }
 
+#[cfg(feature = "login")]
+#[derive(Clap)]
+struct Login {
+    #[clap(
+        long = "device",
+        about = "Authorize with a one-time code entered on another device, the default when no display is available or the build has no OAuth client secret"
+    )]
+    device: bool,
+    #[clap(
+        long = "scopes",
+        value_name = "SCOPES",
+        about = "Comma separated scopes to request, defaults to repo,read:org,workflow,gist",
+        use_delimiter = true,
+        takes_value = true
+    )]
+    scopes: Option<Vec<String>>,
+    #[clap(
+        long = "timeout",
+        value_name = "SECONDS",
+        about = "Seconds to wait for the authorization in the browser, defaults to 300",
+        takes_value = true
+    )]
+    timeout: Option<u64>,
+    #[cfg(feature = "config")]
+    #[clap(
+        long = "hostname",
+        value_name = "HOST",
+        about = "Host to log in to, eg. a GitHub Enterprise Server",
+        default_value = accounts::DEFAULT_HOST
+    )]
+    hostname: String,
+    #[cfg(feature = "config")]
+    #[clap(
+        long = "with-token",
+        about = "Read the access token from the standard input instead of authorizing gh-cli, needed for hosts other than github.com"
+    )]
+    with_token: bool,
+    #[cfg(feature = "encryption")]
+    #[clap(
+        long = "encrypt",
+        about = "Encrypt the stored access token with a passphrase, also used when $GH_CLI_PASSPHRASE is set"
+    )]
+    encrypt: bool,
+}
+
+#[cfg(feature = "auth")]
+#[derive(Clap)]
+struct Auth {
+    #[clap(subcommand)]
+    subcmd: AuthSubCommand,
+}
+
+#[cfg(feature = "auth")]
+#[derive(Clap)]
+enum AuthSubCommand {
+    #[clap(about = "Show the logged in account and check the scopes of the token")]
+    Status(AuthStatus),
+    #[clap(about = "Remove the stored credentials of an account")]
+    Logout(AuthLogout),
+    #[clap(about = "Print the access token, eg. for scripts")]
+    Token(AuthStatus),
+    #[clap(about = "Change the active account, or the account of a directory or owner")]
+    Switch(AuthSwitch),
+}
+
+#[cfg(feature = "auth")]
+#[derive(Clap)]
+struct AuthStatus {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true
+    )]
+    auth_token: Option<String>,
+}
+
+#[cfg(feature = "auth")]
+#[derive(Clap)]
+struct AuthLogout {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true
+    )]
+    auth_token: Option<String>,
+    #[clap(
+        name = "LOGIN",
+        about = "Account to log out, defaults to the active one",
+        index = 1
+    )]
+    login: Option<String>,
+    #[clap(long = "hostname", about = "Host of the account", default_value = accounts::DEFAULT_HOST)]
+    hostname: String,
+    #[clap(
+        long = "revoke",
+        about = "Also revoke the authorization of gh-cli on GitHub, invalidating all of its tokens, needs a build with $GH_OAUTH_CLIENT_SECRET"
+    )]
+    revoke: bool,
+}
+
+#[cfg(feature = "auth")]
+#[derive(Clap)]
+struct AuthSwitch {
+    #[clap(
+        name = "LOGIN",
+        about = "Account to switch to, defaults to the other one when there are two",
+        index = 1
+    )]
+    login: Option<String>,
+    #[clap(long = "hostname", about = "Host of the account", default_value = accounts::DEFAULT_HOST)]
+    hostname: String,
+    #[clap(
+        long = "owner",
+        value_name = "OWNER",
+        about = "Only use the account for the repositories of this user or organization",
+        takes_value = true
+    )]
+    owner: Option<String>,
+    #[clap(
+        long = "directory",
+        value_name = "DIRECTORY",
+        about = "Only use the account in this directory and below",
+        takes_value = true
+    )]
+    directory: Option<String>,
+}
+
+#[cfg(feature = "app")]
+#[derive(Clap)]
+struct App {
+    #[clap(
+        long = "app-id",
+        value_name = "APP_ID",
+        env = app::APP_ID,
+        about = "Id of the GitHub App, in its settings",
+        takes_value = true
+    )]
+    app_id: u64,
+    #[clap(
+        long = "private-key",
+        value_name = "PEM_FILE",
+        env = app::APP_PRIVATE_KEY,
+        about = "Private key of the GitHub App",
+        takes_value = true
+    )]
+    private_key: String,
+    #[clap(subcommand)]
+    subcmd: AppSubCommand,
+}
+
+#[cfg(feature = "app")]
+#[derive(Clap)]
+enum AppSubCommand {
+    #[clap(about = "List the accounts the app is installed on")]
+    Installations,
+    #[clap(about = "Print an installation access token, valid for an hour")]
+    Token(AppToken),
+}
+
+#[cfg(feature = "app")]
+#[derive(Clap)]
+struct AppToken {
+    #[clap(
+        long = "installation",
+        value_name = "INSTALLATION_ID",
+        env = app::APP_INSTALLATION_ID,
+        about = "Id of the installation, see `gh-cli app installations`",
+        takes_value = true
+    )]
+    installation_id: u64,
+    #[clap(
+        long = "repository",
+        value_name = "REPO_NAME",
+        about = "Only grant access to this repository of the installation, can be repeated",
+        multiple_occurrences = true,
+        takes_value = true
+    )]
+    repositories: Vec<String>,
+    #[clap(
+        long = "permission",
+        value_name = "PERMISSION=LEVEL",
+        about = "Only grant this permission, eg. contents=read, can be repeated",
+        multiple_occurrences = true,
+        takes_value = true,
+        parse(try_from_str = parse_permission)
+    )]
+    permissions: Vec<(String, String)>,
+}
+
+#[cfg(feature = "config")]
+#[derive(Clap)]
+struct Config {
+    #[clap(subcommand)]
+    subcmd: ConfigSubCommand,
+}
+
+#[cfg(feature = "config")]
+#[derive(Clap)]
+enum ConfigSubCommand {
+    #[clap(about = "Print the value of a setting")]
+    Get(ConfigGet),
+    #[clap(about = "Change a setting, an empty value resets it to its default")]
+    Set(ConfigSet),
+    #[clap(about = "List the settings with their values")]
+    List,
+    #[clap(about = "Open the config file in the editor")]
+    Edit,
+}
+
+#[cfg(feature = "config")]
+#[derive(Clap)]
+struct ConfigGet {
+    #[clap(
+        name = "KEY",
+        about = "Setting, eg. output or hosts.github.com.api_url",
+        index = 1
+    )]
+    key: String,
+}
+
+#[cfg(feature = "config")]
+#[derive(Clap)]
+struct ConfigSet {
+    #[clap(
+        name = "KEY",
+        about = "Setting, eg. output or hosts.github.com.api_url",
+        index = 1
+    )]
+    key: String,
+    #[clap(
+        name = "VALUE",
+        about = "Value of the setting, empty to reset it to its default",
+        index = 2,
+        setting = clap::ArgSettings::AllowEmptyValues
+    )]
+    value: String,
+}
+
+#[cfg(feature = "config")]
+#[derive(Clap)]
+struct Alias {
+    #[clap(subcommand)]
+    subcmd: AliasSubCommand,
+}
+
+#[cfg(feature = "config")]
+#[derive(Clap)]
+enum AliasSubCommand {
+    #[clap(about = "Add or change an alias, `$1`, `$2`.. standing for its arguments")]
+    Set(AliasSet),
+    #[clap(about = "List the aliases")]
+    List,
+    #[clap(about = "Remove an alias")]
+    Delete(AliasDelete),
+}
+
+#[cfg(feature = "config")]
+#[derive(Clap)]
+struct AliasSet {
+    #[clap(name = "NAME", index = 1)]
+    name: String,
+    #[clap(
+        name = "EXPANSION",
+        about = "Command it stands for, eg. 'actions runs list --branch $1', or a shell command starting with !",
+        index = 2
+    )]
+    expansion: String,
+    #[clap(
+        long = "shell",
+        short = 's',
+        about = "Run the expansion with the shell, same as starting it with !"
+    )]
+    shell: bool,
+}
+
+#[cfg(feature = "config")]
+#[derive(Clap)]
+struct AliasDelete {
+    #[clap(name = "NAME", index = 1)]
+    name: String,
+}
+
+#[cfg(feature = "extensions")]
+#[derive(Clap)]
+struct Extension {
+    #[clap(subcommand)]
+    subcmd: ExtensionSubCommand,
+}
+
+#[cfg(feature = "extensions")]
+#[derive(Clap)]
+enum ExtensionSubCommand {
+    #[clap(about = "List the installed extensions and the ones on the PATH")]
+    List,
+    #[clap(about = "Install an extension from its git repository")]
+    Install(ExtensionInstall),
+    #[clap(about = "Remove an installed extension")]
+    Remove(ExtensionName),
+    #[clap(about = "Pull the latest version of an installed extension, or of all of them")]
+    Upgrade(ExtensionUpgrade),
+}
+
+#[cfg(feature = "extensions")]
+#[derive(Clap)]
+struct ExtensionInstall {
+    #[clap(
+        name = "GIT_URL",
+        about = "Repository named gh-cli-<command>, with the executable gh-cli-<command> at its root",
+        index = 1
+    )]
+    url: String,
+}
+
+#[cfg(feature = "extensions")]
+#[derive(Clap)]
+struct ExtensionName {
+    #[clap(name = "NAME", index = 1)]
+    name: String,
+}
+
+#[cfg(feature = "extensions")]
+#[derive(Clap)]
+struct ExtensionUpgrade {
+    #[clap(
+        name = "NAME",
+        about = "Upgrades all the installed extensions when omitted",
+        index = 1
+    )]
+    name: Option<String>,
+}
+
 #[cfg(any(feature = "basic-info", feature = "collaborators"))]
 #[derive(Clap)]
 #[clap(
@@ -143,12 +595,11 @@ struct RepoView {
         long = "auth_token",
         short = 't',
         value_name = "PERSONAL_ACCESS_TOKEN",
-        env = "GH_ACCESS_TOKEN",
-        hide_env_values = true,
-        about = "Generate token - https://github.com/settings/tokens",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
         display_order = 2,
         takes_value = true,
-        required = true
+        default_value = "",
+        hide_default_value = true
     )]
     auth_token: String,
     #[clap(long, short, about = "Print README")]
@@ -174,12 +625,11 @@ struct Collaborators {
         long = "auth_token",
         short = 't',
         value_name = "PERSONAL_ACCESS_TOKEN",
-        env = "GH_ACCESS_TOKEN",
-        hide_env_values = true,
-        about = "Generate token - https://github.com/settings/tokens",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
         display_order = 2,
         takes_value = true,
-        required = true
+        default_value = "",
+        hide_default_value = true
     )]
     auth_token: String,
     #[clap(subcommand)]
@@ -217,7 +667,15 @@ struct CollaboratorUserWithPermission {
     permission: String,
 }
 
-#[cfg(any(feature = "secrets", feature = "workflows"))]
+#[cfg(any(
+    feature = "secrets",
+    feature = "variables",
+    feature = "runners",
+    feature = "caches",
+    feature = "actions-permissions",
+    feature = "analytics",
+    feature = "workflows"
+))]
 #[derive(Clap)]
 #[clap(
     name = "GitHub Actions CLI",
@@ -230,7 +688,15 @@ struct Actions {
     subcmd: ActionsSubCommand,
 }
 
-#[cfg(any(feature = "secrets", feature = "workflows"))]
+#[cfg(any(
+    feature = "secrets",
+    feature = "variables",
+    feature = "runners",
+    feature = "caches",
+    feature = "actions-permissions",
+    feature = "analytics",
+    feature = "workflows"
+))]
 #[derive(Clap)]
 enum ActionsSubCommand {
     #[cfg(feature = "workflows")]
@@ -245,21 +711,41 @@ enum ActionsSubCommand {
     #[cfg(feature = "secrets")]
     #[clap(about = "Actions Secrets")]
     Secrets(Secrets),
+    #[cfg(feature = "variables")]
+    #[clap(about = "Actions Variables")]
+    Variables(Variables),
+    #[cfg(feature = "runners")]
+    #[clap(about = "Actions Self-hosted Runners")]
+    Runners(Runners),
+    #[cfg(feature = "caches")]
+    #[clap(about = "Actions Caches")]
+    Cache(Cache),
+    #[cfg(feature = "actions-permissions")]
+    #[clap(about = "Actions Permissions")]
+    Permissions(Permissions),
+    #[cfg(feature = "analytics")]
+    #[clap(about = "Duration, success rate and flakiness statistics of workflow runs")]
+    Stats(Stats),
+    #[cfg(feature = "billing")]
+    #[clap(about = "Billable Actions minutes of a repository or organization")]
+    Usage(Usage),
+    #[cfg(feature = "workflows")]
+    #[clap(about = "Latest run of every workflow on the default branch")]
+    Summary(Summary),
 }
 
 #[cfg(feature = "workflows")]
 #[derive(Clap)]
-struct Workflows {
+struct Summary {
     #[clap(
         long = "auth_token",
         short = 't',
         value_name = "PERSONAL_ACCESS_TOKEN",
-        env = "GH_ACCESS_TOKEN",
-        hide_env_values = true,
-        about = "Generate token - https://github.com/settings/tokens",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
         display_order = 2,
         takes_value = true,
-        required = true
+        default_value = "",
+        hide_default_value = true
     )]
     auth_token: String,
     #[clap(
@@ -274,6 +760,39 @@ struct Workflows {
         hide_default_value = true,
     )]
     name: String,
+    #[clap(
+        long = "branch",
+        short = 'b',
+        value_name = "BRANCH",
+        about = "Branch to summarize instead of the default branch"
+    )]
+    branch: Option<String>,
+}
+
+#[cfg(feature = "workflows")]
+#[derive(Clap)]
+struct Workflows {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true
+    )]
+    auth_token: Option<String>,
+    #[clap(
+        long = "name",
+        short = 'n',
+        value_name = "OWNER/NAME",
+        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
+        display_order = 1,
+        takes_value = true,
+        required = *IS_ADDR_REQUIRED,
+        default_value = &REPO_ADDR,
+        hide_default_value = true,
+    )]
+    name: String,
     #[clap(subcommand)]
     subcmd: WorkflowsSubCommand,
 }
@@ -284,6 +803,94 @@ enum WorkflowsSubCommand {
     List,
     Get(WorkflowId),
     Usage(WorkflowId),
+    #[clap(about = "Status badge snippets of the workflows")]
+    Badges(WorkflowsBadges),
+    #[cfg(feature = "workflow-file")]
+    #[clap(about = "Validate the workflow files of the local repository")]
+    Lint(WorkflowsLint),
+    #[cfg(feature = "workflow-file")]
+    #[clap(about = "List the jobs a local workflow file expands to")]
+    Matrix(WorkflowsMatrix),
+    #[cfg(all(feature = "workflow-file", feature = "git-refs"))]
+    #[clap(about = "Pin the actions used by the local workflow files to commit SHAs")]
+    Pin(WorkflowsPin),
+}
+
+#[cfg(all(feature = "workflow-file", feature = "git-refs"))]
+#[derive(Clap)]
+struct WorkflowsPin {
+    #[clap(
+        value_name = "FILE",
+        about = "Workflow files to pin, defaults to the files in .github/workflows"
+    )]
+    files: Vec<String>,
+    #[clap(
+        long = "check",
+        about = "Only report the actions to pin, fails if there are any"
+    )]
+    check: bool,
+    #[clap(
+        long = "update",
+        about = "Pin to the latest release instead of the current tag"
+    )]
+    update: bool,
+    #[clap(long = "all", about = "Also pin the actions maintained by GitHub")]
+    all: bool,
+}
+
+#[cfg(feature = "workflow-file")]
+#[derive(Clap)]
+struct WorkflowsMatrix {
+    #[clap(
+        value_name = "FILE",
+        about = "Workflow file, or the name of a file in .github/workflows"
+    )]
+    file: String,
+    #[clap(
+        long = "job",
+        short = 'j',
+        value_name = "JOB_ID",
+        about = "Only expand this job"
+    )]
+    job: Option<String>,
+    #[clap(
+        long = "run",
+        value_name = "RUN_ID",
+        about = "Compare the expected jobs with the jobs of a workflow run"
+    )]
+    run_id: Option<u32>,
+}
+
+#[cfg(feature = "workflow-file")]
+#[derive(Clap)]
+struct WorkflowsLint {
+    #[clap(
+        value_name = "FILE",
+        about = "Workflow files to lint, defaults to the files in .github/workflows"
+    )]
+    files: Vec<String>,
+    #[clap(
+        long = "remote",
+        about = "Match the files with the workflows of the repository on GitHub"
+    )]
+    remote: bool,
+}
+
+#[cfg(feature = "workflows")]
+#[derive(Clap)]
+struct WorkflowsBadges {
+    #[clap(long = "markdown", about = "Markdown snippets, the default")]
+    markdown: bool,
+    #[clap(long = "html", about = "HTML snippets", conflicts_with = "markdown")]
+    html: bool,
+    #[clap(
+        long = "branch",
+        short = 'b',
+        value_name = "BRANCH",
+        about = "Branch to make badges for, defaults to the default branch",
+        multiple_occurrences = true
+    )]
+    branches: Vec<String>,
 }
 
 #[cfg(feature = "workflows")]
@@ -299,12 +906,11 @@ struct WorkflowRuns {
         long = "auth_token",
         short = 't',
         value_name = "PERSONAL_ACCESS_TOKEN",
-        env = "GH_ACCESS_TOKEN",
-        hide_env_values = true,
-        about = "Generate token - https://github.com/settings/tokens",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
         display_order = 2,
         takes_value = true,
-        required = true
+        default_value = "",
+        hide_default_value = true
     )]
     auth_token: String,
     #[clap(
@@ -342,6 +948,18 @@ enum WorkflowRunsSubCommand {
     DeleteLogs(WorkflowRunId),
     #[clap(about = "Get usage of a Workflow Run for <run_id>")]
     Usage(WorkflowRunId),
+    #[clap(about = "List deployments of a Workflow Run for <run_id> waiting for review")]
+    Pending(WorkflowRunId),
+    #[clap(about = "Approve deployments of a waiting Workflow Run for <run_id>")]
+    Approve(WorkflowRunReview),
+    #[clap(about = "Reject deployments of a waiting Workflow Run for <run_id>")]
+    Reject(WorkflowRunReview),
+    #[cfg(feature = "chrono")]
+    #[clap(about = "Delete old Workflow Runs")]
+    Prune(WorkflowRunsPrune),
+    #[cfg(feature = "timeline")]
+    #[clap(about = "Draw the jobs and steps of a Workflow Run for <run_id> on a timeline")]
+    Timeline(WorkflowRunTimeline),
 }
 
 #[cfg(feature = "workflows")]
@@ -350,39 +968,122 @@ struct WorkflowRunId {
     run_id: u32,
 }
 
-#[cfg(feature = "workflows")]
+#[cfg(all(feature = "workflows", feature = "chrono"))]
 #[derive(Clap)]
-struct WorkflowJobs {
+struct WorkflowRunsPrune {
     #[clap(
-        long = "auth_token",
-        short = 't',
-        value_name = "PERSONAL_ACCESS_TOKEN",
-        env = "GH_ACCESS_TOKEN",
-        hide_env_values = true,
-        about = "Generate token - https://github.com/settings/tokens",
-        display_order = 2,
-        takes_value = true,
-        required = true
+        long = "older-than",
+        value_name = "AGE",
+        about = "Only runs created before this long ago\nEg. 12h, 90d, 2w",
+        parse(try_from_str = parse_age)
     )]
-    auth_token: String,
+    older_than: Option<std::time::Duration>,
     #[clap(
-        long = "name",
-        short = 'n',
-        value_name = "OWNER/NAME",
-        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
-        display_order = 1,
-        takes_value = true,
-        required = *IS_ADDR_REQUIRED,
-        default_value = &REPO_ADDR,
-        hide_default_value = true,
+        long = "status",
+        value_name = "STATUS",
+        about = "Only runs with this status or conclusion\nEg. completed, failure, cancelled"
     )]
-    name: String,
-    #[clap(subcommand)]
-    subcmd: WorkflowJobsSubCommand,
-}
-
-#[cfg(feature = "workflows")]
-#[derive(Clap)]
+    status: Option<String>,
+    #[clap(
+        long = "workflow",
+        short = 'w',
+        value_name = "WORKFLOW",
+        about = "Only runs of this workflow, by file name, name or id\nEg. ci.yml"
+    )]
+    workflow: Option<String>,
+    #[clap(
+        long = "branch",
+        short = 'b',
+        value_name = "BRANCH",
+        about = "Only runs of this branch"
+    )]
+    branch: Option<String>,
+    #[clap(
+        long = "keep-last",
+        value_name = "COUNT",
+        about = "Always keep the most recent runs matching the filters",
+        default_value = "0"
+    )]
+    keep_last: usize,
+    #[clap(
+        long = "concurrency",
+        value_name = "COUNT",
+        about = "Number of runs deleted at the same time",
+        default_value = "8"
+    )]
+    concurrency: usize,
+    #[clap(long = "dry-run", about = "Only print the runs that would be deleted")]
+    dry_run: bool,
+}
+
+#[cfg(feature = "workflows")]
+#[derive(Clap)]
+struct WorkflowRunReview {
+    run_id: u32,
+    #[clap(
+        long = "env",
+        short = 'e',
+        value_name = "ENVIRONMENT",
+        about = "Environment to review, defaults to all the environments you can approve",
+        multiple_occurrences = true
+    )]
+    envs: Vec<String>,
+    #[clap(
+        long = "comment",
+        short = 'c',
+        value_name = "COMMENT",
+        about = "Comment to add to the review",
+        default_value = ""
+    )]
+    comment: String,
+}
+
+#[cfg(feature = "timeline")]
+#[derive(Clap)]
+struct WorkflowRunTimeline {
+    run_id: u32,
+    #[clap(
+        long = "svg",
+        about = "Print the timeline as an SVG image",
+        conflicts_with = "html"
+    )]
+    svg: bool,
+    #[clap(long = "html", about = "Print the timeline as an HTML page")]
+    html: bool,
+}
+
+#[cfg(feature = "workflows")]
+#[derive(Clap)]
+struct WorkflowJobs {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true,
+        default_value = "",
+        hide_default_value = true
+    )]
+    auth_token: String,
+    #[clap(
+        long = "name",
+        short = 'n',
+        value_name = "OWNER/NAME",
+        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
+        display_order = 1,
+        takes_value = true,
+        required = *IS_ADDR_REQUIRED,
+        default_value = &REPO_ADDR,
+        hide_default_value = true,
+    )]
+    name: String,
+    #[clap(subcommand)]
+    subcmd: WorkflowJobsSubCommand,
+}
+
+#[cfg(feature = "workflows")]
+#[derive(Clap)]
 enum WorkflowJobsSubCommand {
     #[clap(about = "List jobs for a Workflow Run for <run_id>")]
     List(WorkflowRunId),
@@ -405,12 +1106,11 @@ struct Secrets {
         long = "auth_token",
         short = 't',
         value_name = "PERSONAL_ACCESS_TOKEN",
-        env = "GH_ACCESS_TOKEN",
-        hide_env_values = true,
-        about = "Generate token - https://github.com/settings/tokens",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
         display_order = 2,
         takes_value = true,
-        required = true
+        default_value = "",
+        hide_default_value = true
     )]
     auth_token: String,
     #[clap(
@@ -425,6 +1125,16 @@ struct Secrets {
         hide_default_value = true,
     )]
     name: String,
+    #[cfg(feature = "secrets-ledger")]
+    #[clap(
+        long = "ledger",
+        value_name = "LEDGER_FILE",
+        env = "GH_SECRETS_LEDGER",
+        about = "Local ledger file to record digests of saved secret values, required for verify",
+        display_order = 3,
+        takes_value = true
+    )]
+    ledger: Option<String>,
     #[clap(subcommand)]
     subcmd: SecretsSubCommand,
 }
@@ -447,6 +1157,9 @@ enum SecretsSubCommand {
     Edit(SecretsNameValue),
     #[clap(about = "Delete a secret")]
     Delete(SecretsName),
+    #[cfg(feature = "secrets-ledger")]
+    #[clap(about = "Verify secrets against the local ledger")]
+    Verify(SecretsVerify),
 }
 
 #[cfg(feature = "secrets")]
@@ -460,6 +1173,8 @@ impl std::fmt::Display for SecretsSubCommand {
             | SecretsSubCommand::Update(_)
             | SecretsSubCommand::Edit(_) => "save",
             SecretsSubCommand::Delete(_) => "delete",
+            #[cfg(feature = "secrets-ledger")]
+            SecretsSubCommand::Verify(_) => "verify",
         };
         write!(f, "{}", act)?;
         Ok(())
@@ -482,254 +1197,3253 @@ struct SecretsNameValue {
     value: String,
 }
 
-#[cfg(feature = "login")]
-async fn handle_login() -> anyhow::Result<()> {
-    let user_input = {
-        use std::io::{Read, Write};
-        print!("Press Enter to open github.com in your browser for auth...");
-        std::io::stdout().flush()?;
-        let mut buf = [0u8; 1];
-        std::io::stdin().read_exact(&mut buf)?;
-        buf[0] as char
-    };
-    if user_input == '\n' {
-        let access_token = gh_auth::start_auth_flow().await?;
-        #[cfg(feature = "config")]
-        {
-            let config = config::Config::new("_", &access_token);
-            if let Ok(config_path) = config::save_config(config).await {
-                eprintln!("# Access token saved to config file: {:?}", config_path);
-            } else {
-                eprintln!("# Unable to establish a config file!");
-                eprintln!("# Run the following to use the access token in subesquent requests!\n");
-                println!("export GH_ACCESS_TOKEN={}", access_token);
-            }
-        }
-        #[cfg(not(feature = "config"))]
-        {
-            eprintln!("# Run the following to use the access token in subesquent requests!\n");
-            println!("export GH_ACCESS_TOKEN={}", access_token);
-        }
-        eprintln!("");
-        let oauth_host = gh_auth::OAUTH_HOST;
-        let client_id = gh_auth::OAUTH_CLIENT_ID;
-        eprintln!(
-            "# Review or revoke access visit - https://{}/settings/connections/applications/{}",
-            oauth_host, client_id
-        );
-    } else {
-        return Err(anyhow::anyhow!("Unexpected input!"));
-    }
-    Ok(())
+#[cfg(feature = "variables")]
+#[derive(Clap)]
+struct Variables {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true,
+        default_value = "",
+        hide_default_value = true
+    )]
+    auth_token: String,
+    #[clap(
+        long = "name",
+        short = 'n',
+        value_name = "OWNER/NAME",
+        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
+        display_order = 1,
+        takes_value = true,
+        required = *IS_ADDR_REQUIRED,
+        default_value = &REPO_ADDR,
+        hide_default_value = true,
+    )]
+    name: String,
+    #[clap(
+        long = "env",
+        short = 'e',
+        value_name = "ENVIRONMENT",
+        about = "Use the variables of a repository environment",
+        display_order = 3,
+        takes_value = true
+    )]
+    env: Option<String>,
+    #[clap(
+        long = "org",
+        short = 'o',
+        value_name = "ORG",
+        about = "Use the variables of an organization",
+        display_order = 4,
+        takes_value = true,
+        conflicts_with = "env"
+    )]
+    org: Option<String>,
+    #[clap(subcommand)]
+    subcmd: VariablesSubCommand,
 }
 
-#[cfg(feature = "basic-info")]
-async fn handle_repo_view(repo: &RepoView) -> anyhow::Result<()> {
-    let RepoView {
-        name,
-        auth_token,
-        readme,
-    } = repo;
-    let repo = RepoRequest::try_from(&name, &auth_token)?;
-    let (basic_info, readme) = {
-        if *readme {
-            tokio::join!(repo.get_basic_info(), async {
-                repo.get_raw_readme().await.ok()
-            })
-        } else {
-            (repo.get_basic_info().await, None)
-        }
-    };
-    let basic_info = basic_info?;
-    if let Some(repo) = &basic_info.repository {
-        let basic_info_response::RepoBasicInfoQueryRepository {
-            name_with_owner,
-            description,
-            created_at,
-            pushed_at,
-            homepage_url,
-            is_private,
-            is_archived,
-            primary_language,
-            license_info,
-            stargazers,
-        } = repo;
-        let access_type = if *is_private { "Private" } else { "Public" };
-        let license = if let Some(license_info) = &license_info {
-            &license_info.name
-        } else {
-            "Unlicensed"
-        };
-        let stargazers = stargazers.total_count;
-        let primary_language = if let Some(primary_language) = primary_language {
-            format!(" [{}]", &primary_language.name)
-        } else {
-            "".to_owned()
-        };
-        println!(
-            "{}",
-            format!(
-                "{} [🚦 {}] [⚖️  {}] [⭐️ {}]{}",
-                name_with_owner, access_type, license, stargazers, primary_language
-            )
-            .bold()
-        );
-        if let Some(homepage_url) = homepage_url {
-            println!("{}", &homepage_url);
-        }
-        if *is_archived {
-            println!("This repo is archived");
-        }
-        if let Some(description) = description {
-            println!("{}", &description);
-        }
-        println!();
-        println!("Created at \t{}", created_at);
-        if let Some(pushed_at) = pushed_at {
-            println!("Last commit at \t{}", pushed_at);
-        }
-        if let Some(readme) = readme {
-            println!();
-            printmd("---");
-            printmd(&readme);
-            printmd("---");
-        }
-    }
-    Ok(())
+#[cfg(feature = "variables")]
+#[derive(Clap)]
+enum VariablesSubCommand {
+    #[clap(about = "List all variables")]
+    List,
+    #[clap(about = "Print a variable")]
+    Get(VariablesName),
+    #[clap(about = "Create or update a variable")]
+    Set(VariablesNameValue),
+    #[clap(about = "Delete a variable")]
+    Delete(VariablesName),
 }
 
-#[cfg(feature = "basic-info")]
-async fn handle_repo_collaborators(collab: &Collaborators) -> anyhow::Result<()> {
-    let Collaborators {
-        name,
-        auth_token,
-        subcmd,
-    } = collab;
-
-    let repo = RepoRequest::try_from(&name, &auth_token)?;
-
-    match &subcmd {
-        CollaboratorsSubCommand::List => {
-            let collaborators = repo.get_collaborators().await?;
-            println!("Collaborators: \n{:#?}", collaborators);
-        }
-        CollaboratorsSubCommand::Check(user) => {
-            let is_collaborator = repo.is_collaborator(&user.username).await?;
-            println!("Is collaborator: {}", is_collaborator);
-        }
-        CollaboratorsSubCommand::Add(user) => {
-            let add_response = repo
-                .add_collaborator(&user.username, &user.permission)
-                .await?;
-            println!("Add collaborator response: {:?}", add_response);
-        }
-        CollaboratorsSubCommand::Remove(user) => {
-            repo.remove_collaborator(&user.username).await?;
-            println!("Removed collaborator");
-        }
-        CollaboratorsSubCommand::Permission(user) => {
-            let resp = repo.get_permission(&user.username).await?;
-            println!("User permission: {}", resp.permission);
-        }
-    }
-
-    Ok(())
+#[cfg(feature = "variables")]
+#[derive(Clap)]
+struct VariablesName {
+    #[clap(name = "VARIABLE_NAME", index = 1)]
+    name: String,
 }
 
-#[cfg(feature = "workflows")]
-async fn handle_actions_workflows(workflows: &Workflows) -> anyhow::Result<()> {
-    let Workflows {
-        name,
-        auth_token,
-        subcmd,
-    } = workflows;
+#[cfg(feature = "variables")]
+#[derive(Clap)]
+struct VariablesNameValue {
+    #[clap(name = "VARIABLE_NAME", index = 1)]
+    name: String,
+    #[clap(name = "VARIABLE_VALUE", index = 2)]
+    value: String,
+}
 
-    let repo = RepoRequest::try_from(&name, &auth_token)?;
+#[cfg(feature = "billing")]
+#[derive(Clap)]
+struct Usage {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true,
+        default_value = "",
+        hide_default_value = true
+    )]
+    auth_token: String,
+    #[clap(
+        long = "name",
+        short = 'n',
+        value_name = "OWNER/NAME",
+        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
+        display_order = 1,
+        takes_value = true,
+        required = *IS_ADDR_REQUIRED,
+        default_value = &REPO_ADDR,
+        hide_default_value = true,
+    )]
+    name: String,
+    #[clap(
+        long = "org",
+        short = 'o',
+        value_name = "ORG",
+        about = "Report on every repository of an organization",
+        display_order = 3,
+        takes_value = true
+    )]
+    org: Option<String>,
+    #[clap(
+        long = "since",
+        value_name = "AGE",
+        about = "Sum the runs created within this period instead of the current billing cycle\nEg. 7d, 30d",
+        parse(try_from_str = parse_age)
+    )]
+    since: Option<std::time::Duration>,
+    #[clap(
+        long = "top",
+        value_name = "COUNT",
+        about = "Number of top consumers to print",
+        default_value = "10"
+    )]
+    top: usize,
+    #[clap(long = "csv", about = "Print every workflow as CSV")]
+    csv: bool,
+}
 
-    match &subcmd {
-        WorkflowsSubCommand::List => {
-            let WorkflowList {
-                total_count,
-                workflows,
-            } = repo.get_all_workflows().await?;
-            let workflows = workflows
+#[cfg(feature = "analytics")]
+#[derive(Clap)]
+struct Stats {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true,
+        default_value = "",
+        hide_default_value = true
+    )]
+    auth_token: String,
+    #[clap(
+        long = "name",
+        short = 'n',
+        value_name = "OWNER/NAME",
+        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
+        display_order = 1,
+        takes_value = true,
+        required = *IS_ADDR_REQUIRED,
+        default_value = &REPO_ADDR,
+        hide_default_value = true,
+    )]
+    name: String,
+    #[clap(
+        long = "workflow",
+        short = 'w',
+        value_name = "WORKFLOW",
+        about = "Only runs of this workflow, by file name, name or id\nEg. ci.yml"
+    )]
+    workflow: Option<String>,
+    #[clap(
+        long = "branch",
+        short = 'b',
+        value_name = "BRANCH",
+        about = "Only runs of this branch"
+    )]
+    branch: Option<String>,
+    #[clap(
+        long = "since",
+        value_name = "AGE",
+        about = "Only runs created within this period\nEg. 7d, 30d, 2w",
+        default_value = "30d",
+        parse(try_from_str = parse_age)
+    )]
+    since: std::time::Duration,
+    #[clap(
+        long = "limit",
+        value_name = "COUNT",
+        about = "Maximum number of runs to analyze",
+        default_value = "500"
+    )]
+    limit: usize,
+    #[clap(long = "json", about = "Print the statistics as JSON")]
+    json: bool,
+}
+
+#[cfg(feature = "actions-permissions")]
+#[derive(Clap)]
+struct Permissions {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true,
+        default_value = "",
+        hide_default_value = true
+    )]
+    auth_token: String,
+    #[clap(
+        long = "name",
+        short = 'n',
+        value_name = "OWNER/NAME",
+        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
+        display_order = 1,
+        takes_value = true,
+        required = *IS_ADDR_REQUIRED,
+        default_value = &REPO_ADDR,
+        hide_default_value = true,
+    )]
+    name: String,
+    #[clap(
+        long = "org",
+        short = 'o',
+        value_name = "ORG",
+        about = "Use the Actions permissions of an organization",
+        display_order = 3,
+        takes_value = true
+    )]
+    org: Option<String>,
+    #[clap(subcommand)]
+    subcmd: PermissionsSubCommand,
+}
+
+#[cfg(feature = "actions-permissions")]
+#[derive(Clap)]
+enum PermissionsSubCommand {
+    #[clap(about = "Print the Actions permissions")]
+    Show,
+    #[clap(about = "Update the Actions permissions, only the given settings are changed")]
+    Set(PermissionsSet),
+}
+
+#[cfg(feature = "actions-permissions")]
+#[derive(Clap)]
+struct PermissionsSet {
+    #[clap(
+        long = "enabled",
+        value_name = "BOOL",
+        about = "Enable Actions for the repository",
+        possible_values = &["true", "false"]
+    )]
+    enabled: Option<bool>,
+    #[clap(
+        long = "enabled-repositories",
+        value_name = "REPOSITORIES",
+        about = "Repositories of the organization allowed to run Actions",
+        possible_values = &["all", "none", "selected"]
+    )]
+    enabled_repositories: Option<gh_lib::core::actions_permissions::EnabledRepositories>,
+    #[clap(
+        long = "allowed-actions",
+        value_name = "ALLOWED",
+        possible_values = &["all", "local_only", "selected"]
+    )]
+    allowed_actions: Option<gh_lib::core::actions_permissions::AllowedActions>,
+    #[clap(
+        long = "github-owned-allowed",
+        value_name = "BOOL",
+        about = "With `selected` actions, allow actions created by GitHub",
+        possible_values = &["true", "false"]
+    )]
+    github_owned_allowed: Option<bool>,
+    #[clap(
+        long = "verified-allowed",
+        value_name = "BOOL",
+        about = "With `selected` actions, allow actions by verified creators",
+        possible_values = &["true", "false"]
+    )]
+    verified_allowed: Option<bool>,
+    #[clap(
+        long = "allow-pattern",
+        value_name = "PATTERN",
+        about = "With `selected` actions, replace the allowed action patterns\nEg. docker/*, octo-org/*@v2",
+        multiple_occurrences = true
+    )]
+    patterns_allowed: Vec<String>,
+    #[clap(
+        long = "default-workflow-permissions",
+        value_name = "PERMISSIONS",
+        about = "Default permissions of the GITHUB_TOKEN",
+        possible_values = &["read", "write"]
+    )]
+    default_workflow_permissions:
+        Option<gh_lib::core::actions_permissions::DefaultWorkflowPermissions>,
+    #[clap(
+        long = "can-approve-pull-requests",
+        value_name = "BOOL",
+        about = "Allow GitHub Actions to approve pull requests",
+        possible_values = &["true", "false"]
+    )]
+    can_approve_pull_requests: Option<bool>,
+    #[clap(
+        long = "fork-pr-approval",
+        value_name = "POLICY",
+        about = "Outside contributors needing approval to run workflows on fork pull requests",
+        possible_values = &[
+            "first_time_contributors_new_to_github",
+            "first_time_contributors",
+            "all_external_contributors"
+        ]
+    )]
+    fork_pr_approval: Option<gh_lib::core::actions_permissions::ForkPrApprovalPolicy>,
+}
+
+#[cfg(feature = "caches")]
+#[derive(Clap)]
+struct Cache {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true,
+        default_value = "",
+        hide_default_value = true
+    )]
+    auth_token: String,
+    #[clap(
+        long = "name",
+        short = 'n',
+        value_name = "OWNER/NAME",
+        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
+        display_order = 1,
+        takes_value = true,
+        required = *IS_ADDR_REQUIRED,
+        default_value = &REPO_ADDR,
+        hide_default_value = true,
+    )]
+    name: String,
+    #[clap(subcommand)]
+    subcmd: CacheSubCommand,
+}
+
+#[cfg(feature = "caches")]
+#[derive(Clap)]
+enum CacheSubCommand {
+    #[clap(about = "List the caches of the repository")]
+    List(CacheList),
+    #[clap(about = "Delete caches by id, key prefix or ref")]
+    Delete(CacheDelete),
+    #[clap(about = "Print the cache usage of the repository or organization")]
+    Usage(CacheUsage),
+}
+
+#[cfg(feature = "caches")]
+#[derive(Clap)]
+struct CacheList {
+    #[clap(
+        long = "key",
+        short = 'k',
+        value_name = "KEY",
+        about = "Key or key prefix"
+    )]
+    key: Option<String>,
+    #[clap(
+        long = "ref",
+        short = 'r',
+        value_name = "REF",
+        about = "Git reference, `*` matches anything\nEg. refs/heads/main, refs/pull/*"
+    )]
+    git_ref: Option<String>,
+    #[clap(
+        long = "sort",
+        value_name = "FIELD",
+        possible_values = &["created_at", "last_accessed_at", "size_in_bytes"],
+        default_value = "last_accessed_at"
+    )]
+    sort: String,
+    #[clap(
+        long = "direction",
+        value_name = "DIRECTION",
+        possible_values = &["asc", "desc"],
+        default_value = "desc"
+    )]
+    direction: String,
+    #[clap(
+        long = "min-size",
+        value_name = "SIZE",
+        about = "Only caches at least this large\nEg. 500k, 100M, 1G",
+        parse(try_from_str = parse_size)
+    )]
+    min_size: Option<u64>,
+    #[clap(
+        long = "unused-for",
+        value_name = "AGE",
+        about = "Only caches not accessed for this long\nEg. 12h, 7d, 2w",
+        parse(try_from_str = parse_age)
+    )]
+    unused_for: Option<std::time::Duration>,
+}
+
+#[cfg(feature = "caches")]
+#[derive(Clap)]
+struct CacheDelete {
+    #[clap(name = "CACHE_ID", index = 1, required_unless_present_any = &["key", "git_ref"])]
+    cache_id: Option<u32>,
+    #[clap(long = "key", short = 'k', value_name = "KEY", about = "Key prefix")]
+    key: Option<String>,
+    #[clap(
+        long = "ref",
+        short = 'r',
+        value_name = "REF",
+        about = "Git reference, `*` matches anything\nCaches of open pull requests are kept\nEg. refs/pull/*"
+    )]
+    git_ref: Option<String>,
+    #[clap(
+        long = "dry-run",
+        about = "Only print the caches that would be deleted"
+    )]
+    dry_run: bool,
+}
+
+#[cfg(feature = "caches")]
+#[derive(Clap)]
+struct CacheUsage {
+    #[clap(
+        long = "org",
+        short = 'o',
+        value_name = "ORG",
+        about = "Print the usage of an organization, per repository"
+    )]
+    org: Option<String>,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct Runners {
+    #[clap(
+        long = "auth_token",
+        short = 't',
+        value_name = "PERSONAL_ACCESS_TOKEN",
+        about = "Generate token - https://github.com/settings/tokens, else $GH_ACCESS_TOKEN",
+        display_order = 2,
+        takes_value = true,
+        default_value = "",
+        hide_default_value = true
+    )]
+    auth_token: String,
+    #[clap(
+        long = "name",
+        short = 'n',
+        value_name = "OWNER/NAME",
+        about = "Repository address including the owner and name seperated by slash\nEg. aslamplr/gh-cli",
+        display_order = 1,
+        takes_value = true,
+        required = *IS_ADDR_REQUIRED,
+        default_value = &REPO_ADDR,
+        hide_default_value = true,
+    )]
+    name: String,
+    #[clap(
+        long = "org",
+        short = 'o',
+        value_name = "ORG",
+        about = "Use the self-hosted runners of an organization",
+        display_order = 3,
+        takes_value = true
+    )]
+    org: Option<String>,
+    #[clap(subcommand)]
+    subcmd: RunnersSubCommand,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+enum RunnersSubCommand {
+    #[clap(about = "List all self-hosted runners, offline runners first")]
+    List,
+    #[clap(about = "Print a self-hosted runner")]
+    Get(RunnerId),
+    #[clap(about = "Remove a self-hosted runner")]
+    Delete(RunnerId),
+    #[clap(about = "Create a token to register a new self-hosted runner")]
+    RegistrationToken,
+    #[clap(about = "Create a token to remove a self-hosted runner")]
+    RemoveToken,
+    #[clap(about = "List the runner application downloads")]
+    Downloads,
+    #[clap(about = "Manage the labels of a self-hosted runner")]
+    Labels(RunnerLabels),
+    #[clap(about = "Manage the runner groups of an organization")]
+    Groups(RunnerGroupsCmd),
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct RunnerId {
+    #[clap(name = "RUNNER_ID", index = 1)]
+    runner_id: u32,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct RunnerLabels {
+    #[clap(subcommand)]
+    subcmd: RunnerLabelsSubCommand,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+enum RunnerLabelsSubCommand {
+    #[clap(about = "List the labels of a runner")]
+    List(RunnerId),
+    #[clap(about = "Add custom labels to a runner")]
+    Add(RunnerIdLabels),
+    #[clap(about = "Replace all custom labels of a runner")]
+    Set(RunnerIdLabels),
+    #[clap(about = "Remove a custom label from a runner")]
+    Remove(RunnerIdLabel),
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct RunnerIdLabels {
+    #[clap(name = "RUNNER_ID", index = 1)]
+    runner_id: u32,
+    #[clap(name = "LABELS", index = 2, required = true)]
+    labels: Vec<String>,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct RunnerIdLabel {
+    #[clap(name = "RUNNER_ID", index = 1)]
+    runner_id: u32,
+    #[clap(name = "LABEL", index = 2)]
+    label: String,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct RunnerGroupsCmd {
+    #[clap(subcommand)]
+    subcmd: RunnerGroupsSubCommand,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+enum RunnerGroupsSubCommand {
+    #[clap(about = "List all runner groups")]
+    List,
+    #[clap(about = "Print a runner group")]
+    Get(RunnerGroupId),
+    #[clap(about = "Create a runner group")]
+    Create(RunnerGroupCreate),
+    #[clap(about = "Delete a runner group")]
+    Delete(RunnerGroupId),
+    #[clap(about = "List the runners of a runner group")]
+    Runners(RunnerGroupId),
+    #[clap(about = "Move a runner into a runner group")]
+    Add(RunnerGroupRunner),
+    #[clap(about = "Remove a runner from a runner group")]
+    Remove(RunnerGroupRunner),
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct RunnerGroupId {
+    #[clap(name = "GROUP_ID", index = 1)]
+    group_id: u32,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct RunnerGroupCreate {
+    #[clap(name = "GROUP_NAME", index = 1)]
+    name: String,
+    #[clap(
+        long = "visibility",
+        value_name = "VISIBILITY",
+        about = "Repositories allowed to use the group",
+        possible_values = &["all", "selected", "private"],
+        default_value = "all"
+    )]
+    visibility: String,
+}
+
+#[cfg(feature = "runners")]
+#[derive(Clap)]
+struct RunnerGroupRunner {
+    #[clap(name = "GROUP_ID", index = 1)]
+    group_id: u32,
+    #[clap(name = "RUNNER_ID", index = 2)]
+    runner_id: u32,
+}
+
+#[cfg(feature = "secrets-ledger")]
+#[derive(Clap)]
+struct SecretsVerify {
+    #[clap(
+        long = "source",
+        short = 's',
+        value_name = "ENV_FILE",
+        about = "Local source of secret values with NAME=VALUE lines to check for stale secrets",
+        takes_value = true
+    )]
+    source: Option<String>,
+}
+
+/// Request for the repository `name`, with the token of the account selected for its owner.
+fn repo_request<'a>(name: &'a str, auth_token: &'a str) -> anyhow::Result<RepoRequest<'a>> {
+    let owner = name.split('/').next().unwrap_or(name);
+    RepoRequest::try_from(name, command_token(owner, auth_token)?)
+}
+
+/// Request for the organization `org`, with the token of the account selected for it.
+#[cfg(any(
+    feature = "variables",
+    feature = "runners",
+    feature = "caches",
+    feature = "billing"
+))]
+fn org_request<'a>(org: &'a str, auth_token: &'a str) -> anyhow::Result<OrgRequest<'a>> {
+    OrgRequest::try_from(org, command_token(org, auth_token)?)
+}
+
+/// Request for the environment `env_name` of the repository `name`, with the token of the account
+/// selected for its owner.
+#[cfg(feature = "variables")]
+fn environment_request<'a>(
+    name: &'a str,
+    env_name: &'a str,
+    auth_token: &'a str,
+) -> anyhow::Result<EnvironmentRequest<'a>> {
+    let owner = name.split('/').next().unwrap_or(name);
+    EnvironmentRequest::try_from(name, env_name, command_token(owner, auth_token)?)
+}
+
+/// Token of `$GH_ACCESS_TOKEN`, if set.
+fn env_token() -> Option<String> {
+    std::env::var("GH_ACCESS_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+/// The token given with `--auth_token`, else the one resolved for the repositories of `owner` by
+/// the first request, the credential helper coming before `$GH_ACCESS_TOKEN`.
+#[cfg_attr(not(feature = "config"), allow(unused_variables))]
+fn command_token(owner: &str, auth_token: &str) -> anyhow::Result<AuthToken> {
+    if auth_token.is_empty() {
+        #[cfg(feature = "config")]
+        return Ok(AuthToken::from_provider(accounts::LazyToken::new(Some(
+            owner,
+        ))));
+        #[cfg(not(feature = "config"))]
+        return env_token()
+            .map(AuthToken::from)
+            .ok_or_else(|| anyhow::anyhow!("--auth_token or GH_ACCESS_TOKEN is required"));
+    }
+    Ok(AuthToken::from(auth_token))
+}
+
+#[cfg(feature = "login")]
+async fn handle_login(login: &Login) -> anyhow::Result<()> {
+    #[cfg(feature = "config")]
+    let store = credentials::FileStore::open();
+    #[cfg(feature = "encryption")]
+    let store = match store {
+        Ok(mut store) if login.encrypt => {
+            store.set_passphrase(credentials::read_new_passphrase()?);
+            Ok(store)
+        }
+        store => store,
+    };
+    let scopes = match &login.scopes {
+        Some(scopes) => scopes.iter().map(String::as_str).collect(),
+        None => gh_auth::DEFAULT_SCOPES.to_vec(),
+    };
+    #[cfg(feature = "config")]
+    let with_token = login.with_token;
+    #[cfg(not(feature = "config"))]
+    let with_token = false;
+    #[cfg(feature = "config")]
+    if !with_token && login.hostname != accounts::DEFAULT_HOST {
+        return Err(anyhow::anyhow!(
+            "gh-cli can only be authorized on {}, pass a token of {} on the standard input with --with-token",
+            accounts::DEFAULT_HOST,
+            login.hostname
+        ));
+    }
+    let access_token = if with_token {
+        let mut access_token = String::new();
+        std::io::stdin().read_line(&mut access_token)?;
+        let access_token = access_token.trim().to_owned();
+        if access_token.is_empty() {
+            return Err(anyhow::anyhow!("No access token on the standard input"));
+        }
+        access_token
+    } else if login.device || !gh_auth::has_display() || !gh_auth::can_use_browser_flow() {
+        gh_auth::start_device_flow(&scopes).await?
+    } else {
+        #[cfg(feature = "config")]
+        let prompt = config::current().is_none_or(config::Config::prompt_enabled);
+        #[cfg(not(feature = "config"))]
+        let prompt = true;
+        if prompt {
+            let user_input = {
+                use std::io::{Read, Write};
+                print!("Press Enter to open github.com in your browser for auth...");
+                std::io::stdout().flush()?;
+                let mut buf = [0u8; 1];
+                std::io::stdin().read_exact(&mut buf)?;
+                buf[0] as char
+            };
+            if user_input != '\n' {
+                return Err(anyhow::anyhow!("Unexpected input!"));
+            }
+        }
+        let timeout = login
+            .timeout
+            .map_or(gh_auth::DEFAULT_TIMEOUT, std::time::Duration::from_secs);
+        gh_auth::start_auth_flow(&scopes, timeout).await?
+    };
+    #[cfg(feature = "config")]
+    {
+        use credentials::CredentialStore as _;
+        let saved = match &store {
+            Ok(store) => accounts::add_identity(store, &login.hostname, &access_token)
+                .await
+                .map(|identity| (identity, store.location())),
+            Err(err) => Err(anyhow::anyhow!("{}", err)),
+        };
+        match saved {
+            Ok((identity, location)) => {
+                eprintln!("# Logged in to {} as {}", identity.host, identity.login);
+                eprintln!("# Access token saved to credentials file: {:?}", location);
+                if let Some(helper) = credential_helper::CredentialHelper::from_config().await {
+                    let credential = credential_helper::Credential {
+                        host: identity.host,
+                        username: Some(identity.login),
+                        token: access_token.to_owned(),
+                    };
+                    match helper.store(&credential).await {
+                        Ok(()) => eprintln!("# Access token handed to the credential helper"),
+                        Err(err) => eprintln!("# {}", err),
+                    }
+                }
+            }
+            Err(err) => {
+                eprintln!("# Unable to save the access token, {}", err);
+                eprintln!("# Run the following to use the access token in subesquent requests!\n");
+                println!("export GH_ACCESS_TOKEN={}", access_token);
+            }
+        }
+    }
+    #[cfg(not(feature = "config"))]
+    {
+        eprintln!("# Run the following to use the access token in subesquent requests!\n");
+        println!("export GH_ACCESS_TOKEN={}", access_token);
+    }
+    if with_token {
+        return Ok(());
+    }
+    eprintln!("");
+    let oauth_host = gh_auth::OAUTH_HOST;
+    let client_id = gh_auth::OAUTH_CLIENT_ID;
+    eprintln!(
+        "# Review or revoke access visit - https://{}/settings/connections/applications/{}",
+        oauth_host, client_id
+    );
+    Ok(())
+}
+
+/// Scopes of classic tokens needed by the subcommands, checked by `auth status`.
+#[cfg(feature = "auth")]
+const REQUIRED_SCOPES: &[(&str, &[&str])] = &[
+    ("repo", &["repo"]),
+    ("secrets", &["repo"]),
+    ("actions", &["repo"]),
+    ("actions variables --org", &["admin:org"]),
+    ("actions runners --org", &["admin:org"]),
+    ("actions permissions --org", &["admin:org"]),
+    ("actions usage --org", &["admin:org"]),
+    ("actions cache usage --org", &["read:org"]),
+];
+
+/// Whether the subcommand line, eg. `actions runners --org`, is one of this build.
+#[cfg(feature = "auth")]
+fn command_exists(command: &str) -> bool {
+    let app = Opts::into_app();
+    let mut subcmd = &app;
+    for word in command.split(' ') {
+        match word.strip_prefix("--") {
+            Some(long) => {
+                return subcmd
+                    .get_arguments()
+                    .any(|arg| arg.get_long() == Some(long))
+            }
+            None => match subcmd.find_subcommand(word) {
+                Some(found) => subcmd = found,
+                None => return false,
+            },
+        }
+    }
+    true
+}
+
+/// The token given with `--auth_token`, else the one resolved for the command.
+#[cfg(feature = "auth")]
+async fn auth_token_or_resolve(auth_token: &Option<String>) -> anyhow::Result<String> {
+    use gh_lib::token::TokenProvider as _;
+
+    match auth_token {
+        Some(auth_token) => Ok(auth_token.to_owned()),
+        None => accounts::LazyToken::new(None).token().await,
+    }
+}
+
+#[cfg(feature = "auth")]
+async fn handle_auth(auth: &Auth) -> anyhow::Result<()> {
+    match &auth.subcmd {
+        AuthSubCommand::Status(status) => handle_auth_status(status).await,
+        AuthSubCommand::Logout(logout) => handle_auth_logout(logout).await,
+        AuthSubCommand::Token(AuthStatus { auth_token }) => {
+            println!("{}", auth_token_or_resolve(auth_token).await?);
+            Ok(())
+        }
+        AuthSubCommand::Switch(switch) => handle_auth_switch(switch).await,
+    }
+}
+
+#[cfg(feature = "config")]
+async fn handle_config(config: &Config) -> anyhow::Result<()> {
+    match &config.subcmd {
+        ConfigSubCommand::Get(ConfigGet { key }) => {
+            let config = config::load_config().await?.unwrap_or_default();
+            if let Some(value) = config.get(key)? {
+                println!("{}", value);
+            }
+        }
+        ConfigSubCommand::Set(ConfigSet { key, value }) => {
+            let mut config = config::load_config().await?.unwrap_or_default();
+            config.set(key, value)?;
+            let path = config::save_config(config).await?;
+            println!("{} Set {} in {:?}", "✓".green(), key, path);
+        }
+        ConfigSubCommand::List => {
+            let config = config::load_config().await?.unwrap_or_default();
+            for listed in config.list() {
+                let value = match listed.value {
+                    Some(value) if listed.is_default => format!("{} (default)", value),
+                    Some(value) => value.to_owned(),
+                    None => "(unset)".to_owned(),
+                };
+                let description = format!("# {}", listed.setting.description);
+                println!("{} = {}  {}", listed.key, value, description.dark_grey());
+            }
+        }
+        ConfigSubCommand::Edit => handle_config_edit().await?,
+    }
+    Ok(())
+}
+
+/// Names of the subcommands of gh-cli, which aliases can't shadow.
+#[cfg(feature = "config")]
+fn builtin_commands() -> Vec<String> {
+    Opts::into_app()
+        .get_subcommands()
+        .map(|subcmd| subcmd.get_name().to_owned())
+        .chain(std::iter::once("help".to_owned()))
+        .collect()
+}
+
+#[cfg(feature = "config")]
+async fn handle_alias(alias: &Alias) -> anyhow::Result<()> {
+    let mut config = config::load_config().await?.unwrap_or_default();
+    match &alias.subcmd {
+        AliasSubCommand::Set(AliasSet {
+            name,
+            expansion,
+            shell,
+        }) => {
+            let expansion = if *shell && !expansion.starts_with('!') {
+                format!("!{}", expansion)
+            } else {
+                expansion.to_owned()
+            };
+            aliases::validate(name, &expansion, &builtin_commands())?;
+            let action = match config.aliases.insert(name.to_owned(), expansion.to_owned()) {
+                Some(_) => "Changed",
+                None => "Added",
+            };
+            config::save_config(config).await?;
+            println!(
+                "{} {} alias {}: {}",
+                "✓".green(),
+                action,
+                name.to_owned().bold(),
+                expansion
+            );
+        }
+        AliasSubCommand::List => {
+            if config.aliases.is_empty() {
+                eprintln!("# No aliases, add one with `gh-cli alias set`");
+            }
+            for (name, expansion) in &config.aliases {
+                println!("{}: {}", name.to_owned().bold(), expansion);
+            }
+        }
+        AliasSubCommand::Delete(AliasDelete { name }) => {
+            let expansion = config
+                .aliases
+                .remove(name)
+                .ok_or_else(|| anyhow::anyhow!("No alias `{}`", name))?;
+            config::save_config(config).await?;
+            println!(
+                "{} Deleted alias {}: {}",
+                "✓".green(),
+                name.to_owned().bold(),
+                expansion
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Runs the extension with the repository of the current directory, its host and token.
+#[cfg(feature = "extensions")]
+async fn run_extension(extension: &extensions::Extension, args: &[String]) -> anyhow::Result<i32> {
+    let repo = *REPO_ADDR;
+    let owner = repo.split('/').next().filter(|owner| !owner.is_empty());
+    let auth_token = accounts::resolve(owner)
+        .await?
+        .map(|(auth_token, _)| auth_token)
+        .unwrap_or_default();
+    let mut env = vec![(extensions::HOST, TARGET_HOST.as_str())];
+    if !repo.is_empty() {
+        env.push((extensions::REPO, repo));
+    }
+    if !auth_token.is_empty() {
+        env.push((extensions::TOKEN, &auth_token));
+    }
+    extensions::run(extension, args, &env).await
+}
+
+#[cfg(feature = "extensions")]
+async fn handle_extension(extension: &Extension) -> anyhow::Result<()> {
+    let extensions = extensions::Extensions::from_env()?;
+    match &extension.subcmd {
+        ExtensionSubCommand::List => {
+            let found = extensions.list();
+            if found.is_empty() {
+                eprintln!("# No extensions, install one with `gh-cli extension install`");
+            }
+            for extension in found {
+                let source = if extension.installed {
+                    "installed"
+                } else {
+                    "PATH"
+                };
+                println!(
+                    "{} {:?} {}",
+                    extension.name.to_owned().bold(),
+                    extension.executable,
+                    format!("({})", source).dark_grey()
+                );
+            }
+        }
+        ExtensionSubCommand::Install(ExtensionInstall { url }) => {
+            let installed = extensions.install(url, &builtin_commands()).await?;
+            println!(
+                "{} Installed extension {}, run it with `gh-cli {}`",
+                "✓".green(),
+                installed.name.to_owned().bold(),
+                installed.name
+            );
+        }
+        ExtensionSubCommand::Remove(ExtensionName { name }) => {
+            extensions.remove(name).await?;
+            println!(
+                "{} Removed extension {}",
+                "✓".green(),
+                name.to_owned().bold()
+            );
+        }
+        ExtensionSubCommand::Upgrade(ExtensionUpgrade { name }) => {
+            let names = match name {
+                Some(name) => vec![name.to_owned()],
+                None => extensions
+                    .list()
+                    .into_iter()
+                    .filter(|extension| extension.installed)
+                    .map(|extension| extension.name)
+                    .collect(),
+            };
+            let mut failed = 0;
+            for name in &names {
+                match extensions.upgrade(name).await {
+                    Ok(head) => println!(
+                        "{} Upgraded extension {} to {}",
+                        "✓".green(),
+                        name.to_owned().bold(),
+                        head
+                    ),
+                    Err(err) => {
+                        failed += 1;
+                        println!("{} {}: {}", "✗".red(), name.to_owned().bold(), err);
+                    }
+                }
+            }
+            if failed > 0 {
+                return Err(anyhow::anyhow!("Unable to upgrade {} extension(s)", failed));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "config")]
+async fn handle_config_edit() -> anyhow::Result<()> {
+    let path = config::get_config_path()
+        .ok_or_else(|| anyhow::anyhow!("Couldn't establish a config path!"))?;
+    // An invalid file is still opened, to be fixed
+    let config = config::load_config().await.ok().flatten();
+    if config.is_none() && std::fs::metadata(&path).is_err() {
+        config::save_config(config::Config::default()).await?;
+    }
+    let var = |key| std::env::var(key).ok().filter(|value| !value.is_empty());
+    let editor = config
+        .and_then(|config| config.editor)
+        .or_else(|| var("VISUAL"))
+        .or_else(|| var("EDITOR"))
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_owned());
+    let mut command = if cfg!(windows) {
+        let mut command = tokio::process::Command::new("cmd");
+        command
+            .arg("/C")
+            .arg(format!("{} \"{}\"", editor, path.display()));
+        command
+    } else {
+        // The path as an argument of the shell, the editor may come with arguments
+        let mut command = tokio::process::Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor))
+            .arg("sh")
+            .arg(&path);
+        command
+    };
+    let status = command
+        .status()
+        .await
+        .map_err(|err| anyhow::anyhow!("Unable to run the editor `{}`, {}", editor, err))?;
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "The editor `{}` failed with {}",
+            editor,
+            status
+        ));
+    }
+    config::load_config().await?;
+    Ok(())
+}
+
+#[cfg(feature = "app")]
+async fn handle_app(app: &App) -> anyhow::Result<()> {
+    let App {
+        app_id,
+        private_key,
+        subcmd,
+    } = app;
+    let github_app = app::load_app(*app_id, private_key).await?;
+    match subcmd {
+        AppSubCommand::Installations => {
+            let installations = github_app
+                .list_installations()
+                .await?
+                .iter()
+                .map(|installation| {
+                    format!(
+                        "|{}|{}|{}|{}|",
+                        installation.id,
+                        installation.account.login,
+                        installation.account.account_type,
+                        installation.repository_selection
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            printmd!("## Installations of app {}", app_id);
+            printmd!(
+                r#"|:-:|:-:|:-:|:-:|
+|**Id**|**Account**|**Type**|**Repositories**|
+|-:|:-|:-|:-|
+{}
+|-"#,
+                installations
+            );
+        }
+        AppSubCommand::Token(AppToken {
+            installation_id,
+            repositories,
+            permissions,
+        }) => {
+            let scope = gh_auth::TokenScope {
+                repositories: repositories.to_owned(),
+                permissions: permissions.iter().cloned().collect(),
+            };
+            let token = github_app
+                .create_installation_token(*installation_id, &scope)
+                .await?;
+            eprintln!("# Expires at {}", token.expires_at.to_rfc3339());
+            println!("{}", token.token);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "app")]
+fn parse_permission(permission: &str) -> anyhow::Result<(String, String)> {
+    match permission.split_once('=') {
+        Some((name, level)) if !name.is_empty() && !level.is_empty() => {
+            Ok((name.to_owned(), level.to_owned()))
+        }
+        _ => anyhow::bail!("invalid permission `{}`, eg. contents=read", permission),
+    }
+}
+
+/// Prints the account of the token and, for the token in use, warns about missing scopes.
+#[cfg(feature = "auth")]
+async fn print_auth_status(
+    host: &str,
+    auth_token: &str,
+    source: &str,
+    in_use: bool,
+) -> anyhow::Result<gh_lib::core::users::AuthenticatedUser> {
+    use gh_lib::core::users::{UserRequest, Users as _};
+
+    let authenticated = UserRequest::try_from(auth_token)?
+        .get_authenticated_user()
+        .await?;
+    let masked_token = match auth_token.find('_') {
+        Some(idx) => format!(
+            "{}{}",
+            &auth_token[..=idx],
+            "*".repeat(auth_token.len() - idx - 1)
+        ),
+        None => "*".repeat(auth_token.len()),
+    };
+    println!(
+        "{} Logged in to {} as {}{}{}",
+        "✓".green(),
+        host,
+        authenticated.user.login.to_owned().bold(),
+        authenticated
+            .user
+            .name
+            .as_ref()
+            .map(|name| format!(" ({})", name))
+            .unwrap_or_default(),
+        if in_use { " [in use]" } else { "" }.dark_green()
+    );
+    println!(
+        "  {} {} ({})",
+        "Token:".bold(),
+        masked_token,
+        source.dark_grey()
+    );
+    match &authenticated.scopes {
+        Some(scopes) if scopes.is_empty() => {
+            println!("  {} {}", "Scopes:".bold(), "none".dark_grey())
+        }
+        Some(scopes) => println!("  {} {}", "Scopes:".bold(), scopes.join(", ")),
+        None => println!(
+            "  {} {}",
+            "Scopes:".bold(),
+            "not reported, fine-grained or GitHub App token".dark_grey()
+        ),
+    }
+    if !in_use {
+        return Ok(authenticated);
+    }
+
+    let mut missing_any = false;
+    let required_scopes = REQUIRED_SCOPES
+        .iter()
+        .filter(|(subcmd, _)| command_exists(subcmd));
+    for (subcmd, required) in required_scopes {
+        let missing = authenticated.missing_scopes(required);
+        if !missing.is_empty() {
+            missing_any = true;
+            eprintln!(
+                "{} {}",
+                "warning: ".bold().yellow(),
+                format!("`{}` needs the `{}` scope", subcmd, missing.join("`, `")).yellow()
+            );
+        }
+    }
+    if missing_any {
+        eprintln!(
+            "{}",
+            "run `gh-cli auth logout` and `gh-cli login` to authorize the missing scopes."
+                .dark_yellow()
+        );
+    }
+    Ok(authenticated)
+}
+
+#[cfg(feature = "auth")]
+async fn handle_auth_status(status: &AuthStatus) -> anyhow::Result<()> {
+    use credentials::CredentialStore as _;
+
+    let given = match &status.auth_token {
+        Some(auth_token) => Some((auth_token.to_owned(), "--auth_token")),
+        None => match accounts::resolve(None).await? {
+            Some((auth_token, accounts::TokenSource::Helper)) => {
+                Some((auth_token, "credential helper"))
+            }
+            Some((auth_token, accounts::TokenSource::Env)) => {
+                Some((auth_token, "$GH_ACCESS_TOKEN"))
+            }
+            #[cfg(feature = "app")]
+            Some((auth_token, accounts::TokenSource::App)) => {
+                Some((auth_token, "GitHub App installation"))
+            }
+            _ => None,
+        },
+    };
+    if let Some((auth_token, source)) = given {
+        print_auth_status(&TARGET_HOST, &auth_token, source, true).await?;
+        return Ok(());
+    }
+
+    let accounts = accounts::get().await?;
+    if accounts.credentials.identities.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Not logged in, run `gh-cli login` or set GH_ACCESS_TOKEN"
+        ));
+    }
+    let auth_token = accounts::stored_token(None).await?.unwrap_or_default();
+    let source = accounts.store.location();
+    let mut credentials = accounts.credentials.clone();
+    let mut config = accounts.config.clone();
+    for identity in &accounts.credentials.identities {
+        accounts::connect(&identity.host);
+        let in_use = identity.host == *TARGET_HOST && identity.access_token == auth_token;
+        match print_auth_status(&identity.host, &identity.access_token, &source, in_use).await {
+            // Tokens stored by older versions are saved without their login
+            Ok(authenticated) if authenticated.user.login != identity.login => {
+                credentials.remove(&identity.host, &identity.login);
+                credentials.upsert(credentials::Identity {
+                    login: authenticated.user.login.to_owned(),
+                    ..identity.clone()
+                });
+                if in_use {
+                    config.set_active_login(&identity.host, authenticated.user.login);
+                }
+            }
+            Ok(_) => {}
+            Err(err) => println!(
+                "{} {} as {}: {}",
+                "✗".red(),
+                identity.host,
+                identity.login.to_owned().bold(),
+                err
+            ),
+        }
+    }
+    if credentials != accounts.credentials {
+        accounts.store.save(&credentials).await?;
+        config::save_config(config).await?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "auth")]
+async fn handle_auth_logout(logout: &AuthLogout) -> anyhow::Result<()> {
+    use credentials::CredentialStore as _;
+
+    let host = &logout.hostname;
+    if logout.revoke && !gh_auth::can_revoke_grant() {
+        return Err(anyhow::anyhow!(
+            "--revoke needs a build of gh-cli with the client secret of its OAuth app, \
+             revoke it on https://{}/settings/applications instead",
+            gh_auth::OAUTH_HOST
+        ));
+    }
+    let accounts = accounts::get().await?;
+    let account = match &logout.login {
+        Some(login) => accounts.credentials.find(host, login),
+        None => accounts.active(host),
+    }
+    .map(|identity| (accounts, identity));
+    if logout.revoke {
+        if host != gh_auth::OAUTH_HOST {
+            return Err(anyhow::anyhow!(
+                "gh-cli is only authorized on {}, its tokens of {} can't be revoked",
+                gh_auth::OAUTH_HOST,
+                host
+            ));
+        }
+        let auth_token = match account {
+            Some((_, identity)) => identity.access_token.to_owned(),
+            None => auth_token_or_resolve(&logout.auth_token).await?,
+        };
+        gh_auth::revoke_grant(&auth_token).await?;
+        eprintln!(
+            "# Authorization revoked on {}, all the tokens issued to gh-cli are invalid now",
+            gh_auth::OAUTH_HOST
+        );
+    }
+    if let Some(helper) = credential_helper::CredentialHelper::from_config().await {
+        let login = logout
+            .login
+            .as_deref()
+            .or_else(|| account.map(|(_, identity)| identity.login.as_str()));
+        helper.erase(host, login).await?;
+        eprintln!(
+            "# Asked the credential helper to forget the token of {}",
+            host
+        );
+    }
+    match account {
+        Some((accounts, identity)) => {
+            let mut credentials = accounts.credentials.clone();
+            credentials.remove(&identity.host, &identity.login);
+            if credentials.identities.is_empty() {
+                accounts.store.delete().await?;
+            } else {
+                accounts.store.save(&credentials).await?;
+            }
+            let mut config = accounts.config.clone();
+            let is_identity = |login: &str| login.eq_ignore_ascii_case(&identity.login);
+            if config
+                .active_login(host)
+                .filter(|login| is_identity(login))
+                .is_some()
+            {
+                config.remove_active_login(host);
+            }
+            config
+                .rules
+                .retain(|rule| !(&rule.host == host && is_identity(&rule.login)));
+            config::save_config(config).await?;
+            eprintln!(
+                "# Logged out of {} as {}, removed from {:?}",
+                host,
+                identity.login,
+                accounts.store.location()
+            );
+        }
+        None => eprintln!("# No stored credentials to remove"),
+    }
+    if std::env::var_os("GH_ACCESS_TOKEN").is_some() {
+        eprint!(
+            "{} {}\n{}\n",
+            "warning: ".bold().yellow(),
+            "$GH_ACCESS_TOKEN is still set in the environment!".yellow(),
+            "run `unset GH_ACCESS_TOKEN` to stop using it.".dark_yellow()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "auth")]
+async fn handle_auth_switch(switch: &AuthSwitch) -> anyhow::Result<()> {
+    let accounts = accounts::get().await?;
+    let host = &switch.hostname;
+    let identity = match &switch.login {
+        Some(login) => accounts.credentials.find(host, login).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No account {} on {}, run `gh-cli login` to add it",
+                login,
+                host
+            )
+        })?,
+        None => match accounts.credentials.hosted(host).collect::<Vec<_>>()[..] {
+            [first, second] if accounts.active(host) == Some(first) => second,
+            [first, _] => first,
+            ref hosted => {
+                let logins = hosted.iter().map(|x| x.login.as_str()).collect::<Vec<_>>();
+                return Err(anyhow::anyhow!(
+                    "Specify the account to switch to, one of: {}",
+                    logins.join(", ")
+                ));
+            }
+        },
+    };
+
+    let mut config = accounts.config.clone();
+    if switch.owner.is_none() && switch.directory.is_none() {
+        config.set_active_login(host, identity.login.to_owned());
+        config::save_config(config).await?;
+        println!(
+            "{} Switched the active account of {} to {}",
+            "✓".green(),
+            host,
+            identity.login.to_owned().bold()
+        );
+        return Ok(());
+    }
+    let directory = match &switch.directory {
+        Some(directory) => Some(format!(
+            "{}",
+            std::fs::canonicalize(accounts::expand_home(directory))?.display()
+        )),
+        None => None,
+    };
+    let rule = config::AccountRule {
+        directory,
+        owner: switch.owner.to_owned(),
+        host: host.to_owned(),
+        login: identity.login.to_owned(),
+    };
+    config.rules.retain(|x| {
+        !(x.host == rule.host && x.directory == rule.directory && x.owner == rule.owner)
+    });
+    let scope = match (&rule.directory, &rule.owner) {
+        (Some(directory), Some(owner)) => format!("the repositories of {} in {}", owner, directory),
+        (Some(directory), None) => directory.to_owned(),
+        (None, Some(owner)) => format!("the repositories of {}", owner),
+        (None, None) => unreachable!(),
+    };
+    // Ahead of the existing rules so that it takes effect
+    config.rules.insert(0, rule);
+    config::save_config(config).await?;
+    println!(
+        "{} Using {} for {}",
+        "✓".green(),
+        identity.login.to_owned().bold(),
+        scope
+    );
+    Ok(())
+}
+
+#[cfg(feature = "basic-info")]
+async fn handle_repo_view(repo: &RepoView) -> anyhow::Result<()> {
+    let RepoView {
+        name,
+        auth_token,
+        readme,
+    } = repo;
+    let repo = repo_request(&name, &auth_token)?;
+    let (basic_info, readme) = {
+        if *readme {
+            tokio::join!(repo.get_basic_info(), async {
+                repo.get_raw_readme().await.ok()
+            })
+        } else {
+            (repo.get_basic_info().await, None)
+        }
+    };
+    let basic_info = basic_info?;
+    if let Some(repo) = &basic_info.repository {
+        let basic_info_response::RepoBasicInfoQueryRepository {
+            name_with_owner,
+            description,
+            created_at,
+            pushed_at,
+            homepage_url,
+            is_private,
+            is_archived,
+            primary_language,
+            license_info,
+            stargazers,
+        } = repo;
+        let access_type = if *is_private { "Private" } else { "Public" };
+        let license = if let Some(license_info) = &license_info {
+            &license_info.name
+        } else {
+            "Unlicensed"
+        };
+        let stargazers = stargazers.total_count;
+        let primary_language = if let Some(primary_language) = primary_language {
+            format!(" [{}]", &primary_language.name)
+        } else {
+            "".to_owned()
+        };
+        println!(
+            "{}",
+            format!(
+                "{} [🚦 {}] [⚖️  {}] [⭐️ {}]{}",
+                name_with_owner, access_type, license, stargazers, primary_language
+            )
+            .bold()
+        );
+        if let Some(homepage_url) = homepage_url {
+            println!("{}", &homepage_url);
+        }
+        if *is_archived {
+            println!("This repo is archived");
+        }
+        if let Some(description) = description {
+            println!("{}", &description);
+        }
+        println!();
+        println!("Created at \t{}", created_at);
+        if let Some(pushed_at) = pushed_at {
+            println!("Last commit at \t{}", pushed_at);
+        }
+        if let Some(readme) = readme {
+            println!();
+            printmd("---");
+            printmd(&readme);
+            printmd("---");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "basic-info")]
+async fn handle_repo_collaborators(collab: &Collaborators) -> anyhow::Result<()> {
+    let Collaborators {
+        name,
+        auth_token,
+        subcmd,
+    } = collab;
+
+    let repo = repo_request(&name, &auth_token)?;
+
+    match &subcmd {
+        CollaboratorsSubCommand::List => {
+            let collaborators = repo.get_collaborators().await?;
+            println!("Collaborators: \n{:#?}", collaborators);
+        }
+        CollaboratorsSubCommand::Check(user) => {
+            let is_collaborator = repo.is_collaborator(&user.username).await?;
+            println!("Is collaborator: {}", is_collaborator);
+        }
+        CollaboratorsSubCommand::Add(user) => {
+            let add_response = repo
+                .add_collaborator(&user.username, &user.permission)
+                .await?;
+            println!("Add collaborator response: {:?}", add_response);
+        }
+        CollaboratorsSubCommand::Remove(user) => {
+            repo.remove_collaborator(&user.username).await?;
+            println!("Removed collaborator");
+        }
+        CollaboratorsSubCommand::Permission(user) => {
+            let resp = repo.get_permission(&user.username).await?;
+            println!("User permission: {}", resp.permission);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "workflows")]
+async fn handle_actions_workflows(workflows: &Workflows) -> anyhow::Result<()> {
+    let Workflows {
+        name,
+        auth_token,
+        subcmd,
+    } = workflows;
+    let auth_token = auth_token.as_deref().unwrap_or_default();
+    // The workflow files are read locally, only the other subcommands need the repository.
+    let repo = || repo_request(name, auth_token);
+
+    match subcmd {
+        #[cfg(feature = "workflow-file")]
+        WorkflowsSubCommand::Lint(lint) => lint_workflow_files(name, auth_token, lint).await?,
+        #[cfg(feature = "workflow-file")]
+        WorkflowsSubCommand::Matrix(matrix) => {
+            expand_workflow_matrix(name, auth_token, matrix).await?
+        }
+        #[cfg(all(feature = "workflow-file", feature = "git-refs"))]
+        WorkflowsSubCommand::Pin(pin) => pin_workflow_actions(auth_token, pin).await?,
+        WorkflowsSubCommand::List => {
+            let WorkflowList {
+                total_count,
+                workflows,
+            } = repo()?.get_all_workflows().await?;
+            let workflows = workflows
+                .iter()
+                .map(|w| {
+                    [
+                        format!("|name|{}", w.name),
+                        format!("|id|{}", w.id),
+                        format!("|node_id|{}", w.node_id),
+                        format!("|path|{}", w.path),
+                        format!("|state|{}", w.state),
+                        format!("|created_at|{}", w.created_at),
+                        format!("|updated_at|{}", w.updated_at),
+                        format!("|url|{}", w.url),
+                        format!("|html_url|{}", w.html_url),
+                        format!("|badge_url|{}", w.badge_url),
+                    ]
+                    .join("\n")
+                })
+                .collect::<Vec<_>>()
+                .join("\n|-:|:-|\n");
+            printmd!(
+                r#"|-:|:-|
+|**Field**|**Value**|
+|-:|:-|
+{}
+|-
+
+Total workflows: {}"#,
+                workflows,
+                total_count
+            );
+        }
+        WorkflowsSubCommand::Get(WorkflowId { workflow_id }) => {
+            let workflow = repo()?.get_a_workflow(*workflow_id).await?;
+            printmd!(
+                r#"|-:|:-|
+|**Field**|**Value**|
+|-:|:-|
+{}
+|-"#,
+                [
+                    format!("|name|{}", workflow.name),
+                    format!("|id|{}", workflow.id),
+                    format!("|node_id|{}", workflow.node_id),
+                    format!("|path|{}", workflow.path),
+                    format!("|state|{}", workflow.state),
+                    format!("|created_at|{}", workflow.created_at),
+                    format!("|updated_at|{}", workflow.updated_at),
+                    format!("|url|{}", workflow.url),
+                    format!("|html_url|{}", workflow.html_url),
+                    format!("|badge_url|{}", workflow.badge_url),
+                ]
+                .join("\n")
+            );
+        }
+        WorkflowsSubCommand::Usage(WorkflowId { workflow_id }) => {
+            let WorkflowUsage { billable } = repo()?.get_workflow_usage(*workflow_id).await?;
+            let get_usage_pf = |pf: &str, timing: Option<WorkflowUsageTiming>| {
+                format!(
+                    "|{}|{}|",
+                    pf,
+                    timing
+                        .map(|t| format!("{}ms", t.total_ms))
+                        .unwrap_or_else(|| "unknown".into())
+                )
+            };
+            printmd!(
+                r#"|-:|-:|
+|**Platform**|**Usage**|
+|-:|-:|
+{}
+|-:|-:|
+{}
+|-:|-:|
+{}
+|-"#,
+                get_usage_pf("MACOS", billable.MACOS),
+                get_usage_pf("UBUNTU", billable.UBUNTU),
+                get_usage_pf("WINDOWS", billable.WINDOWS)
+            );
+        }
+        WorkflowsSubCommand::Badges(WorkflowsBadges { html, branches, .. }) => {
+            let WorkflowList { workflows, .. } = repo()?.get_all_workflows().await?;
+            let branches = if branches.is_empty() {
+                vec![None]
+            } else {
+                branches.iter().map(|b| Some(b.as_str())).collect()
+            };
+            for workflow in workflows.iter().filter(|w| w.state == "active") {
+                for branch in &branches {
+                    let alt = match branch {
+                        Some(branch) => format!("{} ({})", workflow.name, branch),
+                        None => workflow.name.to_owned(),
+                    };
+                    let badge_url = workflow.branch_badge_url(*branch);
+                    let runs_url = workflow.runs_page_url(*branch);
+                    if *html {
+                        println!(
+                            r#"<a href="{}"><img src="{}" alt="{}"></a>"#,
+                            runs_url,
+                            badge_url,
+                            alt.replace('"', "&quot;")
+                        );
+                    } else {
+                        println!("[![{}]({})]({})", alt, badge_url, runs_url);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "workflow-file")]
+fn repo_root() -> std::path::PathBuf {
+    std::process::Command::new("git")
+        .args(&["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|root| std::path::PathBuf::from(root.trim()))
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+}
+
+#[cfg(feature = "workflow-file")]
+fn local_workflow_files(root: &std::path::Path) -> anyhow::Result<Vec<std::path::PathBuf>> {
+    use gh_lib::workflow_file::WORKFLOWS_DIR;
+
+    let dir = root.join(WORKFLOWS_DIR);
+    let mut files = std::fs::read_dir(&dir)
+        .map_err(|err| anyhow::anyhow!("unable to read {}: {}", dir.display(), err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |ext| ext == "yml" || ext == "yaml")
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+/// Path of a file relative to the repository root, as in `Workflow::path`.
+#[cfg(feature = "workflow-file")]
+fn repo_relative_path(root: &std::path::Path, file: &std::path::Path) -> String {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_owned());
+    let file = file.canonicalize().unwrap_or_else(|_| file.to_owned());
+    file.strip_prefix(&root)
+        .unwrap_or(&file)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(feature = "workflow-file")]
+async fn expand_workflow_matrix(
+    name: &str,
+    auth_token: &str,
+    matrix: &WorkflowsMatrix,
+) -> anyhow::Result<()> {
+    use gh_lib::workflow_file::{
+        compare_run_jobs, format_matrix_value, WorkflowFile, WORKFLOWS_DIR,
+    };
+
+    let WorkflowsMatrix { file, job, run_id } = matrix;
+
+    let mut path = std::path::PathBuf::from(file);
+    if !path.exists() {
+        path = repo_root().join(WORKFLOWS_DIR).join(file);
+    }
+    let source = std::fs::read_to_string(&path)
+        .map_err(|err| anyhow::anyhow!("unable to read {}: {}", path.display(), err))?;
+    let workflow = WorkflowFile::parse(&source)?;
+
+    let jobs = match job {
+        Some(job_id) => {
+            let job = workflow
+                .jobs
+                .get(job_id)
+                .ok_or_else(|| anyhow::anyhow!("job `{}` not found in {}", job_id, file))?;
+            vec![(job_id, job)]
+        }
+        None => workflow.jobs.iter().collect(),
+    };
+
+    let mut expected = Vec::new();
+    for (job_id, job) in jobs {
+        let expanded = job.expand(job_id)?;
+        let mut keys = Vec::new();
+        for (key, _) in expanded.iter().flat_map(|job| job.matrix.iter()) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        printmd!("## {}", job_id);
+        printmd!(
+            "**Jobs**:\t{}\t**Max Parallel**:\t{}",
+            expanded.len(),
+            job.max_parallel()
+                .map_or_else(|| "unlimited".to_owned(), |max| max.to_string())
+        );
+        if !keys.is_empty() {
+            let rows = expanded
+                .iter()
+                .map(|job| {
+                    let values = keys
+                        .iter()
+                        .map(|key| {
+                            job.matrix
+                                .get(key)
+                                .map_or_else(String::new, format_matrix_value)
+                        })
+                        .collect::<Vec<_>>();
+                    format!("|{}|{}", job.name, values.join("|"))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let header = keys
+                .iter()
+                .map(|key| format!("**{}**", format_matrix_value(key)))
+                .collect::<Vec<_>>();
+            printmd!(
+                "|:-:|{sep}\n|**Name**|{header}|\n|:-|{sep}\n{rows}\n|-",
+                sep = vec![":-"; keys.len()].join("|"),
+                header = header.join("|"),
+                rows = rows
+            );
+        }
+        expected.extend(expanded);
+    }
+
+    if let Some(run_id) = run_id {
+        let repo = repo_request(name, auth_token)?;
+        let run_jobs = repo.get_workflow_run_jobs(*run_id).await?;
+        let mut comparison = compare_run_jobs(&expected, &run_jobs.jobs);
+        if job.is_some() {
+            // Jobs of the run not expanded from this job are expected to be there.
+            comparison.unexpected.clear();
+        }
+        printmd!("## Run {}", run_id);
+        for name in &comparison.matched {
+            println!("{} {}", "✓".green(), name);
+        }
+        for name in &comparison.missing {
+            println!("{} {} {}", "✗".red(), name, "(missing)".dark_grey());
+        }
+        for name in &comparison.unexpected {
+            println!("{} {} {}", "?".yellow(), name, "(unexpected)".dark_grey());
+        }
+        if !comparison.is_match() {
+            anyhow::bail!("run {} does not have the expected jobs of {}", run_id, file);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "workflow-file", feature = "git-refs"))]
+async fn pin_workflow_actions(auth_token: &str, pin: &WorkflowsPin) -> anyhow::Result<()> {
+    use gh_lib::core::git_refs::GitRefs as _;
+    use gh_lib::workflow_file::{find_uses, is_commit_sha, rewrite_uses, ActionRef};
+    use std::collections::HashMap;
+
+    let WorkflowsPin {
+        files,
+        check,
+        update,
+        all,
+    } = pin;
+
+    let root = repo_root();
+    let files = if files.is_empty() {
+        local_workflow_files(&root)?
+    } else {
+        files.iter().map(std::path::PathBuf::from).collect()
+    };
+    // Without `--update` the actions to pin are known without asking GitHub.
+    let offline = *check && !*update;
+
+    // New `uses` value and tag comment of every reference to rewrite.
+    let mut pins: HashMap<String, (String, String)> = HashMap::new();
+    let mut changes = 0;
+    for file in &files {
+        let source = std::fs::read_to_string(file)
+            .map_err(|err| anyhow::anyhow!("unable to read {}: {}", file.display(), err))?;
+        for uses in find_uses(&source) {
+            let action = match ActionRef::parse(&uses.uses) {
+                Ok(action @ ActionRef::Repository { .. }) => action,
+                _ => continue,
+            };
+            if !*all && !action.is_third_party() {
+                continue;
+            }
+            let (owner, repo, path, git_ref) = match &action {
+                ActionRef::Repository {
+                    owner,
+                    repo,
+                    path,
+                    git_ref,
+                } => (owner, repo, path, git_ref),
+                _ => continue,
+            };
+            let current_tag = match (is_commit_sha(git_ref), uses.pinned_tag()) {
+                (false, _) => git_ref.to_owned(),
+                (true, Some(tag)) if *update => tag.to_owned(),
+                (true, _) => continue,
+            };
+
+            let location = format!("{}:{}", file.display(), uses.line + 1);
+            if offline {
+                println!(
+                    "{} {} is not pinned",
+                    location.yellow(),
+                    uses.uses.to_owned().bold()
+                );
+                changes += 1;
+                continue;
+            }
+
+            let (pinned, tag) = match pins.get(&uses.uses) {
+                Some(pin) => pin.to_owned(),
+                None => {
+                    let repo_addr = format!("{}/{}", owner, repo);
+                    let request = repo_request(&repo_addr, auth_token)?;
+                    let tag = if *update {
+                        request
+                            .get_latest_release()
+                            .await
+                            .map_or(current_tag, |release| release.tag_name)
+                    } else {
+                        current_tag
+                    };
+                    let sha = match request.resolve_commit_sha(&tag).await {
+                        Ok(sha) => sha,
+                        Err(err) => {
+                            eprintln!(
+                                "{} {} skipped: {}",
+                                "warning:".bold().yellow(),
+                                location,
+                                err
+                            );
+                            continue;
+                        }
+                    };
+                    let pinned = ActionRef::Repository {
+                        owner: owner.to_owned(),
+                        repo: repo.to_owned(),
+                        path: path.to_owned(),
+                        git_ref: sha,
+                    }
+                    .to_string();
+                    pins.insert(uses.uses.to_owned(), (pinned.to_owned(), tag.to_owned()));
+                    (pinned, tag)
+                }
+            };
+            if pinned == uses.uses && uses.pinned_tag() == Some(tag.as_str()) {
+                continue;
+            }
+            println!(
+                "{} {} -> {} # {}",
+                location.yellow(),
+                uses.uses.to_owned().bold(),
+                pinned,
+                tag
+            );
+            changes += 1;
+        }
+
+        if !*check {
+            let rewritten = rewrite_uses(&source, |uses| {
+                pins.get(&uses.uses)
+                    .filter(|(pinned, tag)| {
+                        *pinned != uses.uses || uses.pinned_tag() != Some(tag.as_str())
+                    })
+                    .cloned()
+            });
+            if rewritten != source {
+                std::fs::write(file, rewritten)?;
+            }
+        }
+    }
+
+    match (changes, *check) {
+        (0, _) => println!("{}", "All actions are pinned!".bold().green()),
+        (_, true) => anyhow::bail!("{} actions need to be pinned", changes),
+        (_, false) => println!("{}", format!("Pinned {} actions!", changes).bold().green()),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "workflow-file")]
+async fn lint_workflow_files(
+    name: &str,
+    auth_token: &str,
+    lint: &WorkflowsLint,
+) -> anyhow::Result<()> {
+    use gh_lib::workflow_file;
+
+    let WorkflowsLint { files, remote } = lint;
+
+    let root = repo_root();
+    let all_files = files.is_empty();
+    let files = if all_files {
+        local_workflow_files(&root)?
+    } else {
+        files.iter().map(std::path::PathBuf::from).collect()
+    };
+
+    let mut problems = 0;
+    for file in &files {
+        let source = std::fs::read_to_string(file)
+            .map_err(|err| anyhow::anyhow!("unable to read {}: {}", file.display(), err))?;
+        let issues = workflow_file::lint(&source);
+        if issues.is_empty() {
+            println!("{} {}", "✓".green(), file.display());
+            continue;
+        }
+        println!("{} {}", "✗".red(), file.display().to_string().bold());
+        for issue in &issues {
+            println!(
+                "    {}{}{} {}",
+                issue.location.to_owned().yellow(),
+                if issue.location.is_empty() { "" } else { ": " },
+                issue.message,
+                format!("[{}]", issue.kind).dark_grey()
+            );
+        }
+        problems += issues.len();
+    }
+
+    if *remote {
+        let repo = repo_request(name, auth_token)?;
+        let WorkflowList { workflows, .. } = repo.get_all_workflows().await?;
+        let paths = files
+            .iter()
+            .map(|file| repo_relative_path(&root, file))
+            .collect::<Vec<_>>();
+        let matched = paths
+            .iter()
+            .map(|path| match workflows.iter().find(|w| &w.path == path) {
+                Some(w) => format!("|{}|{}|{}|{}", path, w.name, w.id, w.state),
+                None => format!("|{}|*not on GitHub*|||", path),
+            })
+            .chain(
+                workflows
+                    .iter()
+                    .filter(|w| all_files && !paths.contains(&w.path))
+                    .map(|w| {
+                        format!(
+                            "|{} *(missing locally)*|{}|{}|{}",
+                            w.path, w.name, w.id, w.state
+                        )
+                    }),
+            )
+            .collect::<Vec<_>>()
+            .join("\n");
+        printmd!(
+            r#"|:-:|:-:|:-:|:-:
+|**File**|**Workflow**|**Id**|**State**|
+|:-|:-|-:|:-
+{}
+|-"#,
+            matched
+        );
+    }
+
+    if problems > 0 {
+        anyhow::bail!("{} problems found in {} files", problems, files.len());
+    }
+    println!("{}", "No problems found!".bold().green());
+    Ok(())
+}
+
+#[cfg(feature = "workflows")]
+async fn handle_actions_workflow_runs(workflow_runs: &WorkflowRuns) -> anyhow::Result<()> {
+    let WorkflowRuns {
+        name,
+        auth_token,
+        subcmd,
+    } = workflow_runs;
+
+    let repo = repo_request(&name, &auth_token)?;
+
+    match &subcmd {
+        WorkflowRunsSubCommand::List => {
+            let all_repo_runs = repo.get_all_workflow_runs().await?;
+            println!("All Repo Workflow Runs: {:#?}", all_repo_runs);
+        }
+        WorkflowRunsSubCommand::ListWorkflow(WorkflowId { workflow_id }) => {
+            let workflow_runs = repo.get_workflow_runs(*workflow_id).await?;
+            println!("Workflow Runs: {:#?}", workflow_runs);
+        }
+        WorkflowRunsSubCommand::Get(WorkflowRunId { run_id }) => {
+            let workflow_run = repo.get_a_workflow_run(*run_id).await?;
+            println!("Workflow Run: {:#?}", workflow_run);
+        }
+        WorkflowRunsSubCommand::ReRun(WorkflowRunId { run_id }) => {
+            repo.rerun_a_workflow(*run_id).await?;
+            println!("Workflow Re-Run Initiated!");
+        }
+        WorkflowRunsSubCommand::Cancel(WorkflowRunId { run_id }) => {
+            repo.cancel_a_workflow_run(*run_id).await?;
+            println!("Workflow Re-Run Initiated!");
+        }
+        WorkflowRunsSubCommand::DownloadLogs(WorkflowRunId { run_id }) => {
+            let url = repo.get_run_logs_url(*run_id).await?;
+            println!("Logs Download Url: {}", url);
+        }
+        WorkflowRunsSubCommand::DeleteLogs(WorkflowRunId { run_id }) => {
+            repo.delete_run_logs(*run_id).await?;
+            println!("Logs Deleted!");
+        }
+        WorkflowRunsSubCommand::Usage(WorkflowRunId { run_id }) => {
+            let usage = repo.get_workflow_run_usage(*run_id).await?;
+            println!("Workflow Run Usage: {:#?}", usage);
+        }
+        WorkflowRunsSubCommand::Pending(WorkflowRunId { run_id }) => {
+            let deployments = repo
+                .get_pending_deployments(*run_id)
+                .await?
+                .iter()
+                .map(|d| {
+                    format!(
+                        "|{}|{}|{}|{}",
+                        d.environment.name,
+                        if d.current_user_can_approve {
+                            "yes"
+                        } else {
+                            "no"
+                        },
+                        d.wait_timer,
+                        d.reviewers
+                            .iter()
+                            .map(|r| r.reviewer.display_name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            printmd("## Pending Deployments");
+            printmd!(
+                r#"|:-:|:-:|:-:|:-:
+|**Environment**|**Can Approve**|**Wait Timer**|**Reviewers**|
+|:-|:-:|-:|:-
+{}
+|-"#,
+                deployments
+            );
+        }
+        WorkflowRunsSubCommand::Approve(review) => {
+            review_pending_deployments(&repo, review, DeploymentReviewState::Approved).await?
+        }
+        WorkflowRunsSubCommand::Reject(review) => {
+            review_pending_deployments(&repo, review, DeploymentReviewState::Rejected).await?
+        }
+        #[cfg(feature = "chrono")]
+        WorkflowRunsSubCommand::Prune(prune) => prune_workflow_runs(&repo, prune).await?,
+        #[cfg(feature = "timeline")]
+        WorkflowRunsSubCommand::Timeline(timeline) => {
+            workflow_run_timeline(&repo, timeline).await?
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "timeline")]
+async fn workflow_run_timeline(
+    repo: &RepoRequest<'_>,
+    timeline: &WorkflowRunTimeline,
+) -> anyhow::Result<()> {
+    use gh_lib::analytics::RunRecord;
+    use gh_lib::timeline::Timeline;
+
+    let WorkflowRunTimeline { run_id, svg, html } = timeline;
+
+    let run = repo.get_a_workflow_run(*run_id).await?;
+    let jobs = repo.get_workflow_run_jobs(*run_id).await?;
+    let record = RunRecord::from(&run, &jobs.jobs);
+    let timeline = Timeline::from(&record.jobs);
+    let title = format!("Workflow Run #{} on {}", run.run_number, run.head_branch);
+
+    if *svg {
+        print!("{}", timeline.to_svg(&title));
+    } else if *html {
+        print!("{}", timeline.to_html(&title));
+    } else {
+        printmd!("## {}", title);
+        print_timeline(&timeline);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "timeline")]
+fn print_timeline(timeline: &gh_lib::timeline::Timeline) {
+    use crossterm::style::{style, Attribute, Color};
+    use gh_lib::timeline::{format_duration, BarKind};
+    use output::Styled;
+
+    const MAX_LABEL_WIDTH: usize = 36;
+    const DURATION_WIDTH: usize = 9;
+
+    let label_width = timeline
+        .bars
+        .iter()
+        .map(|bar| bar.name.chars().count() + 4)
+        .max()
+        .unwrap_or(0)
+        .min(MAX_LABEL_WIDTH);
+    let columns = crossterm::terminal::size().map_or(80, |(columns, _)| columns as usize);
+    let chart_width = columns
+        .saturating_sub(label_width + DURATION_WIDTH + 2)
+        .max(10);
+    let column = |secs: u64| {
+        (secs as usize * chart_width / timeline.duration_secs.max(1) as usize).min(chart_width)
+    };
+
+    for bar in &timeline.bars {
+        let (marker, indent, glyph) = match (bar.kind, bar.critical) {
+            (BarKind::Job, true) => ("● ", "", '█'),
+            (BarKind::Job, false) => ("  ", "", '█'),
+            (BarKind::Step, _) => ("  ", "  ", '▒'),
+        };
+        let mut label = format!("{}{}{}", marker, indent, bar.name);
+        if label.chars().count() > label_width {
+            label = label.chars().take(label_width - 1).collect::<String>() + "…";
+        }
+        let start = column(bar.start_secs);
+        let width = (column(bar.start_secs + bar.duration_secs) - start).max(1);
+        let color = match bar.conclusion.as_str() {
+            "success" => Color::Green,
+            "failure" | "timed_out" => Color::Red,
+            "cancelled" | "skipped" | "neutral" => Color::DarkGrey,
+            _ => Color::Yellow,
+        };
+
+        let mut label = style(format!("{:<width$}", label, width = label_width));
+        if bar.critical {
+            label = label.with(Color::Magenta).attribute(Attribute::Bold);
+        } else if bar.kind == BarKind::Job {
+            label = label.attribute(Attribute::Bold);
+        }
+        println!(
+            "{} {}{}{} {:>width$}",
+            Styled(label),
+            " ".repeat(start),
+            Styled(style(glyph.to_string().repeat(width)).with(color)),
+            " ".repeat(chart_width - (start + width).min(chart_width)),
+            format_duration(bar.duration_secs),
+            width = DURATION_WIDTH
+        );
+    }
+    println!(
+        "{:<label$} 0s{:>chart$}",
+        "",
+        format_duration(timeline.duration_secs),
+        label = label_width,
+        chart = chart_width - 2
+    );
+    println!(
+        "{} critical path",
+        Styled(style("●").with(Color::Magenta).attribute(Attribute::Bold))
+    );
+}
+
+#[cfg(feature = "workflows")]
+async fn handle_actions_summary(summary: &Summary) -> anyhow::Result<()> {
+    use futures::stream::{self, StreamExt as _};
+    use gh_lib::core::repos::Repositories as _;
+    use gh_lib::core::workflow_runs::{WorkflowRunList, WorkflowRunQueryParams};
+
+    let Summary {
+        name,
+        auth_token,
+        branch,
+    } = summary;
+
+    let repo = repo_request(name, auth_token)?;
+    let branch = match branch {
+        Some(branch) => branch.to_owned(),
+        None => repo.get_repository().await?.default_branch,
+    };
+    let WorkflowList { workflows, .. } = repo.get_all_workflows().await?;
+
+    let repo = &repo;
+    let branch = branch.as_str();
+    let latest_runs = stream::iter(workflows.iter().filter(|w| w.state == "active"))
+        .map(|workflow| async move {
+            let params = WorkflowRunQueryParams {
+                branch: Some(branch),
+                per_page: Some(1),
+                ..Default::default()
+            };
+            let WorkflowRunList { workflow_runs, .. } = repo
+                .get_workflow_runs_with_params(workflow.id, params)
+                .await?;
+            Ok::<_, anyhow::Error>((workflow, workflow_runs.into_iter().next()))
+        })
+        .buffered(8)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let name_width = latest_runs
+        .iter()
+        .map(|(w, _)| w.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    println!("{} {}", "Branch:".bold(), branch);
+    for (workflow, run) in &latest_runs {
+        let name = format!("{:<width$}", workflow.name, width = name_width);
+        let run = match run {
+            Some(run) => run,
+            None => {
+                println!("{} {} {}", "-".dark_grey(), name, "no runs".dark_grey());
+                continue;
+            }
+        };
+        let state = run
+            .conclusion
+            .as_deref()
+            .unwrap_or_else(|| run.status.as_str());
+        let symbol = match state {
+            "success" => "✓".green(),
+            "failure" | "timed_out" | "startup_failure" => "✗".red(),
+            "cancelled" | "skipped" | "neutral" | "stale" => "⊘".dark_grey(),
+            _ => "●".yellow(),
+        };
+        println!(
+            "{} {} {:<10} {:>6} {} {} {}",
+            symbol,
+            name,
+            state,
+            format!("#{}", run.run_number),
+            run.head_sha.chars().take(7).collect::<String>().dark_grey(),
+            run.event,
+            run.updated_at.to_string().dark_grey()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "workflows", feature = "chrono"))]
+async fn resolve_workflow_id(repo: &RepoRequest<'_>, workflow: &str) -> anyhow::Result<u32> {
+    if let Ok(workflow_id) = workflow.parse() {
+        return Ok(workflow_id);
+    }
+    let WorkflowList { workflows, .. } = repo.get_all_workflows().await?;
+    workflows
+        .iter()
+        .find(|w| {
+            w.name == workflow || w.path == workflow || w.path.rsplit('/').next() == Some(workflow)
+        })
+        .map(|w| w.id)
+        .ok_or_else(|| anyhow::anyhow!("workflow `{}` not found", workflow))
+}
+
+#[cfg(all(feature = "workflows", feature = "chrono"))]
+async fn get_workflow_run_pages(
+    repo: &RepoRequest<'_>,
+    workflow_id: Option<u32>,
+    params: WorkflowRunQueryParams<'_>,
+    limit: Option<usize>,
+) -> anyhow::Result<Vec<WorkflowRun>> {
+    const PER_PAGE: u32 = 100;
+
+    let mut runs = Vec::new();
+    let mut page = 1;
+    loop {
+        let params = WorkflowRunQueryParams {
+            per_page: Some(PER_PAGE),
+            page: Some(page),
+            ..params.clone()
+        };
+        let WorkflowRunList {
+            total_count,
+            workflow_runs,
+        } = match workflow_id {
+            Some(workflow_id) => {
+                repo.get_workflow_runs_with_params(workflow_id, params)
+                    .await?
+            }
+            None => repo.get_all_workflow_runs_with_params(params).await?,
+        };
+        let fetched = workflow_runs.len();
+        runs.extend(workflow_runs);
+        if fetched < PER_PAGE as usize
+            || runs.len() >= total_count as usize
+            || limit.map_or(false, |limit| runs.len() >= limit)
+        {
+            break;
+        }
+        page += 1;
+    }
+    if let Some(limit) = limit {
+        runs.truncate(limit);
+    }
+    Ok(runs)
+}
+
+#[cfg(all(feature = "workflows", feature = "chrono"))]
+async fn prune_workflow_runs(
+    repo: &RepoRequest<'_>,
+    prune: &WorkflowRunsPrune,
+) -> anyhow::Result<()> {
+    use futures::stream::{self, StreamExt as _};
+
+    let WorkflowRunsPrune {
+        older_than,
+        status,
+        workflow,
+        branch,
+        keep_last,
+        concurrency,
+        dry_run,
+    } = prune;
+
+    let workflow_id = match workflow {
+        Some(workflow) => Some(resolve_workflow_id(repo, workflow).await?),
+        None => None,
+    };
+    let created_before = older_than.map(time_ago).transpose()?;
+
+    let params = WorkflowRunQueryParams {
+        branch: branch.as_deref(),
+        status: status.as_deref(),
+        ..Default::default()
+    };
+    let runs = get_workflow_run_pages(repo, workflow_id, params, None).await?;
+
+    let total_runs = runs.len();
+    let runs = runs
+        .into_iter()
+        .skip(*keep_last)
+        .filter(|run| {
+            created_before.map_or(true, |before| {
+                std::time::SystemTime::from(run.created_at) < before
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if *dry_run {
+        let runs = runs
+            .iter()
+            .map(|r| {
+                format!(
+                    "|{}|{}|{}|{}|{}",
+                    r.id,
+                    r.run_number,
+                    r.head_branch,
+                    r.conclusion.as_deref().unwrap_or(&r.status),
+                    r.created_at
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        printmd("## Workflow Runs to Delete");
+        printmd!(
+            r#"|:-:|:-:|:-:|:-:|:-:
+|**Id**|**Run**|**Branch**|**Status**|**Created At**|
+|-:|-:|:-|:-:|:-
+{}
+|-"#,
+            runs
+        );
+    }
+
+    let matched = runs.len();
+    let failed = if *dry_run {
+        Vec::new()
+    } else {
+        stream::iter(runs)
+            .map(|run| async move { (run.id, repo.delete_workflow_run(run.id).await) })
+            .buffer_unordered((*concurrency).max(1))
+            .filter_map(|(run_id, result)| async move { result.err().map(|err| (run_id, err)) })
+            .collect::<Vec<_>>()
+            .await
+    };
+
+    printmd("## Prune Summary");
+    printmd!("**Runs Scanned**:\t{}", total_runs);
+    printmd!(
+        "**Runs {}**:\t{}",
+        if *dry_run { "To Delete" } else { "Deleted" },
+        matched - failed.len()
+    );
+    printmd!("**Runs Kept**:\t{}", total_runs - matched);
+    if !failed.is_empty() {
+        printmd!("**Runs Failed**:\t{}", failed.len());
+        for (run_id, err) in &failed {
+            eprintln!("{} run {}: {}", "error:".bold().red(), run_id, err);
+        }
+        anyhow::bail!("failed to delete {} workflow runs", failed.len());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "workflows")]
+async fn review_pending_deployments(
+    repo: &RepoRequest<'_>,
+    review: &WorkflowRunReview,
+    state: DeploymentReviewState,
+) -> anyhow::Result<()> {
+    let WorkflowRunReview {
+        run_id,
+        envs,
+        comment,
+    } = review;
+    let deployments = repo.get_pending_deployments(*run_id).await?;
+    if deployments.is_empty() {
+        anyhow::bail!(
+            "workflow run {} has no deployments waiting for review",
+            run_id
+        );
+    }
+    for env in envs {
+        match deployments.iter().find(|d| &d.environment.name == env) {
+            None => anyhow::bail!("environment `{}` is not waiting for review", env),
+            Some(d) if !d.current_user_can_approve => {
+                anyhow::bail!("you are not allowed to review environment `{}`", env)
+            }
+            _ => {}
+        }
+    }
+    let (environment_ids, names): (Vec<_>, Vec<_>) = deployments
+        .iter()
+        .filter(|d| d.current_user_can_approve)
+        .filter(|d| envs.is_empty() || envs.contains(&d.environment.name))
+        .map(|d| (d.environment.id, d.environment.name.as_str()))
+        .unzip();
+    if environment_ids.is_empty() {
+        anyhow::bail!("you are not allowed to review any of the waiting environments");
+    }
+    repo.review_pending_deployments(*run_id, &environment_ids, state, comment)
+        .await?;
+    let action = match state {
+        DeploymentReviewState::Approved => "approved",
+        DeploymentReviewState::Rejected => "rejected",
+    };
+    println!(
+        "{}",
+        format!("Deployment to {} {}!", names.join(", "), action)
+            .bold()
+            .green()
+    );
+    Ok(())
+}
+
+#[cfg(feature = "workflows")]
+async fn handle_actions_workflow_jobs(workflow_jobs: &WorkflowJobs) -> anyhow::Result<()> {
+    let WorkflowJobs {
+        name,
+        auth_token,
+        subcmd,
+    } = workflow_jobs;
+
+    let repo = repo_request(&name, &auth_token)?;
+
+    match &subcmd {
+        WorkflowJobsSubCommand::List(WorkflowRunId { run_id }) => {
+            let jobs = repo.get_workflow_run_jobs(*run_id).await?;
+            println!("Workflow Run Jobs: {:#?}", jobs);
+        }
+        WorkflowJobsSubCommand::Get(WorkflowJobId { job_id }) => {
+            let job = repo.get_a_workflow_run_job(*job_id).await?;
+            println!("Workflow Rub Job: {:#?}", job);
+        }
+        WorkflowJobsSubCommand::DownloadLogs(WorkflowJobId { job_id }) => {
+            let url = repo.get_job_logs_url(*job_id).await?;
+            println!("Logs Download Url: {}", url);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "secrets")]
+async fn handle_actions_secrets(secrets: &Secrets) -> anyhow::Result<()> {
+    let Secrets {
+        name,
+        auth_token,
+        subcmd,
+        ..
+    } = secrets;
+
+    let repo = repo_request(&name, &auth_token)?;
+
+    match &subcmd {
+        SecretsSubCommand::List => {
+            let SecretListResponse {
+                total_count,
+                secrets,
+            } = repo.get_all_secrets().await?;
+            let secrets = secrets
                 .iter()
-                .map(|w| {
-                    [
-                        format!("|name|{}", w.name),
-                        format!("|id|{}", w.id),
-                        format!("|node_id|{}", w.node_id),
-                        format!("|path|{}", w.path),
-                        format!("|state|{}", w.state),
-                        format!("|created_at|{}", w.created_at),
-                        format!("|updated_at|{}", w.updated_at),
-                        format!("|url|{}", w.url),
-                        format!("|html_url|{}", w.html_url),
-                        format!("|badge_url|{}", w.badge_url),
-                    ]
-                    .join("\n")
-                })
+                .map(|s| format!("|{}|{}|{}", s.name, s.created_at, s.updated_at))
                 .collect::<Vec<_>>()
-                .join("\n|-:|:-|\n");
+                .join("\n");
+            printmd("## Secrets");
+            printmd!("**Total: {}", total_count);
             printmd!(
-                r#"|-:|:-|
-|**Field**|**Value**|
-|-:|:-|
+                r#"|:-:|:-:|:-:
+|**Name**|**Created At**|**Updated At**|
+|-:|:-:|:-
 {}
-|-
+|-"#,
+                secrets
+            );
+        }
+        SecretsSubCommand::Get(SecretsName { name }) => {
+            let Secret {
+                name,
+                created_at,
+                updated_at,
+            } = repo.get_a_secret(&name).await?;
+            printmd!("## Secret");
+            printmd!("**Name**:\t{}", name);
+            printmd!("**Created At**:\t{}", created_at);
+            printmd!("**Updated At**:\t{}", updated_at);
+        }
+        #[cfg(feature = "secrets-save")]
+        SecretsSubCommand::Add(name_value)
+        | SecretsSubCommand::Update(name_value)
+        | SecretsSubCommand::Edit(name_value) => {
+            let SecretsNameValue { name, value } = name_value;
+            #[cfg(feature = "secrets-ledger")]
+            save_secret_with_ledger(&repo, name, value, secrets.ledger.as_deref()).await?;
+            #[cfg(not(feature = "secrets-ledger"))]
+            repo.save_secret(&name, &value).await?;
+            println!(
+                "{}",
+                format!("Secret {} successful!", &subcmd).bold().green()
+            );
+        }
+        SecretsSubCommand::Delete(SecretsName { name }) => {
+            repo.delete_a_secret(&name).await?;
+            println!("{}", "Secret delete successful!".bold().green());
+        }
+        #[cfg(feature = "secrets-ledger")]
+        SecretsSubCommand::Verify(SecretsVerify { source }) => {
+            let ledger_path = secrets.ledger.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("Pass --ledger or set $GH_SECRETS_LEDGER to verify secrets!")
+            })?;
+            let ledger = SecretsLedger::load(ledger_path)?;
+            let local_values = match source {
+                Some(source) => read_env_file(source)?,
+                None => Default::default(),
+            };
+            let SecretListResponse { secrets, .. } = repo.get_all_secrets().await?;
+            let verifications = ledger.verify(name, &secrets, &local_values);
+            let attention_count = verifications
+                .iter()
+                .filter(|v| v.status != SecretStatus::UpToDate)
+                .count();
+            let verifications = verifications
+                .iter()
+                .map(|v| format!("|{}|{}", v.name, v.status))
+                .collect::<Vec<_>>()
+                .join("\n");
+            printmd("## Secrets Verification");
+            printmd!(
+                r#"|:-:|:-:
+|**Name**|**Status**|
+|-:|:-
+{}
+|-"#,
+                verifications
+            );
+            if attention_count > 0 {
+                return Err(anyhow::anyhow!(
+                    "{} secret(s) are not up to date with the ledger!",
+                    attention_count
+                ));
+            }
+            println!("{}", "All secrets are up to date!".bold().green());
+        }
+    }
 
-Total workflows: {}"#,
-                workflows,
-                total_count
+    Ok(())
+}
+
+#[cfg(feature = "secrets-ledger")]
+async fn save_secret_with_ledger(
+    repo: &RepoRequest<'_>,
+    name: &str,
+    value: &str,
+    ledger_path: Option<&str>,
+) -> anyhow::Result<()> {
+    if let Some(ledger_path) = ledger_path {
+        let mut ledger = SecretsLedger::load(ledger_path)?;
+        repo.save_secret_with_ledger(name, value, &mut ledger)
+            .await?;
+        ledger.save(ledger_path)
+    } else {
+        repo.save_secret(name, value).await
+    }
+}
+
+/// Reads `NAME=VALUE` lines, skipping blank lines and `#` comments.
+#[cfg(feature = "secrets-ledger")]
+fn read_env_file(path: &str) -> anyhow::Result<std::collections::HashMap<String, String>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) => {
+                    let value = value.trim();
+                    let value = value
+                        .strip_prefix('"')
+                        .and_then(|v| v.strip_suffix('"'))
+                        .unwrap_or(value);
+                    Some((name.trim().to_owned(), value.to_owned()))
+                }
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+#[cfg(feature = "variables")]
+async fn handle_actions_variables(variables: &Variables) -> anyhow::Result<()> {
+    let Variables {
+        name,
+        auth_token,
+        env,
+        org,
+        subcmd,
+    } = variables;
+
+    let request: Box<dyn gh_lib::core::variables::Variables + Send + Sync> = match (org, env) {
+        (Some(org), _) => Box::new(org_request(org, auth_token)?),
+        (None, Some(env)) => Box::new(environment_request(name, env, auth_token)?),
+        (None, None) => Box::new(repo_request(name, auth_token)?),
+    };
+
+    match subcmd {
+        VariablesSubCommand::List => {
+            let VariableListResponse {
+                total_count,
+                variables,
+            } = request.get_all_variables().await?;
+            let variables = variables
+                .iter()
+                .map(|v| format!("|{}|{}|{}|{}", v.name, v.value, v.created_at, v.updated_at))
+                .collect::<Vec<_>>()
+                .join("\n");
+            printmd("## Variables");
+            printmd!("**Total: {}", total_count);
+            printmd!(
+                r#"|:-:|:-:|:-:|:-:
+|**Name**|**Value**|**Created At**|**Updated At**|
+|-:|:-|:-:|:-
+{}
+|-"#,
+                variables
             );
         }
-        WorkflowsSubCommand::Get(WorkflowId { workflow_id }) => {
-            let workflow = repo.get_a_workflow(*workflow_id).await?;
+        VariablesSubCommand::Get(VariablesName { name }) => {
+            let Variable {
+                name,
+                value,
+                created_at,
+                updated_at,
+                visibility,
+                ..
+            } = request.get_a_variable(name).await?;
+            printmd!("## Variable");
+            printmd!("**Name**:\t{}", name);
+            printmd!("**Value**:\t{}", value);
+            if let Some(visibility) = visibility {
+                printmd!("**Visibility**:\t{}", visibility);
+            }
+            printmd!("**Created At**:\t{}", created_at);
+            printmd!("**Updated At**:\t{}", updated_at);
+        }
+        VariablesSubCommand::Set(VariablesNameValue { name, value }) => {
+            let action = match request.get_a_variable(name).await {
+                Ok(_) => {
+                    request.update_variable(name, value).await?;
+                    "update"
+                }
+                Err(err)
+                    if err
+                        .downcast_ref::<gh_lib::HttpError>()
+                        .is_some_and(|err| err.status == gh_lib::StatusCode::NOT_FOUND) =>
+                {
+                    request.create_variable(name, value).await?;
+                    "create"
+                }
+                Err(err) => return Err(err),
+            };
+            println!(
+                "{}",
+                format!("Variable {} successful!", action).bold().green()
+            );
+        }
+        VariablesSubCommand::Delete(VariablesName { name }) => {
+            request.delete_a_variable(name).await?;
+            println!("{}", "Variable delete successful!".bold().green());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "runners")]
+fn print_runners(runners: &mut [Runner]) {
+    runners.sort_by(|a, b| {
+        a.is_online()
+            .cmp(&b.is_online())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    let runners = runners
+        .iter()
+        .map(|r| {
+            format!(
+                "|{}|{}|{}|{}|{}|{}",
+                r.id,
+                r.name,
+                r.os,
+                r.status,
+                if r.busy { "busy" } else { "idle" },
+                runner_labels(&r.labels)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    printmd!(
+        r#"|:-:|:-:|:-:|:-:|:-:|:-:
+|**Id**|**Name**|**OS**|**Status**|**Busy**|**Labels**|
+|-:|:-|:-:|:-:|:-:|:-
+{}
+|-"#,
+        runners
+    );
+}
+
+#[cfg(feature = "runners")]
+fn runner_labels(labels: &[RunnerLabel]) -> String {
+    labels
+        .iter()
+        .map(|l| l.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(feature = "runners")]
+fn print_runner_group(group: &RunnerGroup) {
+    printmd!("## Runner Group");
+    printmd!("**Id**:\t{}", group.id);
+    printmd!("**Name**:\t{}", group.name);
+    printmd!("**Visibility**:\t{}", group.visibility);
+    printmd!("**Default**:\t{}", group.default);
+    printmd!("**Inherited**:\t{}", group.inherited);
+    printmd!(
+        "**Allows Public Repositories**:\t{}",
+        group.allows_public_repositories
+    );
+}
+
+#[cfg(feature = "runners")]
+async fn handle_actions_runners(runners: &Runners) -> anyhow::Result<()> {
+    let Runners {
+        name,
+        auth_token,
+        org,
+        subcmd,
+    } = runners;
+
+    let request: Box<dyn gh_lib::core::runners::Runners + Send + Sync> = match org {
+        Some(org) => Box::new(org_request(org, auth_token)?),
+        None => Box::new(repo_request(name, auth_token)?),
+    };
+
+    match subcmd {
+        RunnersSubCommand::List => {
+            let RunnerList {
+                total_count,
+                mut runners,
+            } = request.get_all_runners().await?;
+            printmd("## Self-hosted Runners");
+            printmd!("**Total: {}", total_count);
+            print_runners(&mut runners);
+        }
+        RunnersSubCommand::Get(RunnerId { runner_id }) => {
+            let Runner {
+                id,
+                name,
+                os,
+                status,
+                busy,
+                labels,
+            } = request.get_a_runner(*runner_id).await?;
+            printmd!("## Self-hosted Runner");
+            printmd!("**Id**:\t{}", id);
+            printmd!("**Name**:\t{}", name);
+            printmd!("**OS**:\t{}", os);
+            printmd!("**Status**:\t{}", status);
+            printmd!("**Busy**:\t{}", busy);
+            printmd!("**Labels**:\t{}", runner_labels(&labels));
+        }
+        RunnersSubCommand::Delete(RunnerId { runner_id }) => {
+            request.delete_a_runner(*runner_id).await?;
+            println!("{}", "Runner delete successful!".bold().green());
+        }
+        RunnersSubCommand::RegistrationToken => {
+            let token = request.create_registration_token().await?;
+            printmd!("**Token**:\t{}", token.token);
+            printmd!("**Expires At**:\t{}", token.expires_at);
+        }
+        RunnersSubCommand::RemoveToken => {
+            let token = request.create_remove_token().await?;
+            printmd!("**Token**:\t{}", token.token);
+            printmd!("**Expires At**:\t{}", token.expires_at);
+        }
+        RunnersSubCommand::Downloads => {
+            let downloads = request
+                .get_runner_downloads()
+                .await?
+                .iter()
+                .map(|d| format!("|{}|{}|{}", d.os, d.architecture, d.download_url))
+                .collect::<Vec<_>>()
+                .join("\n");
+            printmd("## Runner Downloads");
             printmd!(
-                r#"|-:|:-|
-|**Field**|**Value**|
-|-:|:-|
+                r#"|:-:|:-:|:-:
+|**OS**|**Architecture**|**Download URL**|
+|:-|:-:|:-
 {}
 |-"#,
-                [
-                    format!("|name|{}", workflow.name),
-                    format!("|id|{}", workflow.id),
-                    format!("|node_id|{}", workflow.node_id),
-                    format!("|path|{}", workflow.path),
-                    format!("|state|{}", workflow.state),
-                    format!("|created_at|{}", workflow.created_at),
-                    format!("|updated_at|{}", workflow.updated_at),
-                    format!("|url|{}", workflow.url),
-                    format!("|html_url|{}", workflow.html_url),
-                    format!("|badge_url|{}", workflow.badge_url),
-                ]
-                .join("\n")
+                downloads
+            );
+        }
+        RunnersSubCommand::Labels(RunnerLabels { subcmd }) => {
+            let labels = match subcmd {
+                RunnerLabelsSubCommand::List(RunnerId { runner_id }) => {
+                    request.get_runner_labels(*runner_id).await?
+                }
+                RunnerLabelsSubCommand::Add(RunnerIdLabels { runner_id, labels }) => {
+                    let labels = labels.iter().map(String::as_str).collect::<Vec<_>>();
+                    request.add_runner_labels(*runner_id, &labels).await?
+                }
+                RunnerLabelsSubCommand::Set(RunnerIdLabels { runner_id, labels }) => {
+                    let labels = labels.iter().map(String::as_str).collect::<Vec<_>>();
+                    request.set_runner_labels(*runner_id, &labels).await?
+                }
+                RunnerLabelsSubCommand::Remove(RunnerIdLabel { runner_id, label }) => {
+                    request.remove_runner_label(*runner_id, label).await?
+                }
+            };
+            let labels = labels
+                .labels
+                .iter()
+                .map(|l| format!("|{}|{}", l.name, l.label_type))
+                .collect::<Vec<_>>()
+                .join("\n");
+            printmd("## Runner Labels");
+            printmd!(
+                r#"|:-:|:-:
+|**Name**|**Type**|
+|:-|:-:
+{}
+|-"#,
+                labels
             );
         }
-        WorkflowsSubCommand::Usage(WorkflowId { workflow_id }) => {
-            let WorkflowUsage { billable } = repo.get_workflow_usage(*workflow_id).await?;
-            let get_usage_pf = |pf: &str, timing: Option<WorkflowUsageTiming>| {
-                format!(
-                    "|{}|{}|",
-                    pf,
-                    timing
-                        .map(|t| format!("{}ms", t.total_ms))
-                        .unwrap_or_else(|| "unknown".into())
-                )
+        RunnersSubCommand::Groups(RunnerGroupsCmd { subcmd }) => {
+            let org = org
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("runner groups are only available with --org"))?;
+            let request = org_request(org, auth_token)?;
+            match subcmd {
+                RunnerGroupsSubCommand::List => {
+                    let RunnerGroupList {
+                        total_count,
+                        runner_groups,
+                    } = request.get_all_runner_groups().await?;
+                    let runner_groups = runner_groups
+                        .iter()
+                        .map(|g| format!("|{}|{}|{}|{}", g.id, g.name, g.visibility, g.default))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    printmd("## Runner Groups");
+                    printmd!("**Total: {}", total_count);
+                    printmd!(
+                        r#"|:-:|:-:|:-:|:-:
+|**Id**|**Name**|**Visibility**|**Default**|
+|-:|:-|:-:|:-:
+{}
+|-"#,
+                        runner_groups
+                    );
+                }
+                RunnerGroupsSubCommand::Get(RunnerGroupId { group_id }) => {
+                    print_runner_group(&request.get_a_runner_group(*group_id).await?);
+                }
+                RunnerGroupsSubCommand::Create(RunnerGroupCreate { name, visibility }) => {
+                    let group = request.create_runner_group(name, visibility).await?;
+                    println!("{}", "Runner group create successful!".bold().green());
+                    print_runner_group(&group);
+                }
+                RunnerGroupsSubCommand::Delete(RunnerGroupId { group_id }) => {
+                    request.delete_a_runner_group(*group_id).await?;
+                    println!("{}", "Runner group delete successful!".bold().green());
+                }
+                RunnerGroupsSubCommand::Runners(RunnerGroupId { group_id }) => {
+                    let RunnerList {
+                        total_count,
+                        mut runners,
+                    } = request.get_runner_group_runners(*group_id).await?;
+                    printmd("## Runner Group Runners");
+                    printmd!("**Total: {}", total_count);
+                    print_runners(&mut runners);
+                }
+                RunnerGroupsSubCommand::Add(RunnerGroupRunner {
+                    group_id,
+                    runner_id,
+                }) => {
+                    request.add_runner_to_group(*group_id, *runner_id).await?;
+                    println!("{}", "Runner added to group!".bold().green());
+                }
+                RunnerGroupsSubCommand::Remove(RunnerGroupRunner {
+                    group_id,
+                    runner_id,
+                }) => {
+                    request
+                        .remove_runner_from_group(*group_id, *runner_id)
+                        .await?;
+                    println!("{}", "Runner removed from group!".bold().green());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(any(feature = "caches", all(feature = "workflows", feature = "chrono")))]
+fn parse_age(age: &str) -> anyhow::Result<std::time::Duration> {
+    let invalid = || anyhow::anyhow!("invalid age `{}`, eg. 12h, 7d, 2w", age);
+    let (value, unit) = age.split_at(age.len() - age.trim_start_matches(char::is_numeric).len());
+    let value: u64 = value.parse().map_err(|_| invalid())?;
+    let secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    let duration = value
+        .checked_mul(secs)
+        .map(std::time::Duration::from_secs)
+        .ok_or_else(invalid)?;
+    time_ago(duration).map_err(|_| invalid())?;
+    Ok(duration)
+}
+
+/// The time `age` ago, which must not be before the Unix epoch.
+#[cfg(any(feature = "caches", all(feature = "workflows", feature = "chrono")))]
+fn time_ago(age: std::time::Duration) -> anyhow::Result<std::time::SystemTime> {
+    std::time::SystemTime::now()
+        .checked_sub(age)
+        .filter(|time| *time >= std::time::UNIX_EPOCH)
+        .ok_or_else(|| anyhow::anyhow!("invalid age of {}s, before 1970", age.as_secs()))
+}
+
+#[cfg(feature = "caches")]
+fn parse_size(size: &str) -> anyhow::Result<u64> {
+    let invalid = || anyhow::anyhow!("invalid size `{}`, eg. 500k, 100M, 1G", size);
+    let (value, unit) = size.split_at(size.len() - size.trim_start_matches(char::is_numeric).len());
+    let value: u64 = value.parse().map_err(|_| invalid())?;
+    let unit = unit.to_ascii_uppercase();
+    let multiplier: u64 = match unit.strip_suffix('B').unwrap_or(&unit) {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => return Err(invalid()),
+    };
+    value.checked_mul(multiplier).ok_or_else(invalid)
+}
+
+#[cfg(feature = "caches")]
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(feature = "caches")]
+fn print_caches(caches: &[ActionsCache]) {
+    let total_size: u64 = caches.iter().map(|c| c.size_in_bytes).sum();
+    let caches = caches
+        .iter()
+        .map(|c| {
+            format!(
+                "|{}|{}|{}|{}|{}",
+                c.id,
+                c.key,
+                c.git_ref,
+                format_size(c.size_in_bytes),
+                c.last_accessed_at
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    printmd!(
+        r#"|:-:|:-:|:-:|:-:|:-:
+|**Id**|**Key**|**Ref**|**Size**|**Last Accessed At**|
+|-:|:-|:-|-:|:-:
+{}
+|-"#,
+        caches
+    );
+    printmd!("**Total Size**:\t{}", format_size(total_size));
+}
+
+#[cfg(feature = "caches")]
+async fn handle_actions_cache(cache: &Cache) -> anyhow::Result<()> {
+    let Cache {
+        name,
+        auth_token,
+        subcmd,
+    } = cache;
+
+    match subcmd {
+        CacheSubCommand::List(CacheList {
+            key,
+            git_ref,
+            sort,
+            direction,
+            min_size,
+            unused_for,
+        }) => {
+            let request = repo_request(name, auth_token)?;
+            let filter = CacheFilter {
+                git_ref: git_ref.as_deref(),
+                min_size_in_bytes: *min_size,
+                last_accessed_before: unused_for.map(time_ago).transpose()?,
+            };
+            let caches = request
+                .get_all_caches(&CacheQueryParams {
+                    key: key.as_deref(),
+                    git_ref: git_ref.as_deref().filter(|r| !r.contains('*')),
+                    sort: Some(sort),
+                    direction: Some(direction),
+                })
+                .await?
+                .into_iter()
+                .filter(|c| filter.matches(c))
+                .collect::<Vec<_>>();
+            printmd("## Caches");
+            printmd!("**Total: {}", caches.len());
+            print_caches(&caches);
+        }
+        CacheSubCommand::Delete(CacheDelete {
+            cache_id,
+            key,
+            git_ref,
+            dry_run,
+        }) => {
+            let request = repo_request(name, auth_token)?;
+            let caches = match cache_id {
+                Some(cache_id) if *dry_run => {
+                    println!("Cache {} would be deleted", cache_id);
+                    return Ok(());
+                }
+                Some(cache_id) => {
+                    request.delete_cache_by_id(*cache_id).await?;
+                    println!(
+                        "{}",
+                        format!("Cache {} delete successful!", cache_id)
+                            .bold()
+                            .green()
+                    );
+                    return Ok(());
+                }
+                None => {
+                    let filter = CacheFilter {
+                        git_ref: git_ref.as_deref(),
+                        ..Default::default()
+                    };
+                    request
+                        .get_all_caches(&CacheQueryParams {
+                            key: key.as_deref(),
+                            git_ref: git_ref.as_deref().filter(|r| !r.contains('*')),
+                            ..Default::default()
+                        })
+                        .await?
+                        .into_iter()
+                        .filter(|c| filter.matches(c))
+                        .collect::<Vec<_>>()
+                }
             };
+
+            let mut open_pulls = std::collections::HashMap::new();
+            let mut deleted = Vec::new();
+            for cache in caches {
+                if let Some(number) = pull_request_number(&cache.git_ref) {
+                    let is_open = match open_pulls.get(&number) {
+                        Some(is_open) => *is_open,
+                        None => {
+                            let is_open = !request.is_pull_request_closed(number).await?;
+                            open_pulls.insert(number, is_open);
+                            is_open
+                        }
+                    };
+                    if is_open {
+                        continue;
+                    }
+                }
+                if !dry_run {
+                    request.delete_cache_by_id(cache.id).await?;
+                }
+                deleted.push(cache);
+            }
+
             printmd!(
-                r#"|-:|-:|
-|**Platform**|**Usage**|
-|-:|-:|
-{}
-|-:|-:|
-{}
-|-:|-:|
+                "## {} Caches",
+                if *dry_run { "Would Delete" } else { "Deleted" }
+            );
+            printmd!("**Total: {}", deleted.len());
+            print_caches(&deleted);
+        }
+        CacheSubCommand::Usage(CacheUsage { org: Some(org) }) => {
+            let request = org_request(org, auth_token)?;
+            let usage = request.get_org_cache_usage().await?;
+            let usages = request.get_org_cache_usage_by_repository().await?;
+            let repos = usages
+                .repository_cache_usages
+                .iter()
+                .map(|u| {
+                    format!(
+                        "|{}|{}|{}",
+                        u.full_name,
+                        u.active_caches_count,
+                        format_size(u.active_caches_size_in_bytes)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            printmd!("## Cache Usage");
+            printmd!("**Organization**:\t{}", org);
+            printmd!("**Active Caches**:\t{}", usage.total_active_caches_count);
+            printmd!(
+                "**Active Caches Size**:\t{}",
+                format_size(usage.total_active_caches_size_in_bytes)
+            );
+            printmd!(
+                r#"|:-:|:-:|:-:
+|**Repository**|**Active Caches**|**Size**|
+|:-|-:|-:
 {}
 |-"#,
-                get_usage_pf("MACOS", billable.MACOS),
-                get_usage_pf("UBUNTU", billable.UBUNTU),
-                get_usage_pf("WINDOWS", billable.WINDOWS)
+                repos
+            );
+        }
+        CacheSubCommand::Usage(CacheUsage { org: None }) => {
+            let request = repo_request(name, auth_token)?;
+            let usage = request.get_cache_usage().await?;
+            printmd!("## Cache Usage");
+            printmd!("**Repository**:\t{}", usage.full_name);
+            printmd!("**Active Caches**:\t{}", usage.active_caches_count);
+            printmd!(
+                "**Active Caches Size**:\t{}",
+                format_size(usage.active_caches_size_in_bytes)
             );
         }
     }
@@ -737,142 +4451,517 @@ Total workflows: {}"#,
     Ok(())
 }
 
-#[cfg(feature = "workflows")]
-async fn handle_actions_workflow_runs(workflow_runs: &WorkflowRuns) -> anyhow::Result<()> {
-    let WorkflowRuns {
+#[cfg(feature = "actions-permissions")]
+async fn print_actions_permissions(
+    request: &(dyn gh_lib::core::actions_permissions::ActionsPermissions + Send + Sync),
+) -> anyhow::Result<()> {
+    let ActionsPermissionsSettings {
+        enabled,
+        enabled_repositories,
+        allowed_actions,
+        ..
+    } = request.get_actions_permissions().await?;
+    printmd!("## Actions Permissions");
+    if let Some(enabled) = enabled {
+        printmd!("**Enabled**:\t{}", enabled);
+    }
+    if let Some(enabled_repositories) = enabled_repositories {
+        printmd!("**Enabled Repositories**:\t{}", enabled_repositories);
+    }
+    if let Some(allowed_actions) = allowed_actions {
+        printmd!("**Allowed Actions**:\t{}", allowed_actions);
+        if allowed_actions == gh_lib::core::actions_permissions::AllowedActions::Selected {
+            let SelectedActions {
+                github_owned_allowed,
+                verified_allowed,
+                patterns_allowed,
+            } = request.get_selected_actions().await?;
+            printmd!(
+                "**GitHub Owned Allowed**:\t{}",
+                github_owned_allowed.unwrap_or_default()
+            );
+            printmd!(
+                "**Verified Allowed**:\t{}",
+                verified_allowed.unwrap_or_default()
+            );
+            printmd!("**Patterns Allowed**:\t{}", patterns_allowed.join(", "));
+        }
+    }
+    let WorkflowPermissions {
+        default_workflow_permissions,
+        can_approve_pull_request_reviews,
+    } = request.get_workflow_permissions().await?;
+    printmd!(
+        "**Default Workflow Permissions**:\t{}",
+        default_workflow_permissions
+    );
+    printmd!(
+        "**Can Approve Pull Requests**:\t{}",
+        can_approve_pull_request_reviews
+    );
+    match request.get_fork_pr_approval_policy().await {
+        Ok(ForkPrApproval { approval_policy }) => {
+            printmd!("**Fork PR Approval**:\t{}", approval_policy)
+        }
+        Err(err) => printmd!("**Fork PR Approval**:\t_unavailable ({})_", err),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "actions-permissions")]
+async fn handle_actions_permissions(permissions: &Permissions) -> anyhow::Result<()> {
+    let Permissions {
         name,
         auth_token,
+        org,
         subcmd,
-    } = workflow_runs;
+    } = permissions;
 
-    let repo = RepoRequest::try_from(&name, &auth_token)?;
+    let request: Box<dyn gh_lib::core::actions_permissions::ActionsPermissions + Send + Sync> =
+        match org {
+            Some(org) => Box::new(org_request(org, auth_token)?),
+            None => Box::new(repo_request(name, auth_token)?),
+        };
 
-    match &subcmd {
-        WorkflowRunsSubCommand::List => {
-            let all_repo_runs = repo.get_all_workflow_runs().await?;
-            println!("All Repo Workflow Runs: {:#?}", all_repo_runs);
-        }
-        WorkflowRunsSubCommand::ListWorkflow(WorkflowId { workflow_id }) => {
-            let workflow_runs = repo.get_workflow_runs(*workflow_id).await?;
-            println!("Workflow Runs: {:#?}", workflow_runs);
-        }
-        WorkflowRunsSubCommand::Get(WorkflowRunId { run_id }) => {
-            let workflow_run = repo.get_a_workflow_run(*run_id).await?;
-            println!("Workflow Run: {:#?}", workflow_run);
-        }
-        WorkflowRunsSubCommand::ReRun(WorkflowRunId { run_id }) => {
-            repo.rerun_a_workflow(*run_id).await?;
-            println!("Workflow Re-Run Initiated!");
-        }
-        WorkflowRunsSubCommand::Cancel(WorkflowRunId { run_id }) => {
-            repo.cancel_a_workflow_run(*run_id).await?;
-            println!("Workflow Re-Run Initiated!");
-        }
-        WorkflowRunsSubCommand::DownloadLogs(WorkflowRunId { run_id }) => {
-            let url = repo.get_run_logs_url(*run_id).await?;
-            println!("Logs Download Url: {}", url);
-        }
-        WorkflowRunsSubCommand::DeleteLogs(WorkflowRunId { run_id }) => {
-            repo.delete_run_logs(*run_id).await?;
-            println!("Logs Deleted!");
-        }
-        WorkflowRunsSubCommand::Usage(WorkflowRunId { run_id }) => {
-            let usage = repo.get_workflow_run_usage(*run_id).await?;
-            println!("Workflow Run Usage: {:#?}", usage);
+    match subcmd {
+        PermissionsSubCommand::Show => print_actions_permissions(request.as_ref()).await?,
+        PermissionsSubCommand::Set(PermissionsSet {
+            enabled,
+            enabled_repositories,
+            allowed_actions,
+            github_owned_allowed,
+            verified_allowed,
+            patterns_allowed,
+            default_workflow_permissions,
+            can_approve_pull_requests,
+            fork_pr_approval,
+        }) => {
+            match (org, enabled, enabled_repositories) {
+                (Some(_), Some(_), _) => {
+                    anyhow::bail!("use --enabled-repositories with --org instead of --enabled")
+                }
+                (None, _, Some(_)) => {
+                    anyhow::bail!("--enabled-repositories can only be used with --org")
+                }
+                _ => {}
+            }
+            if enabled.is_some() || enabled_repositories.is_some() || allowed_actions.is_some() {
+                let current = request.get_actions_permissions().await?;
+                request
+                    .set_actions_permissions(&ActionsPermissionsSettings {
+                        enabled: enabled.or(current.enabled),
+                        enabled_repositories: enabled_repositories.or(current.enabled_repositories),
+                        allowed_actions: allowed_actions.or(current.allowed_actions),
+                        selected_actions_url: None,
+                    })
+                    .await?;
+            }
+            if github_owned_allowed.is_some()
+                || verified_allowed.is_some()
+                || !patterns_allowed.is_empty()
+            {
+                let current = request.get_selected_actions().await?;
+                request
+                    .set_selected_actions(&SelectedActions {
+                        github_owned_allowed: github_owned_allowed.or(current.github_owned_allowed),
+                        verified_allowed: verified_allowed.or(current.verified_allowed),
+                        patterns_allowed: if patterns_allowed.is_empty() {
+                            current.patterns_allowed
+                        } else {
+                            patterns_allowed.clone()
+                        },
+                    })
+                    .await?;
+            }
+            if default_workflow_permissions.is_some() || can_approve_pull_requests.is_some() {
+                let current = request.get_workflow_permissions().await?;
+                request
+                    .set_workflow_permissions(&WorkflowPermissions {
+                        default_workflow_permissions: default_workflow_permissions
+                            .unwrap_or(current.default_workflow_permissions),
+                        can_approve_pull_request_reviews: can_approve_pull_requests
+                            .unwrap_or(current.can_approve_pull_request_reviews),
+                    })
+                    .await?;
+            }
+            if let Some(approval_policy) = fork_pr_approval {
+                request
+                    .set_fork_pr_approval_policy(&ForkPrApproval {
+                        approval_policy: *approval_policy,
+                    })
+                    .await?;
+            }
+            println!("{}", "Permissions update successful!".bold().green());
+            print_actions_permissions(request.as_ref()).await?;
         }
     }
 
     Ok(())
 }
 
-#[cfg(feature = "workflows")]
-async fn handle_actions_workflow_jobs(workflow_jobs: &WorkflowJobs) -> anyhow::Result<()> {
-    let WorkflowJobs {
-        name,
-        auth_token,
-        subcmd,
-    } = workflow_jobs;
+#[cfg(feature = "billing")]
+async fn get_repo_usage(
+    repo_addr: &str,
+    auth_token: &str,
+    since: Option<std::time::Duration>,
+) -> anyhow::Result<Vec<gh_lib::analytics::UsageEntry>> {
+    use futures::stream::{self, StreamExt as _};
+    use gh_lib::analytics::{created_since, UsageEntry};
 
-    let repo = RepoRequest::try_from(&name, &auth_token)?;
+    const CONCURRENCY: usize = 8;
 
-    match &subcmd {
-        WorkflowJobsSubCommand::List(WorkflowRunId { run_id }) => {
-            let jobs = repo.get_workflow_run_jobs(*run_id).await?;
-            println!("Workflow Run Jobs: {:#?}", jobs);
+    let repo = repo_request(repo_addr, auth_token)?;
+    let repo = &repo;
+    let workflows = repo.get_all_workflows().await?.workflows;
+
+    let entries = match since {
+        None => {
+            stream::iter(workflows)
+                .map(|workflow| async move {
+                    let WorkflowUsage { billable } = repo.get_workflow_usage(workflow.id).await?;
+                    let ms = |timing: Option<WorkflowUsageTiming>| {
+                        timing.map_or(0, |t| u64::from(t.total_ms))
+                    };
+                    Ok::<_, anyhow::Error>(UsageEntry {
+                        repo: repo_addr.to_owned(),
+                        workflow: workflow.name,
+                        ubuntu_ms: ms(billable.UBUNTU),
+                        windows_ms: ms(billable.WINDOWS),
+                        macos_ms: ms(billable.MACOS),
+                    })
+                })
+                .buffer_unordered(CONCURRENCY)
+                .collect::<Vec<_>>()
+                .await
         }
-        WorkflowJobsSubCommand::Get(WorkflowJobId { job_id }) => {
-            let job = repo.get_a_workflow_run_job(*job_id).await?;
-            println!("Workflow Rub Job: {:#?}", job);
+        Some(since) => {
+            let names = workflows
+                .into_iter()
+                .map(|w| (w.id, w.name))
+                .collect::<std::collections::HashMap<_, _>>();
+            let names = &names;
+            let created = created_since(time_ago(since)?);
+            let params = WorkflowRunQueryParams {
+                status: Some("completed"),
+                created: Some(&created),
+                ..Default::default()
+            };
+            let runs = get_workflow_run_pages(repo, None, params, None).await?;
+            stream::iter(runs)
+                .map(|run| async move {
+                    let usage = repo.get_workflow_run_usage(run.id).await?;
+                    Ok::<_, anyhow::Error>(UsageEntry {
+                        repo: repo_addr.to_owned(),
+                        workflow: names
+                            .get(&run.workflow_id)
+                            .cloned()
+                            .unwrap_or_else(|| run.workflow_id.to_string()),
+                        ubuntu_ms: usage.billable.UBUNTU.total_ms.into(),
+                        windows_ms: usage.billable.WINDOWS.total_ms.into(),
+                        macos_ms: usage.billable.MACOS.total_ms.into(),
+                    })
+                })
+                .buffer_unordered(CONCURRENCY)
+                .collect::<Vec<_>>()
+                .await
         }
-        WorkflowJobsSubCommand::DownloadLogs(WorkflowJobId { job_id }) => {
-            let url = repo.get_job_logs_url(*job_id).await?;
-            println!("Logs Download Url: {}", url);
+    };
+    entries.into_iter().collect()
+}
+
+#[cfg(feature = "billing")]
+async fn handle_actions_usage(usage: &Usage) -> anyhow::Result<()> {
+    use gh_lib::analytics::{UsageReport, OS_MULTIPLIERS};
+    use gh_lib::core::billing::Billing as _;
+    use gh_lib::core::orgs::OrgRepositories as _;
+
+    let Usage {
+        name,
+        auth_token,
+        org,
+        since,
+        top,
+        csv,
+    } = usage;
+
+    let (repos, billing) = match org {
+        Some(org) => {
+            let request = org_request(org, auth_token)?;
+            let repos = request
+                .get_all_repositories()
+                .await?
+                .into_iter()
+                .filter(|r| !r.archived)
+                .map(|r| r.full_name)
+                .collect::<Vec<_>>();
+            (repos, Some(request.get_actions_billing().await))
+        }
+        None => (vec![name.to_owned()], None),
+    };
+
+    let mut entries = Vec::new();
+    for repo in &repos {
+        match get_repo_usage(repo, auth_token, *since).await {
+            Ok(repo_entries) => entries.extend(repo_entries),
+            Err(err) if org.is_some() => {
+                eprintln!("{} skipped {}: {}", "warning:".bold().yellow(), repo, err)
+            }
+            Err(err) => return Err(err),
         }
     }
+    let report = UsageReport::from(entries);
+
+    if *csv {
+        print!("{}", report.to_csv());
+        return Ok(());
+    }
+
+    let total = report.total();
+    let platforms = OS_MULTIPLIERS
+        .iter()
+        .zip(total.minutes().iter())
+        .map(|((os, multiplier), minutes)| {
+            format!(
+                "|{}|{}|x{}|{}",
+                os,
+                minutes,
+                multiplier,
+                minutes * multiplier
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    printmd!(
+        "## Actions Usage ({})",
+        since.map_or_else(
+            || "current billing cycle".to_owned(),
+            |_| "selected period".to_owned()
+        )
+    );
+    printmd!("**Repositories**:\t{}", repos.len());
+    printmd!(
+        r#"|:-:|:-:|:-:|:-:
+|**Platform**|**Minutes**|**Multiplier**|**Billable**|
+|:-|-:|-:|-:
+{}
+|-"#,
+        platforms
+    );
+    printmd!("**Total Billable Minutes**:\t{}", total.billable_minutes());
+
+    let consumers = report
+        .top_consumers(*top)
+        .iter()
+        .map(|e| {
+            let [ubuntu, windows, macos] = e.minutes();
+            format!(
+                "|{}|{}|{}|{}|{}|{}",
+                e.repo,
+                e.workflow,
+                ubuntu,
+                windows,
+                macos,
+                e.billable_minutes()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    printmd!("## Top {} Consumers", top);
+    printmd!(
+        r#"|:-:|:-:|:-:|:-:|:-:|:-:
+|**Repository**|**Workflow**|**Ubuntu**|**Windows**|**macOS**|**Billable**|
+|:-|:-|-:|-:|-:|-:
+{}
+|-"#,
+        consumers
+    );
 
+    match billing {
+        Some(Ok(billing)) => {
+            printmd("## Organization Billing");
+            printmd!("**Minutes Used**:\t{}", billing.total_minutes_used);
+            printmd!(
+                "**Paid Minutes Used**:\t{}",
+                billing.total_paid_minutes_used
+            );
+            printmd!("**Included Minutes**:\t{}", billing.included_minutes);
+            for (runner, minutes) in &billing.minutes_used_breakdown {
+                printmd!("**{}**:\t{}", runner, minutes);
+            }
+        }
+        Some(Err(err)) => eprintln!(
+            "{} organization billing unavailable: {}",
+            "warning:".bold().yellow(),
+            err
+        ),
+        None => {}
+    }
     Ok(())
 }
 
-#[cfg(feature = "secrets")]
-async fn handle_actions_secrets(secrets: &Secrets) -> anyhow::Result<()> {
-    let Secrets {
+#[cfg(feature = "analytics")]
+fn format_secs(secs: Option<u64>) -> String {
+    match secs {
+        Some(secs) if secs >= 60 => format!("{}m {:02}s", secs / 60, secs % 60),
+        Some(secs) => format!("{}s", secs),
+        None => "-".into(),
+    }
+}
+
+#[cfg(feature = "analytics")]
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio.map_or_else(|| "-".into(), |ratio| format!("{:.1}%", ratio * 100.0))
+}
+
+#[cfg(feature = "analytics")]
+async fn handle_actions_stats(stats: &Stats) -> anyhow::Result<()> {
+    use futures::stream::{self, StreamExt as _};
+    use gh_lib::analytics::{analyze, created_since, RunAnalytics, RunRecord};
+
+    let Stats {
         name,
         auth_token,
-        subcmd,
-    } = secrets;
+        workflow,
+        branch,
+        since,
+        limit,
+        json,
+    } = stats;
 
-    let repo = RepoRequest::try_from(&name, &auth_token)?;
+    #[cfg(feature = "config")]
+    let json = *json || config::current().is_some_and(config::Config::json_output);
+    #[cfg(not(feature = "config"))]
+    let json = *json;
 
-    match &subcmd {
-        SecretsSubCommand::List => {
-            let SecretListResponse {
-                total_count,
-                secrets,
-            } = repo.get_all_secrets().await?;
-            let secrets = secrets
-                .iter()
-                .map(|s| format!("|{}|{}|{}", s.name, s.created_at, s.updated_at))
-                .collect::<Vec<_>>()
-                .join("\n");
-            printmd("## Secrets");
-            printmd!("**Total: {}", total_count);
-            printmd!(
-                r#"|:-:|:-:|:-:
-|**Name**|**Created At**|**Updated At**|
-|-:|:-:|:-
+    let repo = repo_request(name, auth_token)?;
+    let workflow_id = match workflow {
+        Some(workflow) => Some(resolve_workflow_id(&repo, workflow).await?),
+        None => None,
+    };
+    let workflow_names = repo
+        .get_all_workflows()
+        .await?
+        .workflows
+        .into_iter()
+        .map(|w| (w.id, w.name))
+        .collect();
+    let created = created_since(time_ago(*since)?);
+    let params = WorkflowRunQueryParams {
+        branch: branch.as_deref(),
+        status: Some("completed"),
+        created: Some(&created),
+        ..Default::default()
+    };
+    let runs = get_workflow_run_pages(&repo, workflow_id, params, Some(*limit)).await?;
+    let repo = &repo;
+    let records = stream::iter(runs)
+        .map(|run| async move {
+            // Reruns included, to tell the jobs that failed and then passed
+            let jobs = repo.get_all_workflow_run_jobs(run.id).await?;
+            Ok::<_, anyhow::Error>(RunRecord::from(&run, &jobs))
+        })
+        .buffer_unordered(8)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let RunAnalytics {
+        workflows,
+        mut jobs,
+        mut steps,
+    } = analyze(&records, &workflow_names);
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&RunAnalytics {
+                workflows,
+                jobs,
+                steps
+            })?
+        );
+        return Ok(());
+    }
+
+    let workflows = workflows
+        .iter()
+        .map(|w| {
+            format!(
+                "|{}|{}|{}|{}|{}|{}|{}",
+                w.name,
+                w.runs,
+                format_ratio(w.success_rate),
+                format_secs(w.duration.p50_secs),
+                format_secs(w.duration.p90_secs),
+                format_secs(w.queue.p50_secs),
+                format_ratio(w.flakiness)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    printmd!("## Workflow Stats ({} runs)", records.len());
+    printmd!(
+        r#"|:-:|:-:|:-:|:-:|:-:|:-:|:-:
+|**Workflow**|**Runs**|**Success**|**p50**|**p90**|**Queue p50**|**Flaky**|
+|:-|-:|-:|-:|-:|-:|-:
 {}
 |-"#,
-                secrets
-            );
-        }
-        SecretsSubCommand::Get(SecretsName { name }) => {
-            let Secret {
-                name,
-                created_at,
-                updated_at,
-            } = repo.get_a_secret(&name).await?;
-            printmd!("## Secret");
-            printmd!("**Name**:\t{}", name);
-            printmd!("**Created At**:\t{}", created_at);
-            printmd!("**Updated At**:\t{}", updated_at);
-        }
-        #[cfg(feature = "secrets-save")]
-        SecretsSubCommand::Add(name_value)
-        | SecretsSubCommand::Update(name_value)
-        | SecretsSubCommand::Edit(name_value) => {
-            let SecretsNameValue { name, value } = name_value;
-            repo.save_secret(&name, &value).await?;
-            println!(
-                "{}",
-                format!("Secret {} successful!", &subcmd).bold().green()
-            );
-        }
-        SecretsSubCommand::Delete(SecretsName { name }) => {
-            repo.delete_a_secret(&name).await?;
-            println!("{}", "Secret delete successful!".bold().green());
-        }
-    }
+        workflows
+    );
+
+    jobs.sort_by_key(|j| std::cmp::Reverse(j.duration.p90_secs));
+    let jobs = jobs
+        .iter()
+        .map(|j| {
+            format!(
+                "|{}|{}|{}|{}|{}|{}|{}",
+                j.workflow,
+                j.name,
+                j.runs,
+                format_ratio(j.success_rate),
+                format_secs(j.duration.p50_secs),
+                format_secs(j.duration.p90_secs),
+                format_ratio(j.flakiness)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    printmd("## Job Stats");
+    printmd!(
+        r#"|:-:|:-:|:-:|:-:|:-:|:-:|:-:
+|**Workflow**|**Job**|**Runs**|**Success**|**p50**|**p90**|**Flaky**|
+|:-|:-|-:|-:|-:|-:|-:
+{}
+|-"#,
+        jobs
+    );
 
+    const TOP_STEPS: usize = 10;
+    steps.sort_by_key(|s| std::cmp::Reverse(s.duration.p90_secs));
+    let steps = steps
+        .iter()
+        .take(TOP_STEPS)
+        .map(|s| {
+            format!(
+                "|{}|{}|{}|{}",
+                s.job,
+                s.name,
+                format_secs(s.duration.p50_secs),
+                format_secs(s.duration.p90_secs)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    printmd!("## Slowest {} Steps", TOP_STEPS);
+    printmd!(
+        r#"|:-:|:-:|:-:|:-:
+|**Job**|**Step**|**p50**|**p90**|
+|:-|:-|-:|-:
+{}
+|-"#,
+        steps
+    );
     Ok(())
 }
 
@@ -880,25 +4969,80 @@ async fn handle_actions_secrets(secrets: &Secrets) -> anyhow::Result<()> {
 async fn main() -> anyhow::Result<()> {
     #[cfg(feature = "config")]
     {
-        if let Some(config::Config { access_token, .. }) = config::get_config().await {
-            const GH_ACCESS_TOKEN: &str = "GH_ACCESS_TOKEN";
-            if std::env::var(GH_ACCESS_TOKEN).is_err() {
-                std::env::set_var(GH_ACCESS_TOKEN, access_token);
-            } else {
-                eprint!(
-                    "{} {}\n{}\n",
+        let config = match config::load_config().await {
+            Ok(config) => config.unwrap_or_default(),
+            Err(err) => {
+                eprintln!(
+                    "{} {}",
                     "warning: ".bold().yellow(),
-                    "using $GH_ACCESS_TOKEN from env, ignored token in config file!".yellow(),
-                    "run `unset GH_ACCESS_TOKEN` if this is not intentional.".dark_yellow()
+                    err.to_string().yellow()
                 );
+                config::Config::default()
             }
+        };
+        output::configure(config.color(), config.pager());
+        config::init(config);
+        accounts::connect(&TARGET_HOST);
+    }
+    #[cfg(feature = "config")]
+    let args = {
+        let args = std::env::args_os()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let aliases = config::current()
+            .map(|config| config.aliases.to_owned())
+            .unwrap_or_default();
+        match aliases::expand(args, &aliases, &builtin_commands())? {
+            aliases::Expansion::Args(args) => args,
+            aliases::Expansion::Shell {
+                name,
+                command,
+                args,
+            } => std::process::exit(aliases::run_shell(&name, &command, &args).await?),
+        }
+    };
+    #[cfg(not(feature = "config"))]
+    let args = std::env::args_os();
+    #[cfg(feature = "config")]
+    if std::env::var_os("GH_ACCESS_TOKEN").is_some()
+        && config::current().is_none_or(|config| config.credential_helper.is_none())
+        && credentials::get_credentials_path().is_some_and(|path| path.exists())
+    {
+        eprint!(
+            "{} {}\n{}\n",
+            "warning: ".bold().yellow(),
+            "using $GH_ACCESS_TOKEN from env, ignored stored token!".yellow(),
+            "run `unset GH_ACCESS_TOKEN` if this is not intentional.".dark_yellow()
+        );
+    }
+    #[cfg(feature = "extensions")]
+    {
+        let command = args
+            .get(1)
+            .filter(|command| !command.starts_with('-') && !builtin_commands().contains(command));
+        let extension = match (command, extensions::Extensions::from_env()) {
+            (Some(command), Ok(extensions)) => extensions.find(command),
+            _ => None,
+        };
+        if let Some(extension) = extension {
+            std::process::exit(run_extension(&extension, &args[2..]).await?);
         }
     }
-    let opts: Opts = Opts::parse();
+    let opts: Opts = Opts::parse_from(args);
 
     match opts.subcmd {
         #[cfg(feature = "login")]
-        SubCommand::Login => handle_login().await?,
+        SubCommand::Login(login) => handle_login(&login).await?,
+        #[cfg(feature = "auth")]
+        SubCommand::Auth(auth) => handle_auth(&auth).await?,
+        #[cfg(feature = "app")]
+        SubCommand::App(app) => handle_app(&app).await?,
+        #[cfg(feature = "config")]
+        SubCommand::Config(config) => handle_config(&config).await?,
+        #[cfg(feature = "config")]
+        SubCommand::Alias(alias) => handle_alias(&alias).await?,
+        #[cfg(feature = "extensions")]
+        SubCommand::Extension(extension) => handle_extension(&extension).await?,
         #[cfg(feature = "basic-info")]
         SubCommand::Repo(repo) => match repo.subcmd {
             RepoSubCommand::View(repo_view) => handle_repo_view(&repo_view).await?,
@@ -916,11 +5060,21 @@ async fn main() -> anyhow::Result<()> {
             );
             handle_actions_secrets(&secrets).await?
         }
-        #[cfg(any(feature = "secrets", feature = "workflows"))]
+        #[cfg(any(
+            feature = "secrets",
+            feature = "variables",
+            feature = "runners",
+            feature = "caches",
+            feature = "actions-permissions",
+            feature = "analytics",
+            feature = "workflows"
+        ))]
         SubCommand::Actions(actions) => match actions.subcmd {
             #[cfg(feature = "workflows")]
             ActionsSubCommand::Workflows(workflows) => handle_actions_workflows(&workflows).await?,
             #[cfg(feature = "workflows")]
+            ActionsSubCommand::Summary(summary) => handle_actions_summary(&summary).await?,
+            #[cfg(feature = "workflows")]
             ActionsSubCommand::Runs(workflow_runs) => {
                 handle_actions_workflow_runs(&workflow_runs).await?
             }
@@ -930,8 +5084,75 @@ async fn main() -> anyhow::Result<()> {
             }
             #[cfg(feature = "secrets")]
             ActionsSubCommand::Secrets(secrets) => handle_actions_secrets(&secrets).await?,
+            #[cfg(feature = "variables")]
+            ActionsSubCommand::Variables(variables) => handle_actions_variables(&variables).await?,
+            #[cfg(feature = "runners")]
+            ActionsSubCommand::Runners(runners) => handle_actions_runners(&runners).await?,
+            #[cfg(feature = "caches")]
+            ActionsSubCommand::Cache(cache) => handle_actions_cache(&cache).await?,
+            #[cfg(feature = "actions-permissions")]
+            ActionsSubCommand::Permissions(permissions) => {
+                handle_actions_permissions(&permissions).await?
+            }
+            #[cfg(feature = "analytics")]
+            ActionsSubCommand::Stats(stats) => handle_actions_stats(&stats).await?,
+            #[cfg(feature = "billing")]
+            ActionsSubCommand::Usage(usage) => handle_actions_usage(&usage).await?,
         },
     }
 
     Ok(())
 }
+
+#[cfg(all(
+    test,
+    feature = "auth",
+    feature = "variables",
+    feature = "runners",
+    feature = "actions-permissions",
+    feature = "billing",
+    feature = "caches"
+))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_scopes_of_existing_commands() {
+        for (subcmd, _) in REQUIRED_SCOPES {
+            let command = subcmd.split(' ').next().unwrap_or_default();
+            assert!(
+                !command_exists(command) || command_exists(subcmd),
+                "no command `{}`",
+                subcmd
+            );
+        }
+        assert!(command_exists("actions runners --org"));
+        assert!(!command_exists("actions stats --org"));
+        assert!(!command_exists("actions nothing"));
+    }
+
+    #[test]
+    fn parse_ages() {
+        assert_eq!(
+            parse_age("2w").ok(),
+            Some(std::time::Duration::from_secs(14 * 24 * 60 * 60))
+        );
+        assert!(parse_age("2y").is_err());
+        assert!(parse_age("d").is_err());
+        // Overflowing the seconds, or reaching before 1970.
+        assert!(parse_age("30500568904943w").is_err());
+        assert!(parse_age("100000w").is_err());
+    }
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!(parse_size("500").ok(), Some(500));
+        assert_eq!(parse_size("500b").ok(), Some(500));
+        assert_eq!(parse_size("2k").ok(), Some(2048));
+        assert_eq!(parse_size("100MB").ok(), Some(100 << 20));
+        assert!(parse_size("1KBB").is_err());
+        assert!(parse_size("1T").is_err());
+        assert!(parse_size("G").is_err());
+        assert!(parse_size("99999999999G").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use crossterm::style::{style, Attribute, Color, StyledContent};
+use crossterm::tty::IsTty;
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+const AUTO: u8 = 0;
+const ALWAYS: u8 = 1;
+const NEVER: u8 = 2;
+
+static COLOR: AtomicU8 = AtomicU8::new(AUTO);
+
+lazy_static::lazy_static! {
+    static ref PAGER: Mutex<Option<String>> = Mutex::new(None);
+    static ref ANSI_ESCAPE: regex::Regex = regex::Regex::new("\x1b\\[[0-9;]*[A-Za-z]").unwrap();
+}
+
+/// Applies the `color` and `pager` settings to the rest of the output.
+#[cfg_attr(not(feature = "config"), allow(dead_code))]
+pub(crate) fn configure(color: &str, pager: Option<&str>) {
+    let mode = match color {
+        "always" => ALWAYS,
+        "never" => NEVER,
+        _ => AUTO,
+    };
+    COLOR.store(mode, Ordering::Relaxed);
+    *PAGER.lock().unwrap() = pager.map(ToOwned::to_owned);
+}
+
+/// Whether to style the output, `auto` only when it is a terminal and `$NO_COLOR` is unset.
+pub(crate) fn color_enabled() -> bool {
+    lazy_static::lazy_static! {
+        static ref AUTO_COLOR: bool =
+            io::stdout().is_tty() && std::env::var_os("NO_COLOR").is_none();
+    }
+    match COLOR.load(Ordering::Relaxed) {
+        ALWAYS => true,
+        NEVER => false,
+        _ => *AUTO_COLOR,
+    }
+}
+
+/// Command paging the output, the `pager` setting else `$PAGER`, none when empty or `cat`.
+fn pager() -> Option<String> {
+    PAGER
+        .lock()
+        .unwrap()
+        .clone()
+        .or_else(|| std::env::var("PAGER").ok())
+        .map(|pager| pager.trim().to_owned())
+        .filter(|pager| !pager.is_empty() && pager != "cat")
+}
+
+fn strip_ansi(text: &str) -> std::borrow::Cow<'_, str> {
+    ANSI_ESCAPE.replace_all(text, "")
+}
+
+/// Prints `text`, through the pager when it is longer than the terminal.
+pub(crate) fn print_long(text: &str) {
+    let text = if color_enabled() {
+        text.into()
+    } else {
+        strip_ansi(text)
+    };
+    let overflows = io::stdout().is_tty()
+        && crossterm::terminal::size()
+            .map(|(_, height)| text.lines().count() >= height as usize)
+            .unwrap_or(false);
+    if overflows {
+        if let Some(pager) = pager() {
+            match page(&pager, &text) {
+                Ok(()) => return,
+                Err(err) => eprintln!("warning: unable to run the pager `{}`, {}", pager, err),
+            }
+        }
+    }
+    print!("{}", text);
+}
+
+fn page(pager: &str, text: &str) -> io::Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let mut child = command.arg(pager).stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager early closes its input
+        match stdin.write_all(text.as_bytes()) {
+            Err(err) if err.kind() != io::ErrorKind::BrokenPipe => return Err(err),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Styled content printed without its style when the color is disabled.
+pub(crate) struct Styled<D: Display + Clone>(pub(crate) StyledContent<D>);
+
+impl<D: Display + Clone> Display for Styled<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if color_enabled() {
+            self.0.fmt(f)
+        } else {
+            self.0.content().fmt(f)
+        }
+    }
+}
+
+macro_rules! styles {
+    ($($name:ident => $apply:expr,)*) => {
+        /// Stand-in for crossterm's `Colorize` and `Styler` following the `color` setting.
+        #[allow(dead_code)]
+        pub(crate) trait Stylize: Display + Clone + Sized {
+            $(
+                fn $name(self) -> Styled<Self> {
+                    Styled(style(self)).$name()
+                }
+            )*
+        }
+
+        // Some styles are only used by some features
+        #[allow(dead_code)]
+        impl<D: Display + Clone> Styled<D> {
+            $(
+                pub(crate) fn $name(self) -> Self {
+                    let apply: fn(StyledContent<D>) -> StyledContent<D> = $apply;
+                    Styled(apply(self.0))
+                }
+            )*
+        }
+    };
+}
+
+styles! {
+    bold => |content| content.attribute(Attribute::Bold),
+    red => |content| content.with(Color::Red),
+    green => |content| content.with(Color::Green),
+    yellow => |content| content.with(Color::Yellow),
+    dark_green => |content| content.with(Color::DarkGreen),
+    dark_yellow => |content| content.with(Color::DarkYellow),
+    dark_grey => |content| content.with(Color::DarkGrey),
+}
+
+impl<D: Display + Clone> Stylize for D {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn styles_follow_the_color_setting() {
+        configure("always", None);
+        assert_eq!(
+            "ok".green().bold().to_string(),
+            "\x1b[38;5;10m\x1b[1mok\x1b[0m"
+        );
+        configure("never", None);
+        assert_eq!("ok".green().bold().to_string(), "ok");
+        assert_eq!(strip_ansi("\x1b[38;5;10m\x1b[1mok\x1b[0m"), "ok");
+        configure("auto", None);
+    }
+}
@@ -0,0 +1,658 @@
+#![cfg(feature = "analytics")]
+//! Duration, success rate and flakiness statistics over completed workflow runs.
+//!
+//! Works on plain records so it can be fed from the API as well as from fixtures.
+use crate::core::workflow_jobs::{WorkflowRunJob, WorkflowRunJobStep};
+use crate::core::workflow_runs::WorkflowRun;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Conclusions counted as an outcome of the code under test, `cancelled` or `skipped` runs are not.
+const DECISIVE_CONCLUSIONS: [&str; 3] = ["success", "failure", "timed_out"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunRecord {
+    pub workflow_id: u32,
+    pub head_sha: String,
+    pub conclusion: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub jobs: Vec<JobRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRecord {
+    pub name: String,
+    /// Attempt of the run the job belongs to, reruns of the run starting at 2.
+    pub attempt: u32,
+    pub conclusion: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub steps: Vec<StepRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepRecord {
+    pub name: String,
+    pub conclusion: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+impl RunRecord {
+    pub fn from(run: &WorkflowRun, jobs: &[WorkflowRunJob]) -> Self {
+        RunRecord {
+            workflow_id: run.workflow_id,
+            head_sha: run.head_sha.to_owned(),
+            conclusion: run.conclusion.to_owned(),
+            created_at: run.created_at,
+            updated_at: run.updated_at,
+            jobs: jobs.iter().map(JobRecord::from).collect(),
+        }
+    }
+
+    /// Jobs of the latest attempt, the one the conclusion of the run is about.
+    fn latest_jobs(&self) -> impl Iterator<Item = &JobRecord> {
+        let latest = self
+            .jobs
+            .iter()
+            .map(|j| j.attempt)
+            .max()
+            .unwrap_or_default();
+        self.jobs.iter().filter(move |j| j.attempt == latest)
+    }
+
+    /// Wall clock time from the first job start to the last job completion of the latest attempt.
+    fn duration_secs(&self) -> u64 {
+        let started_at = self.latest_jobs().map(|j| j.started_at).min();
+        let completed_at = self.latest_jobs().map(|j| j.completed_at).max();
+        match (started_at, completed_at) {
+            (Some(started_at), Some(completed_at)) => secs_between(started_at, completed_at),
+            _ => secs_between(self.created_at, self.updated_at),
+        }
+    }
+
+    /// Time the run waited for its first job to be picked up by a runner.
+    fn queue_secs(&self) -> Option<u64> {
+        let started_at = self.jobs.iter().map(|j| j.started_at).min()?;
+        Some(secs_between(self.created_at, started_at))
+    }
+}
+
+impl JobRecord {
+    fn from(job: &WorkflowRunJob) -> Self {
+        JobRecord {
+            name: job.name.to_owned(),
+            attempt: job.run_attempt.unwrap_or(1),
+            conclusion: job.conclusion.to_owned(),
+            started_at: job.started_at,
+            completed_at: job.completed_at,
+            steps: job.steps.iter().map(StepRecord::from).collect(),
+        }
+    }
+}
+
+impl StepRecord {
+    fn from(step: &WorkflowRunJobStep) -> Self {
+        StepRecord {
+            name: step.name.to_owned(),
+            conclusion: step.conclusion.to_owned(),
+            started_at: step.started_at,
+            completed_at: step.completed_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct DurationStats {
+    pub count: usize,
+    pub p50_secs: Option<u64>,
+    pub p90_secs: Option<u64>,
+}
+
+impl DurationStats {
+    pub fn from(mut durations: Vec<u64>) -> Self {
+        durations.sort_unstable();
+        DurationStats {
+            count: durations.len(),
+            p50_secs: percentile(&durations, 50.0),
+            p90_secs: percentile(&durations, 90.0),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct WorkflowStats {
+    pub workflow_id: u32,
+    pub name: String,
+    pub runs: usize,
+    /// Share of successful runs among the runs with a decisive conclusion.
+    pub success_rate: Option<f64>,
+    pub duration: DurationStats,
+    pub queue: DurationStats,
+    /// Commits having a failed run followed by a successful one.
+    pub flaky_commits: usize,
+    /// Share of the tested commits that were flaky.
+    pub flakiness: Option<f64>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct JobStats {
+    pub workflow: String,
+    pub name: String,
+    /// Runs of the job, each attempt of a rerun workflow run counting as one.
+    pub runs: usize,
+    pub success_rate: Option<f64>,
+    pub duration: DurationStats,
+    /// Commits the job failed on and then passed, in a later run or a rerun of the same run.
+    pub flaky_commits: usize,
+    /// Share of the commits the job tested that were flaky.
+    pub flakiness: Option<f64>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct StepStats {
+    pub workflow: String,
+    pub job: String,
+    pub name: String,
+    pub duration: DurationStats,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct RunAnalytics {
+    pub workflows: Vec<WorkflowStats>,
+    pub jobs: Vec<JobStats>,
+    pub steps: Vec<StepStats>,
+}
+
+/// Nearest-rank percentile of already sorted values.
+pub fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.max(1).min(sorted.len()) - 1])
+}
+
+/// `created` query filter of the workflow runs API for runs created since `time`.
+pub fn created_since(time: std::time::SystemTime) -> String {
+    format!(">={}", DateTime::<Utc>::from(time).format("%Y-%m-%d"))
+}
+
+/// Computes the statistics of `runs`, `workflow_names` maps workflow ids to display names.
+pub fn analyze(runs: &[RunRecord], workflow_names: &HashMap<u32, String>) -> RunAnalytics {
+    let workflow_name = |workflow_id: u32| {
+        workflow_names
+            .get(&workflow_id)
+            .cloned()
+            .unwrap_or_else(|| workflow_id.to_string())
+    };
+
+    let mut by_workflow: BTreeMap<u32, Vec<&RunRecord>> = BTreeMap::new();
+    for run in runs {
+        by_workflow.entry(run.workflow_id).or_default().push(run);
+    }
+
+    let mut analytics = RunAnalytics::default();
+    for (workflow_id, runs) in by_workflow {
+        let name = workflow_name(workflow_id);
+        let (flaky_commits, commits) =
+            count_flaky_commits(runs.iter().filter_map(|r| {
+                Some((r.head_sha.as_str(), r.created_at, r.conclusion.as_deref()?))
+            }));
+        analytics.workflows.push(WorkflowStats {
+            workflow_id,
+            name: name.to_owned(),
+            runs: runs.len(),
+            success_rate: success_rate(runs.iter().filter_map(|r| r.conclusion.as_deref())),
+            duration: DurationStats::from(runs.iter().map(|r| r.duration_secs()).collect()),
+            queue: DurationStats::from(runs.iter().filter_map(|r| r.queue_secs()).collect()),
+            flaky_commits,
+            flakiness: ratio(flaky_commits, commits),
+        });
+
+        let mut jobs: BTreeMap<&str, Vec<(&str, &JobRecord)>> = BTreeMap::new();
+        for run in &runs {
+            for job in &run.jobs {
+                jobs.entry(&job.name)
+                    .or_default()
+                    .push((&run.head_sha, job));
+            }
+        }
+        for (job_name, jobs) in jobs {
+            let (flaky_commits, commits) = count_flaky_commits(
+                jobs.iter()
+                    .map(|(sha, j)| (*sha, j.started_at, j.conclusion.as_str())),
+            );
+            let jobs = jobs.into_iter().map(|(_, j)| j).collect::<Vec<_>>();
+            analytics.jobs.push(JobStats {
+                workflow: name.to_owned(),
+                name: job_name.to_owned(),
+                runs: jobs.len(),
+                success_rate: success_rate(jobs.iter().map(|j| j.conclusion.as_str())),
+                duration: DurationStats::from(
+                    jobs.iter()
+                        .map(|j| secs_between(j.started_at, j.completed_at))
+                        .collect(),
+                ),
+                flaky_commits,
+                flakiness: ratio(flaky_commits, commits),
+            });
+
+            let mut steps: BTreeMap<&str, Vec<u64>> = BTreeMap::new();
+            for step in jobs.iter().flat_map(|j| &j.steps) {
+                if step.conclusion != "skipped" {
+                    steps
+                        .entry(&step.name)
+                        .or_default()
+                        .push(secs_between(step.started_at, step.completed_at));
+                }
+            }
+            for (step_name, durations) in steps {
+                analytics.steps.push(StepStats {
+                    workflow: name.to_owned(),
+                    job: job_name.to_owned(),
+                    name: step_name.to_owned(),
+                    duration: DurationStats::from(durations),
+                });
+            }
+        }
+    }
+    analytics
+}
+
+/// Counts the commits that failed and then passed on a later try, along with the tested commits,
+/// from the `(head_sha, time, conclusion)` of each try.
+fn count_flaky_commits<'a>(
+    outcomes: impl Iterator<Item = (&'a str, DateTime<Utc>, &'a str)>,
+) -> (usize, usize) {
+    let mut by_sha: HashMap<&str, Vec<(DateTime<Utc>, &str)>> = HashMap::new();
+    for (sha, time, conclusion) in outcomes {
+        if is_decisive(conclusion) {
+            by_sha.entry(sha).or_default().push((time, conclusion));
+        }
+    }
+    let mut flaky = 0;
+    for outcomes in by_sha.values_mut() {
+        outcomes.sort_by_key(|(time, _)| *time);
+        let first_failure = outcomes
+            .iter()
+            .position(|(_, conclusion)| *conclusion != "success");
+        if let Some(idx) = first_failure {
+            if outcomes[idx..]
+                .iter()
+                .any(|(_, conclusion)| *conclusion == "success")
+            {
+                flaky += 1;
+            }
+        }
+    }
+    (flaky, by_sha.len())
+}
+
+fn success_rate<'a>(conclusions: impl Iterator<Item = &'a str>) -> Option<f64> {
+    let (success, total) = conclusions
+        .filter(|c| is_decisive(c))
+        .fold((0, 0), |(success, total), c| {
+            (success + (c == "success") as usize, total + 1)
+        });
+    ratio(success, total)
+}
+
+fn is_decisive(conclusion: &str) -> bool {
+    DECISIVE_CONCLUSIONS.contains(&conclusion)
+}
+
+fn ratio(count: usize, total: usize) -> Option<f64> {
+    if total == 0 {
+        None
+    } else {
+        Some(count as f64 / total as f64)
+    }
+}
+
+pub(crate) fn secs_between(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+    (to - from).num_seconds().max(0) as u64
+}
+
+/// Billable minute multipliers of the GitHub hosted runners per operating system.
+pub const OS_MULTIPLIERS: [(&str, u64); 3] = [("UBUNTU", 1), ("WINDOWS", 2), ("MACOS", 10)];
+
+/// Billable time of a workflow, per operating system.
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct UsageEntry {
+    pub repo: String,
+    pub workflow: String,
+    pub ubuntu_ms: u64,
+    pub windows_ms: u64,
+    pub macos_ms: u64,
+}
+
+impl UsageEntry {
+    /// Raw minutes per operating system, in the order of `OS_MULTIPLIERS`.
+    pub fn minutes(&self) -> [u64; 3] {
+        [
+            ms_to_minutes(self.ubuntu_ms),
+            ms_to_minutes(self.windows_ms),
+            ms_to_minutes(self.macos_ms),
+        ]
+    }
+
+    /// Minutes weighted by the operating system multipliers.
+    pub fn billable_minutes(&self) -> u64 {
+        self.minutes()
+            .iter()
+            .zip(OS_MULTIPLIERS.iter())
+            .map(|(minutes, (_, multiplier))| minutes * multiplier)
+            .sum()
+    }
+
+    fn add(&mut self, other: &UsageEntry) {
+        self.ubuntu_ms += other.ubuntu_ms;
+        self.windows_ms += other.windows_ms;
+        self.macos_ms += other.macos_ms;
+    }
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct UsageReport {
+    pub entries: Vec<UsageEntry>,
+}
+
+impl UsageReport {
+    /// Builds the report merging the entries of the same repository and workflow.
+    pub fn from(entries: impl IntoIterator<Item = UsageEntry>) -> Self {
+        let mut merged: BTreeMap<(String, String), UsageEntry> = BTreeMap::new();
+        for entry in entries {
+            merged
+                .entry((entry.repo.to_owned(), entry.workflow.to_owned()))
+                .or_insert_with(|| UsageEntry {
+                    repo: entry.repo.to_owned(),
+                    workflow: entry.workflow.to_owned(),
+                    ..Default::default()
+                })
+                .add(&entry);
+        }
+        UsageReport {
+            entries: merged.into_iter().map(|(_, entry)| entry).collect(),
+        }
+    }
+
+    pub fn total(&self) -> UsageEntry {
+        let mut total = UsageEntry::default();
+        for entry in &self.entries {
+            total.add(entry);
+        }
+        total
+    }
+
+    /// The `count` entries with the most billable minutes.
+    pub fn top_consumers(&self, count: usize) -> Vec<&UsageEntry> {
+        let mut entries = self.entries.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.billable_minutes()));
+        entries.truncate(count);
+        entries
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "repo,workflow,ubuntu_minutes,windows_minutes,macos_minutes,billable_minutes\n",
+        );
+        for entry in &self.entries {
+            let [ubuntu, windows, macos] = entry.minutes();
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                csv_field(&entry.repo),
+                csv_field(&entry.workflow),
+                ubuntu,
+                windows,
+                macos,
+                entry.billable_minutes()
+            ));
+        }
+        csv
+    }
+}
+
+fn ms_to_minutes(ms: u64) -> u64 {
+    (ms + 59_999) / 60_000
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(minute: u32, second: u32) -> DateTime<Utc> {
+        format!("2020-01-22T19:{:02}:{:02}Z", minute, second)
+            .parse()
+            .unwrap()
+    }
+
+    fn job(name: &str, conclusion: &str, start: u32, end: u32) -> JobRecord {
+        JobRecord {
+            name: name.into(),
+            attempt: 1,
+            conclusion: conclusion.into(),
+            started_at: time(start, 0),
+            completed_at: time(end, 0),
+            steps: vec![
+                StepRecord {
+                    name: "Checkout".into(),
+                    conclusion: "success".into(),
+                    started_at: time(start, 0),
+                    completed_at: time(start, 10),
+                },
+                StepRecord {
+                    name: "Deploy".into(),
+                    conclusion: "skipped".into(),
+                    started_at: time(start, 10),
+                    completed_at: time(start, 10),
+                },
+            ],
+        }
+    }
+
+    fn run(sha: &str, conclusion: &str, created: u32, jobs: Vec<JobRecord>) -> RunRecord {
+        RunRecord {
+            workflow_id: 159038,
+            head_sha: sha.into(),
+            conclusion: Some(conclusion.into()),
+            created_at: time(created, 0),
+            updated_at: time(created + 10, 0),
+            jobs,
+        }
+    }
+
+    #[test]
+    fn nearest_rank_percentile() {
+        let values = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_eq!(percentile(&values, 50.0), Some(5));
+        assert_eq!(percentile(&values, 90.0), Some(9));
+        assert_eq!(percentile(&values, 100.0), Some(10));
+        assert_eq!(percentile(&[42], 90.0), Some(42));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn analyze_runs() {
+        let runs = vec![
+            run("sha-a", "failure", 0, vec![job("build", "failure", 1, 4)]),
+            run(
+                "sha-a",
+                "success",
+                10,
+                vec![job("build", "success", 12, 17)],
+            ),
+            run(
+                "sha-b",
+                "success",
+                20,
+                vec![job("build", "success", 20, 26)],
+            ),
+            run(
+                "sha-c",
+                "cancelled",
+                30,
+                vec![job("build", "cancelled", 31, 32)],
+            ),
+        ];
+        let names = vec![(159038, "CI".to_owned())].into_iter().collect();
+
+        let analytics = analyze(&runs, &names);
+
+        assert_eq!(
+            analytics.workflows,
+            vec![WorkflowStats {
+                workflow_id: 159038,
+                name: "CI".into(),
+                runs: 4,
+                success_rate: Some(2.0 / 3.0),
+                duration: DurationStats {
+                    count: 4,
+                    p50_secs: Some(180),
+                    p90_secs: Some(360),
+                },
+                queue: DurationStats {
+                    count: 4,
+                    p50_secs: Some(60),
+                    p90_secs: Some(120),
+                },
+                flaky_commits: 1,
+                flakiness: Some(0.5),
+            }]
+        );
+        assert_eq!(analytics.jobs.len(), 1);
+        assert_eq!(analytics.jobs[0].success_rate, Some(2.0 / 3.0));
+        assert_eq!(analytics.jobs[0].duration.p90_secs, Some(360));
+        assert_eq!(analytics.jobs[0].flakiness, Some(0.5));
+        assert_eq!(
+            analytics.steps,
+            vec![StepStats {
+                workflow: "CI".into(),
+                job: "build".into(),
+                name: "Checkout".into(),
+                duration: DurationStats {
+                    count: 4,
+                    p50_secs: Some(10),
+                    p90_secs: Some(10),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn job_flakiness_across_reruns() {
+        let rerun = |name, conclusion, start, end| JobRecord {
+            attempt: 2,
+            ..job(name, conclusion, start, end)
+        };
+        let runs = vec![
+            // Only `test` failed, and passed when the run was rerun.
+            run(
+                "sha-a",
+                "success",
+                0,
+                vec![
+                    job("lint", "success", 1, 2),
+                    job("test", "failure", 1, 3),
+                    rerun("lint", "success", 5, 6),
+                    rerun("test", "success", 5, 9),
+                ],
+            ),
+            run(
+                "sha-b",
+                "success",
+                20,
+                vec![
+                    job("lint", "success", 21, 22),
+                    job("test", "success", 21, 24),
+                ],
+            ),
+            // Failed for good, not flaky.
+            run(
+                "sha-c",
+                "failure",
+                30,
+                vec![
+                    job("lint", "failure", 31, 32),
+                    job("test", "failure", 31, 33),
+                    rerun("lint", "failure", 35, 36),
+                    rerun("test", "cancelled", 35, 36),
+                ],
+            ),
+        ];
+
+        let analytics = analyze(&runs, &HashMap::new());
+
+        // The conclusion of the rerun run hides the failure from the workflow statistics.
+        assert_eq!(analytics.workflows[0].flaky_commits, 0);
+        assert_eq!(analytics.workflows[0].duration.p90_secs, Some(240));
+        let flakiness = analytics
+            .jobs
+            .iter()
+            .map(|j| (j.name.as_str(), j.runs, j.flaky_commits, j.flakiness))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flakiness,
+            vec![("lint", 5, 0, Some(0.0)), ("test", 5, 1, Some(1.0 / 3.0))]
+        );
+    }
+
+    #[test]
+    fn created_since_filter() -> anyhow::Result<()> {
+        let time: DateTime<Utc> = "2020-01-22T19:33:08Z".parse()?;
+        assert_eq!(created_since(time.into()), ">=2020-01-22");
+        Ok(())
+    }
+
+    fn usage(repo: &str, workflow: &str, ubuntu: u64, windows: u64, macos: u64) -> UsageEntry {
+        UsageEntry {
+            repo: repo.into(),
+            workflow: workflow.into(),
+            ubuntu_ms: ubuntu * 60_000,
+            windows_ms: windows * 60_000,
+            macos_ms: macos * 60_000,
+        }
+    }
+
+    #[test]
+    fn usage_report() {
+        let report = UsageReport::from(vec![
+            usage("octo-org/api", "CI", 10, 0, 0),
+            usage("octo-org/api", "CI", 5, 0, 1),
+            usage("octo-org/app", "Release, iOS", 0, 3, 2),
+        ]);
+
+        assert_eq!(report.entries.len(), 2);
+        assert_eq!(report.entries[0].billable_minutes(), 15 + 10);
+        assert_eq!(report.entries[1].billable_minutes(), 3 * 2 + 2 * 10);
+        assert_eq!(report.total().minutes(), [15, 3, 3]);
+        assert_eq!(report.top_consumers(1)[0].repo, "octo-org/app");
+        assert_eq!(
+            report.to_csv(),
+            "repo,workflow,ubuntu_minutes,windows_minutes,macos_minutes,billable_minutes\n\
+             octo-org/api,CI,15,0,1,25\n\
+             octo-org/app,\"Release, iOS\",0,3,2,26\n"
+        );
+    }
+
+    #[test]
+    fn partial_minutes_round_up() {
+        let entry = UsageEntry {
+            ubuntu_ms: 1,
+            macos_ms: 60_001,
+            ..Default::default()
+        };
+        assert_eq!(entry.minutes(), [1, 0, 2]);
+        assert_eq!(entry.billable_minutes(), 21);
+    }
+}
@@ -0,0 +1,388 @@
+#![cfg(feature = "actions-permissions")]
+use super::orgs::OrgRequest;
+use super::repos::RepoRequest;
+use crate::utils::http::{HttpBody, HttpClient};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
+
+#[async_trait]
+pub trait ActionsPermissions {
+    async fn get_actions_permissions(&self) -> Result<ActionsPermissionsSettings>;
+    async fn set_actions_permissions(&self, settings: &ActionsPermissionsSettings) -> Result<()>;
+    async fn get_selected_actions(&self) -> Result<SelectedActions>;
+    async fn set_selected_actions(&self, selected: &SelectedActions) -> Result<()>;
+    async fn get_workflow_permissions(&self) -> Result<WorkflowPermissions>;
+    async fn set_workflow_permissions(&self, permissions: &WorkflowPermissions) -> Result<()>;
+    async fn get_fork_pr_approval_policy(&self) -> Result<ForkPrApproval>;
+    async fn set_fork_pr_approval_policy(&self, approval: &ForkPrApproval) -> Result<()>;
+}
+
+macro_rules! impl_actions_permissions {
+    ($request:ident, $prefix:literal, $owner:ident) => {
+        #[async_trait]
+        impl ActionsPermissions for $request<'_> {
+            async fn get_actions_permissions(&self) -> Result<ActionsPermissionsSettings> {
+                let path = format!($prefix, self.$owner);
+                get_permissions(&self.http_client, &path, "").await
+            }
+
+            async fn set_actions_permissions(
+                &self,
+                settings: &ActionsPermissionsSettings,
+            ) -> Result<()> {
+                let path = format!($prefix, self.$owner);
+                set_permissions(&self.http_client, &path, "", settings).await
+            }
+
+            async fn get_selected_actions(&self) -> Result<SelectedActions> {
+                let path = format!($prefix, self.$owner);
+                get_permissions(&self.http_client, &path, "/selected-actions").await
+            }
+
+            async fn set_selected_actions(&self, selected: &SelectedActions) -> Result<()> {
+                let path = format!($prefix, self.$owner);
+                set_permissions(&self.http_client, &path, "/selected-actions", selected).await
+            }
+
+            async fn get_workflow_permissions(&self) -> Result<WorkflowPermissions> {
+                let path = format!($prefix, self.$owner);
+                get_permissions(&self.http_client, &path, "/workflow").await
+            }
+
+            async fn set_workflow_permissions(
+                &self,
+                permissions: &WorkflowPermissions,
+            ) -> Result<()> {
+                let path = format!($prefix, self.$owner);
+                set_permissions(&self.http_client, &path, "/workflow", permissions).await
+            }
+
+            async fn get_fork_pr_approval_policy(&self) -> Result<ForkPrApproval> {
+                let path = format!($prefix, self.$owner);
+                get_permissions(&self.http_client, &path, FORK_PR_APPROVAL_PATH).await
+            }
+
+            async fn set_fork_pr_approval_policy(&self, approval: &ForkPrApproval) -> Result<()> {
+                let path = format!($prefix, self.$owner);
+                set_permissions(&self.http_client, &path, FORK_PR_APPROVAL_PATH, approval).await
+            }
+        }
+    };
+}
+
+const FORK_PR_APPROVAL_PATH: &str = "/fork-pr-contributor-approval";
+
+impl_actions_permissions!(RepoRequest, "repos/{}/actions/permissions", repo);
+impl_actions_permissions!(OrgRequest, "orgs/{}/actions/permissions", org);
+
+/// General Actions permissions, `enabled` is only used by repositories and
+/// `enabled_repositories` only by organizations.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ActionsPermissionsSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_repositories: Option<EnabledRepositories>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_actions: Option<AllowedActions>,
+    #[serde(skip_serializing)]
+    pub selected_actions_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnabledRepositories {
+    All,
+    None,
+    Selected,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedActions {
+    All,
+    LocalOnly,
+    Selected,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct SelectedActions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github_owned_allowed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_allowed: Option<bool>,
+    #[serde(default)]
+    pub patterns_allowed: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct WorkflowPermissions {
+    pub default_workflow_permissions: DefaultWorkflowPermissions,
+    pub can_approve_pull_request_reviews: bool,
+}
+
+/// Default permissions granted to the `GITHUB_TOKEN` of a workflow run.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultWorkflowPermissions {
+    Read,
+    Write,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ForkPrApproval {
+    pub approval_policy: ForkPrApprovalPolicy,
+}
+
+/// Which outside contributors need an approval before workflows run on their fork pull requests.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForkPrApprovalPolicy {
+    FirstTimeContributorsNewToGithub,
+    FirstTimeContributors,
+    AllExternalContributors,
+}
+
+macro_rules! impl_str_enum {
+    ($name:ident { $($variant:ident => $value:literal,)+ }) => {
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                let value = match self {
+                    $(Self::$variant => $value,)+
+                };
+                write!(f, "{}", value)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = anyhow::Error;
+
+            fn from_str(value: &str) -> Result<Self> {
+                match value {
+                    $($value => Ok(Self::$variant),)+
+                    _ => Err(anyhow::anyhow!(
+                        "invalid value `{}`, expected one of: {}",
+                        value,
+                        [$($value),+].join(", ")
+                    )),
+                }
+            }
+        }
+    };
+}
+
+impl_str_enum!(EnabledRepositories {
+    All => "all",
+    None => "none",
+    Selected => "selected",
+});
+
+impl_str_enum!(AllowedActions {
+    All => "all",
+    LocalOnly => "local_only",
+    Selected => "selected",
+});
+
+impl_str_enum!(DefaultWorkflowPermissions {
+    Read => "read",
+    Write => "write",
+});
+
+impl_str_enum!(ForkPrApprovalPolicy {
+    FirstTimeContributorsNewToGithub => "first_time_contributors_new_to_github",
+    FirstTimeContributors => "first_time_contributors",
+    AllExternalContributors => "all_external_contributors",
+});
+
+async fn get_permissions<T>(http_client: &HttpClient, path: &str, sub_path: &str) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let url = with_base_url!("{}{}", path, sub_path);
+    http_client.get(&url).await?.deserialize().await
+}
+
+async fn set_permissions<T>(
+    http_client: &HttpClient,
+    path: &str,
+    sub_path: &str,
+    body: &T,
+) -> Result<()>
+where
+    T: Serialize,
+{
+    let url = with_base_url!("{}{}", path, sub_path);
+    http_client
+        .put(&url, HttpBody::try_from_serialize(body)?)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn get_actions_permissions() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/aslamplr/gh-cli/actions/permissions")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "enabled": true,
+                "allowed_actions": "selected",
+                "selected_actions_url": "https://api.github.com/repositories/42/actions/permissions/selected-actions"
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let permissions = repo_req.get_actions_permissions().await?;
+
+        m.assert();
+        assert_eq!(
+            permissions,
+            ActionsPermissionsSettings {
+                enabled: Some(true),
+                enabled_repositories: None,
+                allowed_actions: Some(AllowedActions::Selected),
+                selected_actions_url: Some(
+                    "https://api.github.com/repositories/42/actions/permissions/selected-actions"
+                        .into()
+                ),
+            }
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_org_actions_permissions() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("PUT", "/orgs/octo-org/actions/permissions")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_body(Matcher::Json(serde_json::json!({
+                "enabled_repositories": "all",
+                "allowed_actions": "local_only"
+            })))
+            .with_status(204)
+            .expect(1)
+            .create();
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        org_req
+            .set_actions_permissions(&ActionsPermissionsSettings {
+                enabled_repositories: Some(EnabledRepositories::All),
+                allowed_actions: Some(AllowedActions::LocalOnly),
+                ..Default::default()
+            })
+            .await?;
+
+        m.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_workflow_permissions() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/aslamplr/gh-cli/actions/permissions/workflow")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "default_workflow_permissions": "read",
+                "can_approve_pull_request_reviews": true
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let permissions = repo_req.get_workflow_permissions().await?;
+
+        m.assert();
+        assert_eq!(
+            permissions,
+            WorkflowPermissions {
+                default_workflow_permissions: DefaultWorkflowPermissions::Read,
+                can_approve_pull_request_reviews: true,
+            }
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_selected_actions() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock(
+            "PUT",
+            "/repos/aslamplr/gh-cli/actions/permissions/selected-actions",
+        )
+        .match_body(Matcher::Json(serde_json::json!({
+            "github_owned_allowed": true,
+            "patterns_allowed": ["monalisa/octocat@*", "docker/*"]
+        })))
+        .with_status(204)
+        .expect(1)
+        .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req
+            .set_selected_actions(&SelectedActions {
+                github_owned_allowed: Some(true),
+                verified_allowed: None,
+                patterns_allowed: vec!["monalisa/octocat@*".into(), "docker/*".into()],
+            })
+            .await?;
+
+        m.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_fork_pr_approval_policy() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock(
+            "PUT",
+            "/repos/aslamplr/gh-cli/actions/permissions/fork-pr-contributor-approval",
+        )
+        .match_body(Matcher::Json(serde_json::json!({
+            "approval_policy": "all_external_contributors"
+        })))
+        .with_status(204)
+        .expect(1)
+        .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req
+            .set_fork_pr_approval_policy(&ForkPrApproval {
+                approval_policy: "all_external_contributors".parse()?,
+            })
+            .await?;
+
+        m.assert();
+        Ok(())
+    }
+}
@@ -30,7 +30,9 @@ impl From<&Repo<'_>> for basic_info_response::Variables {
 #[async_trait]
 impl BasicInfo for RepoRequest<'_> {
     async fn get_basic_info(&self) -> Result<BasicInfoResponse> {
-        let RepoRequest { repo, http_client } = self;
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
         let graphql_query = RepoBasicInfoQuery::build_query(repo.into());
         let resp = query_graphql(http_client, graphql_query).await?;
         resp.data
@@ -38,7 +40,9 @@ impl BasicInfo for RepoRequest<'_> {
     }
 
     async fn get_raw_readme(&self) -> Result<String> {
-        let RepoRequest { repo, http_client } = self;
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
         let resp = http_client
             .request(&with_base_url!("{}/readme", repo), HttpMethod::GET)
             .header("Accept", "application/vnd.github.VERSION.raw")
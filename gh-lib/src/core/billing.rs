@@ -0,0 +1,87 @@
+#![cfg(feature = "billing")]
+use super::orgs::OrgRequest;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
+
+#[async_trait]
+pub trait Billing {
+    async fn get_actions_billing(&self) -> Result<ActionsBilling>;
+}
+
+#[async_trait]
+impl Billing for OrgRequest<'_> {
+    async fn get_actions_billing(&self) -> Result<ActionsBilling> {
+        let OrgRequest { org, http_client } = self;
+        let url = with_base_url!("orgs/{}/settings/billing/actions", org);
+        http_client.get(&url).await?.deserialize().await
+    }
+}
+
+/// Actions minutes of the current billing cycle.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ActionsBilling {
+    pub total_minutes_used: u64,
+    pub total_paid_minutes_used: f64,
+    pub included_minutes: u64,
+    /// Minutes per runner type, eg. `UBUNTU`, `MACOS` or `ubuntu_4_core`.
+    pub minutes_used_breakdown: BTreeMap<String, u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn get_actions_billing() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/orgs/octo-org/settings/billing/actions")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "total_minutes_used": 305,
+                "total_paid_minutes_used": 0,
+                "included_minutes": 3000,
+                "minutes_used_breakdown": {
+                  "UBUNTU": 205,
+                  "MACOS": 10,
+                  "WINDOWS": 90
+                }
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        let billing = org_req.get_actions_billing().await?;
+
+        m.assert();
+        assert_eq!(
+            billing,
+            ActionsBilling {
+                total_minutes_used: 305,
+                total_paid_minutes_used: 0.0,
+                included_minutes: 3000,
+                minutes_used_breakdown: vec![
+                    ("MACOS".to_owned(), 10),
+                    ("UBUNTU".to_owned(), 205),
+                    ("WINDOWS".to_owned(), 90),
+                ]
+                .into_iter()
+                .collect(),
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,500 @@
+#![cfg(feature = "caches")]
+use super::orgs::OrgRequest;
+use super::repos::RepoRequest;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded::Serializer;
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
+
+/// Largest page size allowed by the caches API.
+const PER_PAGE: u32 = 100;
+
+#[async_trait]
+pub trait Caches {
+    async fn get_cache_usage(&self) -> Result<CacheUsage>;
+    async fn get_caches(&self, params: &CacheQueryParams<'_>, page: u32) -> Result<CacheList>;
+    /// Walks every page of caches matching `params`.
+    async fn get_all_caches(&self, params: &CacheQueryParams<'_>) -> Result<Vec<ActionsCache>>;
+    async fn delete_cache_by_id(&self, cache_id: u32) -> Result<()>;
+    /// Deletes the caches with the exact `key`, optionally only the ones of `git_ref`.
+    async fn delete_caches_by_key(&self, key: &str, git_ref: Option<&str>) -> Result<CacheList>;
+    /// Whether the pull request `number` is closed, used to purge the caches of `refs/pull/*`.
+    async fn is_pull_request_closed(&self, number: u32) -> Result<bool>;
+}
+
+#[async_trait]
+pub trait OrgCaches {
+    async fn get_org_cache_usage(&self) -> Result<OrgCacheUsage>;
+    async fn get_org_cache_usage_by_repository(&self) -> Result<RepositoryCacheUsageList>;
+}
+
+#[async_trait]
+impl Caches for RepoRequest<'_> {
+    async fn get_cache_usage(&self) -> Result<CacheUsage> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}/actions/cache/usage", repo);
+        http_client.get(&url).await?.deserialize().await
+    }
+
+    async fn get_caches(&self, params: &CacheQueryParams<'_>, page: u32) -> Result<CacheList> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}/actions/caches", repo);
+        let query = {
+            let mut serializer = Serializer::new(String::new());
+            serializer.append_pair("per_page", &PER_PAGE.to_string());
+            serializer.append_pair("page", &page.to_string());
+            if let Some(key) = params.key {
+                serializer.append_pair("key", key);
+            }
+            if let Some(git_ref) = params.git_ref {
+                serializer.append_pair("ref", git_ref);
+            }
+            if let Some(sort) = params.sort {
+                serializer.append_pair("sort", sort);
+            }
+            if let Some(direction) = params.direction {
+                serializer.append_pair("direction", direction);
+            }
+            serializer.finish()
+        };
+        http_client
+            .get(&format!("{}?{}", url, query))
+            .await?
+            .deserialize()
+            .await
+    }
+
+    async fn get_all_caches(&self, params: &CacheQueryParams<'_>) -> Result<Vec<ActionsCache>> {
+        let mut caches = Vec::new();
+        let mut page = 1;
+        loop {
+            let CacheList {
+                total_count,
+                actions_caches,
+            } = self.get_caches(params, page).await?;
+            let fetched = actions_caches.len();
+            caches.extend(actions_caches);
+            if fetched < PER_PAGE as usize || caches.len() >= total_count as usize {
+                break;
+            }
+            page += 1;
+        }
+        Ok(caches)
+    }
+
+    async fn delete_cache_by_id(&self, cache_id: u32) -> Result<()> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}/actions/caches/{}", repo, cache_id);
+        http_client.delete(&url).await?;
+        Ok(())
+    }
+
+    async fn delete_caches_by_key(&self, key: &str, git_ref: Option<&str>) -> Result<CacheList> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}/actions/caches", repo);
+        let query = {
+            let mut serializer = Serializer::new(String::new());
+            serializer.append_pair("key", key);
+            if let Some(git_ref) = git_ref {
+                serializer.append_pair("ref", git_ref);
+            }
+            serializer.finish()
+        };
+        http_client
+            .delete(&format!("{}?{}", url, query))
+            .await?
+            .deserialize()
+            .await
+    }
+
+    async fn is_pull_request_closed(&self, number: u32) -> Result<bool> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}/pulls/{}", repo, number);
+        let PullRequestState { state } = http_client.get(&url).await?.deserialize().await?;
+        Ok(state == "closed")
+    }
+}
+
+#[async_trait]
+impl OrgCaches for OrgRequest<'_> {
+    async fn get_org_cache_usage(&self) -> Result<OrgCacheUsage> {
+        let OrgRequest { org, http_client } = self;
+        let url = with_base_url!("orgs/{}/actions/cache/usage", org);
+        http_client.get(&url).await?.deserialize().await
+    }
+
+    async fn get_org_cache_usage_by_repository(&self) -> Result<RepositoryCacheUsageList> {
+        let OrgRequest { org, http_client } = self;
+        let url = with_base_url!("orgs/{}/actions/cache/usage-by-repository", org);
+        http_client.get(&url).await?.deserialize().await
+    }
+}
+
+/// Server side filters and sorting of the caches list.
+#[derive(Debug, Default)]
+pub struct CacheQueryParams<'a> {
+    /// Explicit key or key prefix.
+    pub key: Option<&'a str>,
+    /// Full git reference, eg. `refs/heads/main` or `refs/pull/42/merge`.
+    pub git_ref: Option<&'a str>,
+    /// One of `created_at`, `last_accessed_at` or `size_in_bytes`.
+    pub sort: Option<&'a str>,
+    /// Either `asc` or `desc`.
+    pub direction: Option<&'a str>,
+}
+
+/// Client side filters the caches API does not support.
+#[derive(Debug, Default)]
+pub struct CacheFilter<'a> {
+    /// Git reference pattern, `*` matches any sequence of characters.
+    pub git_ref: Option<&'a str>,
+    pub min_size_in_bytes: Option<u64>,
+    pub last_accessed_before: Option<std::time::SystemTime>,
+}
+
+impl CacheFilter<'_> {
+    pub fn matches(&self, cache: &ActionsCache) -> bool {
+        self.git_ref
+            .map_or(true, |pattern| wildcard_match(pattern, &cache.git_ref))
+            && self
+                .min_size_in_bytes
+                .map_or(true, |size| cache.size_in_bytes >= size)
+            && self.last_accessed_before.map_or(true, |before| {
+                cache.last_accessed_at < chrono::DateTime::<chrono::Utc>::from(before)
+            })
+    }
+}
+
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == value,
+        Some(idx) => {
+            let (prefix, rest) = (&pattern[..idx], &pattern[idx + 1..]);
+            value.starts_with(prefix)
+                && (idx..=value.len())
+                    .filter(|i| value.is_char_boundary(*i))
+                    .any(|i| wildcard_match(rest, &value[i..]))
+        }
+    }
+}
+
+/// Number of the pull request of a `refs/pull/<number>/...` reference.
+pub fn pull_request_number(git_ref: &str) -> Option<u32> {
+    git_ref
+        .strip_prefix("refs/pull/")?
+        .split('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CacheUsage {
+    pub full_name: String,
+    pub active_caches_size_in_bytes: u64,
+    pub active_caches_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct OrgCacheUsage {
+    pub total_active_caches_size_in_bytes: u64,
+    pub total_active_caches_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RepositoryCacheUsageList {
+    pub total_count: u32,
+    pub repository_cache_usages: Vec<CacheUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CacheList {
+    pub total_count: u32,
+    pub actions_caches: Vec<ActionsCache>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ActionsCache {
+    pub id: u32,
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub key: String,
+    pub version: String,
+    pub last_accessed_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub size_in_bytes: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct PullRequestState {
+    state: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    fn cache(id: u32, git_ref: &str, size_in_bytes: u64, last_accessed_at: &str) -> ActionsCache {
+        ActionsCache {
+            id,
+            git_ref: git_ref.into(),
+            key: format!("Linux-node-{}", id),
+            version: "73885106f58cc52a7df9ec4d4a5622a5614813162cb516c759a30af6bf56e6f0".into(),
+            last_accessed_at: last_accessed_at.parse().unwrap(),
+            created_at: "2019-01-24T22:45:36.000Z".parse().unwrap(),
+            size_in_bytes,
+        }
+    }
+
+    fn cache_json(id: u32, git_ref: &str) -> String {
+        format!(
+            r#"{{
+                "id": {id},
+                "ref": "{git_ref}",
+                "key": "Linux-node-{id}",
+                "version": "73885106f58cc52a7df9ec4d4a5622a5614813162cb516c759a30af6bf56e6f0",
+                "last_accessed_at": "2019-01-24T22:45:36.000Z",
+                "created_at": "2019-01-24T22:45:36.000Z",
+                "size_in_bytes": 1024
+              }}"#,
+            id = id,
+            git_ref = git_ref
+        )
+    }
+
+    #[tokio::test]
+    async fn get_cache_usage() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/aslamplr/gh-cli/actions/cache/usage")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "full_name": "aslamplr/gh-cli",
+                "active_caches_size_in_bytes": 2322142,
+                "active_caches_count": 3
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let usage = repo_req.get_cache_usage().await?;
+
+        m.assert();
+        assert_eq!(
+            usage,
+            CacheUsage {
+                full_name: "aslamplr/gh-cli".into(),
+                active_caches_size_in_bytes: 2322142,
+                active_caches_count: 3,
+            }
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_org_cache_usage() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/orgs/octo-org/actions/cache/usage")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "total_active_caches_size_in_bytes": 3344284,
+                "total_active_caches_count": 5
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        let usage = org_req.get_org_cache_usage().await?;
+
+        m.assert();
+        assert_eq!(
+            usage,
+            OrgCacheUsage {
+                total_active_caches_size_in_bytes: 3344284,
+                total_active_caches_count: 5,
+            }
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_all_caches_walks_pages() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let first_page = (1..=PER_PAGE)
+            .map(|id| cache_json(id, "refs/heads/main"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let m1 = mock("GET", "/repos/aslamplr/gh-cli/actions/caches")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("page".into(), "1".into()),
+                Matcher::UrlEncoded("key".into(), "Linux-node".into()),
+                Matcher::UrlEncoded("sort".into(), "size_in_bytes".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{ "total_count": 101, "actions_caches": [{}] }}"#,
+                first_page
+            ))
+            .expect(1)
+            .create();
+        let m2 = mock("GET", "/repos/aslamplr/gh-cli/actions/caches")
+            .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{ "total_count": 101, "actions_caches": [{}] }}"#,
+                cache_json(101, "refs/pull/42/merge")
+            ))
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let caches = repo_req
+            .get_all_caches(&CacheQueryParams {
+                key: Some("Linux-node"),
+                sort: Some("size_in_bytes"),
+                ..Default::default()
+            })
+            .await?;
+
+        m1.assert();
+        m2.assert();
+        assert_eq!(caches.len(), 101);
+        assert_eq!(caches[100].git_ref, "refs/pull/42/merge");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_caches_by_key() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("DELETE", "/repos/aslamplr/gh-cli/actions/caches")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("key".into(), "Linux-node-7".into()),
+                Matcher::UrlEncoded("ref".into(), "refs/pull/42/merge".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{ "total_count": 1, "actions_caches": [{}] }}"#,
+                cache_json(7, "refs/pull/42/merge")
+            ))
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let deleted = repo_req
+            .delete_caches_by_key("Linux-node-7", Some("refs/pull/42/merge"))
+            .await?;
+
+        m.assert();
+        assert_eq!(deleted.actions_caches[0].id, 7);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn is_pull_request_closed() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/aslamplr/gh-cli/pulls/42")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "number": 42, "state": "closed" }"#)
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        assert!(repo_req.is_pull_request_closed(42).await?);
+
+        m.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn cache_filter_matches() -> Result<()> {
+        let filter = CacheFilter {
+            git_ref: Some("refs/pull/*"),
+            min_size_in_bytes: Some(1000),
+            last_accessed_before: Some(
+                "2020-01-01T00:00:00Z"
+                    .parse::<chrono::DateTime<chrono::Utc>>()?
+                    .into(),
+            ),
+        };
+
+        assert!(filter.matches(&cache(
+            1,
+            "refs/pull/42/merge",
+            1024,
+            "2019-12-01T00:00:00Z"
+        )));
+        assert!(!filter.matches(&cache(2, "refs/heads/main", 1024, "2019-12-01T00:00:00Z")));
+        assert!(!filter.matches(&cache(3, "refs/pull/42/merge", 10, "2019-12-01T00:00:00Z")));
+        assert!(!filter.matches(&cache(
+            4,
+            "refs/pull/42/merge",
+            1024,
+            "2020-02-01T00:00:00Z"
+        )));
+        assert!(CacheFilter::default().matches(&cache(
+            5,
+            "refs/heads/main",
+            0,
+            "2020-02-01T00:00:00Z"
+        )));
+        Ok(())
+    }
+
+    #[test]
+    fn wildcard_and_pull_request_refs() {
+        assert!(wildcard_match("refs/pull/*/merge", "refs/pull/42/merge"));
+        assert!(!wildcard_match("refs/pull/*/merge", "refs/pull/42/head"));
+        assert!(wildcard_match("refs/heads/main", "refs/heads/main"));
+        assert!(wildcard_match("*", ""));
+        assert_eq!(pull_request_number("refs/pull/42/merge"), Some(42));
+        assert_eq!(pull_request_number("refs/heads/main"), None);
+    }
+}
@@ -127,13 +127,17 @@ pub enum AddCollaboratorResponse {
 }
 
 async fn get_collaborators(params: &RepoRequest<'_>) -> Result<Vec<Collaborator>> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/collaborators", repo);
     http_client.get(&url).await?.deserialize().await
 }
 
 async fn is_collaborator(params: &RepoRequest<'_>, username: &str) -> Result<bool> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/collaborators/{}", repo, username);
     Ok(http_client.get(&url).await.is_ok())
 }
@@ -143,7 +147,9 @@ async fn add_collaborator(
     username: &str,
     permission: CollaboratorPermission,
 ) -> Result<AddCollaboratorResponse> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/collaborators/{}", repo, username);
     match http_client
         .put(
@@ -160,7 +166,9 @@ async fn add_collaborator(
 }
 
 async fn remove_collaborator(params: &RepoRequest<'_>, username: &str) -> Result<()> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/collaborators/{}", repo, username);
     http_client.delete(&url).await?;
     Ok(())
@@ -170,7 +178,9 @@ async fn get_permission(
     params: &RepoRequest<'_>,
     username: &str,
 ) -> Result<CollaboratorPermissionResponse> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/collaborators/{}/permission", repo, username);
     Ok(http_client.get(&url).await?.deserialize().await?)
 }
@@ -0,0 +1,285 @@
+#![cfg(feature = "git-refs")]
+use super::repos::RepoRequest;
+use crate::utils::http::{HttpError, StatusCode};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
+
+/// Annotated tags can point at other tags, give up peeling after this many.
+const MAX_TAG_DEPTH: usize = 8;
+
+#[async_trait]
+pub trait GitRefs {
+    /// Fully qualified reference without the `refs/` prefix, eg. `tags/v1` or `heads/main`.
+    async fn get_a_reference(&self, git_ref: &str) -> Result<GitReference>;
+    async fn get_a_tag(&self, tag_sha: &str) -> Result<GitTag>;
+    async fn get_latest_release(&self) -> Result<Release>;
+    /// Commit SHA of a tag or a branch, following annotated tags to their commit.
+    async fn resolve_commit_sha(&self, name: &str) -> Result<String>;
+}
+
+#[async_trait]
+impl GitRefs for RepoRequest<'_> {
+    async fn get_a_reference(&self, git_ref: &str) -> Result<GitReference> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}/git/ref/{}", repo, git_ref);
+        http_client.get(&url).await?.deserialize().await
+    }
+
+    async fn get_a_tag(&self, tag_sha: &str) -> Result<GitTag> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}/git/tags/{}", repo, tag_sha);
+        http_client.get(&url).await?.deserialize().await
+    }
+
+    async fn get_latest_release(&self) -> Result<Release> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}/releases/latest", repo);
+        http_client.get(&url).await?.deserialize().await
+    }
+
+    async fn resolve_commit_sha(&self, name: &str) -> Result<String> {
+        let reference = match self.get_a_reference(&format!("tags/{}", name)).await {
+            Ok(reference) => reference,
+            Err(err) if is_not_found(&err) => {
+                match self.get_a_reference(&format!("heads/{}", name)).await {
+                    Ok(reference) => reference,
+                    Err(err) if is_not_found(&err) => {
+                        return Err(anyhow!("`{}` is neither a tag nor a branch", name))
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            Err(err) => return Err(err),
+        };
+        let mut object = reference.object;
+        for _ in 0..MAX_TAG_DEPTH {
+            if object.object_type != "tag" {
+                return Ok(object.sha);
+            }
+            object = self.get_a_tag(&object.sha).await?.object;
+        }
+        Err(anyhow!("tag `{}` does not lead to a commit", name))
+    }
+}
+
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<HttpError>()
+        .is_some_and(|err| err.status == StatusCode::NOT_FOUND)
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct GitReference {
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub node_id: String,
+    pub url: String,
+    pub object: GitObject,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct GitObject {
+    pub sha: String,
+    /// `commit` or, for annotated tags, `tag`.
+    #[serde(rename = "type")]
+    pub object_type: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct GitTag {
+    pub sha: String,
+    pub tag: String,
+    pub message: String,
+    pub object: GitObject,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Release {
+    pub id: u32,
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub html_url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    const COMMIT_SHA: &str = "aa218f56b14c9653891f9e74264a383fa43fefbd";
+    const TAG_SHA: &str = "940bd336248efae0f9ee5bc7b2d5c985887b16ac";
+
+    fn reference_json(git_ref: &str, sha: &str, object_type: &str) -> String {
+        format!(
+            r#"{{
+                "ref": "refs/{git_ref}",
+                "node_id": "MDM6UmVmcmVmcy9oZWFkcy9mZWF0dXJlQQ==",
+                "url": "https://api.github.com/repos/octocat/Hello-World/git/refs/{git_ref}",
+                "object": {{
+                  "type": "{object_type}",
+                  "sha": "{sha}",
+                  "url": "https://api.github.com/repos/octocat/Hello-World/git/{object_type}s/{sha}"
+                }}
+              }}"#,
+            git_ref = git_ref,
+            sha = sha,
+            object_type = object_type
+        )
+    }
+
+    #[tokio::test]
+    async fn resolve_annotated_tag() -> Result<()> {
+        let repo_addr = "octocat/annotated";
+        let auth_token = "auth_secret_token";
+
+        let ref_mock = mock("GET", "/repos/octocat/annotated/git/ref/tags/v1.2.0")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(reference_json("tags/v1.2.0", TAG_SHA, "tag"))
+            .expect(1)
+            .create();
+        let tag_mock = mock(
+            "GET",
+            format!("/repos/octocat/annotated/git/tags/{}", TAG_SHA).as_str(),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{
+                "sha": "{}",
+                "tag": "v1.2.0",
+                "message": "initial version",
+                "object": {{
+                  "type": "commit",
+                  "sha": "{}",
+                  "url": "https://api.github.com/repos/octocat/Hello-World/git/commits/{}"
+                }}
+              }}"#,
+            TAG_SHA, COMMIT_SHA, COMMIT_SHA
+        ))
+        .expect(1)
+        .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let sha = repo_req.resolve_commit_sha("v1.2.0").await?;
+
+        ref_mock.assert();
+        tag_mock.assert();
+        assert_eq!(sha, COMMIT_SHA);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn resolve_branch() -> Result<()> {
+        let repo_addr = "octocat/branch";
+        let auth_token = "auth_secret_token";
+
+        let tag_mock = mock("GET", "/repos/octocat/branch/git/ref/tags/main")
+            .with_status(404)
+            .with_body(r#"{"message": "Not Found"}"#)
+            .expect(1)
+            .create();
+        let branch_mock = mock("GET", "/repos/octocat/branch/git/ref/heads/main")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(reference_json("heads/main", COMMIT_SHA, "commit"))
+            .expect(1)
+            .create();
+        let missing_mock = mock("GET", "/repos/octocat/branch/git/ref/tags/v9")
+            .with_status(404)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let sha = repo_req.resolve_commit_sha("main").await?;
+
+        tag_mock.assert();
+        branch_mock.assert();
+        assert_eq!(sha, COMMIT_SHA);
+        assert!(repo_req.resolve_commit_sha("v9").await.is_err());
+        missing_mock.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn resolve_server_error() -> Result<()> {
+        let repo_addr = "octocat/unavailable";
+        let auth_token = "auth_secret_token";
+
+        let tag_mock = mock("GET", "/repos/octocat/unavailable/git/ref/tags/v1")
+            .with_status(502)
+            .with_body("Bad Gateway")
+            .expect(1)
+            .create();
+        let branch_mock = mock("GET", "/repos/octocat/unavailable/git/ref/heads/v1")
+            .expect(0)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let err = repo_req.resolve_commit_sha("v1").await.unwrap_err();
+
+        tag_mock.assert();
+        branch_mock.assert();
+        assert_eq!(
+            err.downcast_ref::<HttpError>().map(|err| err.status),
+            Some(StatusCode::BAD_GATEWAY)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_latest_release() -> Result<()> {
+        let repo_addr = "octocat/release";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/octocat/release/releases/latest")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "url": "https://api.github.com/repos/octocat/Hello-World/releases/1",
+                "html_url": "https://github.com/octocat/Hello-World/releases/v1.0.0",
+                "id": 1,
+                "tag_name": "v1.0.0",
+                "target_commitish": "master",
+                "name": "v1.0.0",
+                "draft": false,
+                "prerelease": false
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let release = repo_req.get_latest_release().await?;
+
+        m.assert();
+        assert_eq!(
+            release,
+            Release {
+                id: 1,
+                tag_name: "v1.0.0".into(),
+                name: Some("v1.0.0".into()),
+                draft: false,
+                prerelease: false,
+                html_url: "https://github.com/octocat/Hello-World/releases/v1.0.0".into(),
+            }
+        );
+        Ok(())
+    }
+}
@@ -1,10 +1,27 @@
+#[cfg(feature = "actions-permissions")]
+pub mod actions_permissions;
 #[cfg(feature = "basic-info")]
 pub mod basic_info;
+#[cfg(feature = "billing")]
+pub mod billing;
+#[cfg(feature = "caches")]
+pub mod caches;
 #[cfg(feature = "collaborators")]
 pub mod collaborators;
+#[cfg(feature = "git-refs")]
+pub mod git_refs;
+pub mod orgs;
 pub mod repos;
+#[cfg(feature = "runners")]
+pub mod runners;
 #[cfg(feature = "secrets")]
 pub mod secrets;
+#[cfg(feature = "secrets-ledger")]
+pub mod secrets_ledger;
+#[cfg(feature = "users")]
+pub mod users;
+#[cfg(feature = "variables")]
+pub mod variables;
 #[cfg(feature = "workflows")]
 pub mod workflow_jobs;
 #[cfg(feature = "workflows")]
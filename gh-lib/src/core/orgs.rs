@@ -0,0 +1,108 @@
+use crate::token::AuthToken;
+use crate::utils::http::HttpClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
+
+#[derive(Debug)]
+pub struct OrgRequest<'a> {
+    pub(crate) org: &'a str,
+    pub(crate) http_client: HttpClient,
+}
+
+impl<'a> OrgRequest<'a> {
+    pub fn try_from(org: &'a str, auth_token: impl Into<AuthToken>) -> Result<Self> {
+        let http_client = HttpClient::new(auth_token)?;
+        Ok(OrgRequest { org, http_client })
+    }
+}
+
+#[async_trait]
+pub trait OrgRepositories {
+    /// Walks every page of the repositories of the organization.
+    async fn get_all_repositories(&self) -> Result<Vec<OrgRepository>>;
+}
+
+#[async_trait]
+impl OrgRepositories for OrgRequest<'_> {
+    async fn get_all_repositories(&self) -> Result<Vec<OrgRepository>> {
+        const PER_PAGE: usize = 100;
+
+        let OrgRequest { org, http_client } = self;
+        let mut repositories = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = with_base_url!("orgs/{}/repos?per_page={}&page={}", org, PER_PAGE, page);
+            let resp: Vec<OrgRepository> = http_client.get(&url).await?.deserialize().await?;
+            let fetched = resp.len();
+            repositories.extend(resp);
+            if fetched < PER_PAGE {
+                break;
+            }
+            page += 1;
+        }
+        Ok(repositories)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct OrgRepository {
+    pub id: u32,
+    pub name: String,
+    pub full_name: String,
+    pub private: bool,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn get_all_repositories() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/orgs/octo-org/repos")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                  {
+                    "id": 1296269,
+                    "name": "Hello-World",
+                    "full_name": "octo-org/Hello-World",
+                    "private": false,
+                    "archived": false
+                  }
+                ]"#,
+            )
+            .expect(1)
+            .create();
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        let repositories = org_req.get_all_repositories().await?;
+
+        m.assert();
+        assert_eq!(
+            repositories,
+            vec![OrgRepository {
+                id: 1296269,
+                name: "Hello-World".into(),
+                full_name: "octo-org/Hello-World".into(),
+                private: false,
+                archived: false,
+            }]
+        );
+        Ok(())
+    }
+}
@@ -1,5 +1,13 @@
+use crate::token::AuthToken;
 use crate::utils::http::HttpClient;
+#[cfg(feature = "secrets-save")]
+use crate::utils::sealed_box::SecretEncryptorCache;
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
 
 #[derive(Debug)]
 pub struct Repo<'a> {
@@ -11,20 +19,56 @@ pub struct Repo<'a> {
 pub struct RepoRequest<'a> {
     pub(crate) repo: Repo<'a>,
     pub(crate) http_client: HttpClient,
+    #[cfg(feature = "secrets-save")]
+    pub(crate) secret_encryptor: SecretEncryptorCache,
 }
 
 impl<'a> RepoRequest<'a> {
-    pub fn try_from(repo_addr: &'a str, auth_token: &'a str) -> Result<Self> {
+    pub fn try_from(repo_addr: &'a str, auth_token: impl Into<AuthToken>) -> Result<Self> {
+        let repo = Repo::try_from(repo_addr)?;
+        let http_client = HttpClient::new(auth_token)?;
+        Ok(RepoRequest {
+            repo,
+            http_client,
+            #[cfg(feature = "secrets-save")]
+            secret_encryptor: Default::default(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct EnvironmentRequest<'a> {
+    pub(crate) repo: Repo<'a>,
+    pub(crate) env_name: &'a str,
+    pub(crate) http_client: HttpClient,
+}
+
+impl<'a> EnvironmentRequest<'a> {
+    pub fn try_from(
+        repo_addr: &'a str,
+        env_name: &'a str,
+        auth_token: impl Into<AuthToken>,
+    ) -> Result<Self> {
+        let repo = Repo::try_from(repo_addr)?;
+        let http_client = HttpClient::new(auth_token)?;
+        Ok(EnvironmentRequest {
+            repo,
+            env_name,
+            http_client,
+        })
+    }
+}
+
+impl<'a> Repo<'a> {
+    pub fn try_from(repo_addr: &'a str) -> Result<Self> {
         let slash_idx = repo_addr
             .find('/')
             .ok_or_else(|| anyhow!("Unable to parse repo_name from: {}", repo_addr))?;
         let (repo_owner, repo_name) = repo_addr.split_at(slash_idx);
-        let repo = Repo {
-            repo_owner: &repo_owner,
+        Ok(Repo {
+            repo_owner,
             repo_name: &repo_name[1..],
-        };
-        let http_client = HttpClient::new(auth_token)?;
-        Ok(RepoRequest { repo, http_client })
+        })
     }
 }
 
@@ -33,3 +77,82 @@ impl std::fmt::Display for Repo<'_> {
         write!(f, "{}/{}", self.repo_owner, self.repo_name)
     }
 }
+
+#[async_trait]
+pub trait Repositories {
+    async fn get_repository(&self) -> Result<Repository>;
+}
+
+#[async_trait]
+impl Repositories for RepoRequest<'_> {
+    async fn get_repository(&self) -> Result<Repository> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let url = with_base_url!("repos/{}", repo);
+        http_client.get(&url).await?.deserialize().await
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Repository {
+    pub id: u32,
+    pub name: String,
+    pub full_name: String,
+    pub private: bool,
+    pub default_branch: String,
+    #[serde(default)]
+    pub archived: bool,
+    pub html_url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn get_repository() -> Result<()> {
+        let repo_addr = "octocat/Hello-World";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/octocat/Hello-World")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "id": 1296269,
+                "name": "Hello-World",
+                "full_name": "octocat/Hello-World",
+                "private": false,
+                "html_url": "https://github.com/octocat/Hello-World",
+                "default_branch": "master",
+                "archived": false
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let repository = repo_req.get_repository().await?;
+
+        m.assert();
+        assert_eq!(
+            repository,
+            Repository {
+                id: 1296269,
+                name: "Hello-World".into(),
+                full_name: "octocat/Hello-World".into(),
+                private: false,
+                default_branch: "master".into(),
+                archived: false,
+                html_url: "https://github.com/octocat/Hello-World".into(),
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,816 @@
+#![cfg(feature = "runners")]
+use super::orgs::OrgRequest;
+use super::repos::RepoRequest;
+use crate::utils::http::{HttpBody, HttpClient};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
+
+const PER_PAGE: usize = 100;
+
+#[async_trait]
+pub trait Runners {
+    async fn get_all_runners(&self) -> Result<RunnerList>;
+    async fn get_a_runner(&self, runner_id: u32) -> Result<Runner>;
+    async fn delete_a_runner(&self, runner_id: u32) -> Result<()>;
+    async fn create_registration_token(&self) -> Result<RunnerToken>;
+    async fn create_remove_token(&self) -> Result<RunnerToken>;
+    async fn get_runner_downloads(&self) -> Result<Vec<RunnerApplication>>;
+    async fn get_runner_labels(&self, runner_id: u32) -> Result<RunnerLabelList>;
+    async fn add_runner_labels(&self, runner_id: u32, labels: &[&str]) -> Result<RunnerLabelList>;
+    async fn set_runner_labels(&self, runner_id: u32, labels: &[&str]) -> Result<RunnerLabelList>;
+    async fn remove_runner_label(&self, runner_id: u32, label: &str) -> Result<RunnerLabelList>;
+}
+
+#[async_trait]
+pub trait RunnerGroups {
+    async fn get_all_runner_groups(&self) -> Result<RunnerGroupList>;
+    async fn get_a_runner_group(&self, group_id: u32) -> Result<RunnerGroup>;
+    async fn create_runner_group(&self, name: &str, visibility: &str) -> Result<RunnerGroup>;
+    async fn delete_a_runner_group(&self, group_id: u32) -> Result<()>;
+    async fn get_runner_group_runners(&self, group_id: u32) -> Result<RunnerList>;
+    async fn add_runner_to_group(&self, group_id: u32, runner_id: u32) -> Result<()>;
+    async fn remove_runner_from_group(&self, group_id: u32, runner_id: u32) -> Result<()>;
+}
+
+macro_rules! impl_runners {
+    ($request:ident, $prefix:literal, $owner:ident) => {
+        #[async_trait]
+        impl Runners for $request<'_> {
+            async fn get_all_runners(&self) -> Result<RunnerList> {
+                let path = format!($prefix, self.$owner);
+                get_all_runners(&self.http_client, &path).await
+            }
+
+            async fn get_a_runner(&self, runner_id: u32) -> Result<Runner> {
+                let path = format!($prefix, self.$owner);
+                get_a_runner(&self.http_client, &path, runner_id).await
+            }
+
+            async fn delete_a_runner(&self, runner_id: u32) -> Result<()> {
+                let path = format!($prefix, self.$owner);
+                delete_a_runner(&self.http_client, &path, runner_id).await
+            }
+
+            async fn create_registration_token(&self) -> Result<RunnerToken> {
+                let path = format!($prefix, self.$owner);
+                create_runner_token(&self.http_client, &path, "registration-token").await
+            }
+
+            async fn create_remove_token(&self) -> Result<RunnerToken> {
+                let path = format!($prefix, self.$owner);
+                create_runner_token(&self.http_client, &path, "remove-token").await
+            }
+
+            async fn get_runner_downloads(&self) -> Result<Vec<RunnerApplication>> {
+                let path = format!($prefix, self.$owner);
+                get_runner_downloads(&self.http_client, &path).await
+            }
+
+            async fn get_runner_labels(&self, runner_id: u32) -> Result<RunnerLabelList> {
+                let path = format!($prefix, self.$owner);
+                get_runner_labels(&self.http_client, &path, runner_id).await
+            }
+
+            async fn add_runner_labels(
+                &self,
+                runner_id: u32,
+                labels: &[&str],
+            ) -> Result<RunnerLabelList> {
+                let path = format!($prefix, self.$owner);
+                save_runner_labels(&self.http_client, &path, runner_id, labels, false).await
+            }
+
+            async fn set_runner_labels(
+                &self,
+                runner_id: u32,
+                labels: &[&str],
+            ) -> Result<RunnerLabelList> {
+                let path = format!($prefix, self.$owner);
+                save_runner_labels(&self.http_client, &path, runner_id, labels, true).await
+            }
+
+            async fn remove_runner_label(
+                &self,
+                runner_id: u32,
+                label: &str,
+            ) -> Result<RunnerLabelList> {
+                let path = format!($prefix, self.$owner);
+                remove_runner_label(&self.http_client, &path, runner_id, label).await
+            }
+        }
+    };
+}
+
+impl_runners!(RepoRequest, "repos/{}/actions", repo);
+impl_runners!(OrgRequest, "orgs/{}/actions", org);
+
+#[async_trait]
+impl RunnerGroups for OrgRequest<'_> {
+    async fn get_all_runner_groups(&self) -> Result<RunnerGroupList> {
+        let OrgRequest { org, http_client } = self;
+        let mut runner_groups = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = with_base_url!(
+                "orgs/{}/actions/runner-groups?per_page={}&page={}",
+                org,
+                PER_PAGE,
+                page
+            );
+            let resp: RunnerGroupList = http_client.get(&url).await?.deserialize().await?;
+            let fetched = resp.runner_groups.len();
+            runner_groups.extend(resp.runner_groups);
+            if fetched < PER_PAGE || runner_groups.len() >= resp.total_count as usize {
+                return Ok(RunnerGroupList {
+                    total_count: resp.total_count,
+                    runner_groups,
+                });
+            }
+            page += 1;
+        }
+    }
+
+    async fn get_a_runner_group(&self, group_id: u32) -> Result<RunnerGroup> {
+        let OrgRequest { org, http_client } = self;
+        let url = with_base_url!("orgs/{}/actions/runner-groups/{}", org, group_id);
+        http_client.get(&url).await?.deserialize().await
+    }
+
+    async fn create_runner_group(&self, name: &str, visibility: &str) -> Result<RunnerGroup> {
+        let OrgRequest { org, http_client } = self;
+        let url = with_base_url!("orgs/{}/actions/runner-groups", org);
+        let body = RunnerGroupCreateRequest { name, visibility };
+        http_client
+            .post(&url, HttpBody::try_from_serialize(&body)?)
+            .await?
+            .deserialize()
+            .await
+    }
+
+    async fn delete_a_runner_group(&self, group_id: u32) -> Result<()> {
+        let OrgRequest { org, http_client } = self;
+        let url = with_base_url!("orgs/{}/actions/runner-groups/{}", org, group_id);
+        http_client.delete(&url).await?;
+        Ok(())
+    }
+
+    async fn get_runner_group_runners(&self, group_id: u32) -> Result<RunnerList> {
+        let OrgRequest { org, http_client } = self;
+        let path = format!("orgs/{}/actions/runner-groups/{}", org, group_id);
+        get_all_runners(http_client, &path).await
+    }
+
+    async fn add_runner_to_group(&self, group_id: u32, runner_id: u32) -> Result<()> {
+        let OrgRequest { org, http_client } = self;
+        let url = with_base_url!(
+            "orgs/{}/actions/runner-groups/{}/runners/{}",
+            org,
+            group_id,
+            runner_id
+        );
+        http_client.put(&url, HttpBody::empty()).await?;
+        Ok(())
+    }
+
+    async fn remove_runner_from_group(&self, group_id: u32, runner_id: u32) -> Result<()> {
+        let OrgRequest { org, http_client } = self;
+        let url = with_base_url!(
+            "orgs/{}/actions/runner-groups/{}/runners/{}",
+            org,
+            group_id,
+            runner_id
+        );
+        http_client.delete(&url).await?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunnerList {
+    pub total_count: u32,
+    pub runners: Vec<Runner>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Runner {
+    pub id: u32,
+    pub name: String,
+    pub os: String,
+    pub status: String,
+    pub busy: bool,
+    pub labels: Vec<RunnerLabel>,
+}
+
+impl Runner {
+    pub fn is_online(&self) -> bool {
+        self.status == "online"
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunnerLabelList {
+    pub total_count: u32,
+    pub labels: Vec<RunnerLabel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunnerLabel {
+    #[serde(default)]
+    pub id: Option<u32>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub label_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunnerToken {
+    pub token: String,
+    #[cfg(feature = "chrono")]
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    #[cfg(not(feature = "chrono"))]
+    pub expires_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunnerApplication {
+    pub os: String,
+    pub architecture: String,
+    pub download_url: String,
+    pub filename: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunnerGroupList {
+    pub total_count: u32,
+    pub runner_groups: Vec<RunnerGroup>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunnerGroup {
+    pub id: u32,
+    pub name: String,
+    pub visibility: String,
+    pub default: bool,
+    pub runners_url: String,
+    #[serde(default)]
+    pub inherited: bool,
+    #[serde(default)]
+    pub allows_public_repositories: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct RunnerGroupCreateRequest<'a> {
+    name: &'a str,
+    visibility: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct RunnerLabelsRequest<'a> {
+    labels: &'a [&'a str],
+}
+
+/// Runners of every page of `{path}/runners`, the API listing 30 per page by default.
+async fn get_all_runners(http_client: &HttpClient, path: &str) -> Result<RunnerList> {
+    let mut runners = Vec::new();
+    let mut page = 1;
+    loop {
+        let url = with_base_url!("{}/runners?per_page={}&page={}", path, PER_PAGE, page);
+        let resp: RunnerList = http_client.get(&url).await?.deserialize().await?;
+        let fetched = resp.runners.len();
+        runners.extend(resp.runners);
+        if fetched < PER_PAGE || runners.len() >= resp.total_count as usize {
+            return Ok(RunnerList {
+                total_count: resp.total_count,
+                runners,
+            });
+        }
+        page += 1;
+    }
+}
+
+async fn get_a_runner(http_client: &HttpClient, path: &str, runner_id: u32) -> Result<Runner> {
+    let url = with_base_url!("{}/runners/{}", path, runner_id);
+    http_client.get(&url).await?.deserialize().await
+}
+
+async fn delete_a_runner(http_client: &HttpClient, path: &str, runner_id: u32) -> Result<()> {
+    let url = with_base_url!("{}/runners/{}", path, runner_id);
+    http_client.delete(&url).await?;
+    Ok(())
+}
+
+async fn create_runner_token(
+    http_client: &HttpClient,
+    path: &str,
+    token_type: &str,
+) -> Result<RunnerToken> {
+    let url = with_base_url!("{}/runners/{}", path, token_type);
+    http_client
+        .post(&url, HttpBody::empty())
+        .await?
+        .deserialize()
+        .await
+}
+
+async fn get_runner_downloads(
+    http_client: &HttpClient,
+    path: &str,
+) -> Result<Vec<RunnerApplication>> {
+    let url = with_base_url!("{}/runners/downloads", path);
+    http_client.get(&url).await?.deserialize().await
+}
+
+async fn get_runner_labels(
+    http_client: &HttpClient,
+    path: &str,
+    runner_id: u32,
+) -> Result<RunnerLabelList> {
+    let url = with_base_url!("{}/runners/{}/labels", path, runner_id);
+    http_client.get(&url).await?.deserialize().await
+}
+
+async fn save_runner_labels(
+    http_client: &HttpClient,
+    path: &str,
+    runner_id: u32,
+    labels: &[&str],
+    replace: bool,
+) -> Result<RunnerLabelList> {
+    let url = with_base_url!("{}/runners/{}/labels", path, runner_id);
+    let body = HttpBody::try_from_serialize(&RunnerLabelsRequest { labels })?;
+    let resp = if replace {
+        http_client.put(&url, body).await?
+    } else {
+        http_client.post(&url, body).await?
+    };
+    resp.deserialize().await
+}
+
+async fn remove_runner_label(
+    http_client: &HttpClient,
+    path: &str,
+    runner_id: u32,
+    label: &str,
+) -> Result<RunnerLabelList> {
+    let url = with_base_url!("{}/runners/{}/labels/{}", path, runner_id, label);
+    http_client.delete(&url).await?.deserialize().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    const RUNNER_LIST_BODY: &str = r#"{
+        "total_count": 2,
+        "runners": [
+          {
+            "id": 23,
+            "name": "MBP",
+            "os": "macos",
+            "status": "online",
+            "busy": true,
+            "labels": [
+              { "id": 5, "name": "self-hosted", "type": "read-only" },
+              { "id": 7, "name": "X64", "type": "read-only" }
+            ]
+          },
+          {
+            "id": 24,
+            "name": "iMac",
+            "os": "macos",
+            "status": "offline",
+            "busy": false,
+            "labels": [
+              { "id": 5, "name": "self-hosted", "type": "read-only" },
+              { "id": 9, "name": "gpu", "type": "custom" }
+            ]
+          }
+        ]
+      }"#;
+
+    fn label(id: u32, name: &str, label_type: &str) -> RunnerLabel {
+        RunnerLabel {
+            id: Some(id),
+            name: name.into(),
+            label_type: label_type.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn get_all_runners() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/aslamplr/gh-cli/actions/runners")
+            .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(RUNNER_LIST_BODY)
+            .expect(1)
+            .create();
+
+        let expected_runners = RunnerList {
+            total_count: 2,
+            runners: vec![
+                Runner {
+                    id: 23,
+                    name: "MBP".into(),
+                    os: "macos".into(),
+                    status: "online".into(),
+                    busy: true,
+                    labels: vec![
+                        label(5, "self-hosted", "read-only"),
+                        label(7, "X64", "read-only"),
+                    ],
+                },
+                Runner {
+                    id: 24,
+                    name: "iMac".into(),
+                    os: "macos".into(),
+                    status: "offline".into(),
+                    busy: false,
+                    labels: vec![
+                        label(5, "self-hosted", "read-only"),
+                        label(9, "gpu", "custom"),
+                    ],
+                },
+            ],
+        };
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let runners = repo_req.get_all_runners().await?;
+
+        m.assert();
+        assert_eq!(runners, expected_runners);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_all_org_runners() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/orgs/octo-org/actions/runners")
+            .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(RUNNER_LIST_BODY)
+            .expect(1)
+            .create();
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        let runners = org_req.get_all_runners().await?;
+
+        m.assert();
+        assert_eq!(runners.total_count, 2);
+        assert!(!runners.runners[1].is_online());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_runner_group_runners_walks_pages() -> Result<()> {
+        let auth_token = "auth_secret_token";
+        let runner_json = |id| {
+            format!(
+                r#"{{ "id": {}, "name": "runner-{}", "os": "linux", "status": "online", "busy": false, "labels": [] }}"#,
+                id, id
+            )
+        };
+
+        let first_page = (1..=PER_PAGE)
+            .map(runner_json)
+            .collect::<Vec<_>>()
+            .join(",");
+        let m1 = mock("GET", "/orgs/octo-org/actions/runner-groups/2/runners")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("per_page".into(), "100".into()),
+                Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{ "total_count": 101, "runners": [{}] }}"#,
+                first_page
+            ))
+            .expect(1)
+            .create();
+        let m2 = mock("GET", "/orgs/octo-org/actions/runner-groups/2/runners")
+            .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{ "total_count": 101, "runners": [{}] }}"#,
+                runner_json(101)
+            ))
+            .expect(1)
+            .create();
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        let runners = org_req.get_runner_group_runners(2).await?;
+
+        m1.assert();
+        m2.assert();
+        assert_eq!(runners.total_count, 101);
+        assert_eq!(runners.runners.len(), 101);
+        assert_eq!(runners.runners[100].name, "runner-101");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_a_runner() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("DELETE", "/repos/aslamplr/gh-cli/actions/runners/23")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(204)
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req.delete_a_runner(23).await?;
+
+        m.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_registration_token() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock(
+            "POST",
+            "/repos/aslamplr/gh-cli/actions/runners/registration-token",
+        )
+        .match_header(
+            "Authorization",
+            Matcher::Exact(format!("Bearer {}", auth_token)),
+        )
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "token": "LLBF3JGZDX3P5PMEXLND6TS6FCWO6",
+                "expires_at": "2020-01-22T12:13:35.123-08:00"
+              }"#,
+        )
+        .expect(1)
+        .create();
+
+        let expected_token = RunnerToken {
+            token: "LLBF3JGZDX3P5PMEXLND6TS6FCWO6".into(),
+            expires_at: "2020-01-22T12:13:35.123-08:00".parse()?,
+        };
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let token = repo_req.create_registration_token().await?;
+
+        m.assert();
+        assert_eq!(token, expected_token);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_runner_downloads() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/aslamplr/gh-cli/actions/runners/downloads")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                  {
+                    "os": "osx",
+                    "architecture": "x64",
+                    "download_url": "https://github.com/actions/runner/releases/download/v2.164.0/actions-runner-osx-x64-2.164.0.tar.gz",
+                    "filename": "actions-runner-osx-x64-2.164.0.tar.gz"
+                  }
+                ]"#,
+            )
+            .expect(1)
+            .create();
+
+        let expected_downloads = vec![RunnerApplication {
+            os: "osx".into(),
+            architecture: "x64".into(),
+            download_url: "https://github.com/actions/runner/releases/download/v2.164.0/actions-runner-osx-x64-2.164.0.tar.gz".into(),
+            filename: "actions-runner-osx-x64-2.164.0.tar.gz".into(),
+        }];
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let downloads = repo_req.get_runner_downloads().await?;
+
+        m.assert();
+        assert_eq!(downloads, expected_downloads);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_runner_labels() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("POST", "/repos/aslamplr/gh-cli/actions/runners/24/labels")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_body(Matcher::Json(serde_json::json!({ "labels": ["gpu"] })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "total_count": 2,
+                "labels": [
+                  { "id": 5, "name": "self-hosted", "type": "read-only" },
+                  { "id": 9, "name": "gpu", "type": "custom" }
+                ]
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let labels = repo_req.add_runner_labels(24, &["gpu"]).await?;
+
+        m.assert();
+        assert_eq!(
+            labels,
+            RunnerLabelList {
+                total_count: 2,
+                labels: vec![
+                    label(5, "self-hosted", "read-only"),
+                    label(9, "gpu", "custom")
+                ],
+            }
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_runner_labels() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("PUT", "/repos/aslamplr/gh-cli/actions/runners/24/labels")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_body(Matcher::Json(
+                serde_json::json!({ "labels": ["gpu", "large"] }),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "total_count": 2,
+                "labels": [
+                  { "id": 9, "name": "gpu", "type": "custom" },
+                  { "id": 10, "name": "large", "type": "custom" }
+                ]
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let labels = repo_req.set_runner_labels(24, &["gpu", "large"]).await?;
+
+        m.assert();
+        assert_eq!(labels.total_count, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn remove_runner_label() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock(
+            "DELETE",
+            "/repos/aslamplr/gh-cli/actions/runners/24/labels/gpu",
+        )
+        .match_header(
+            "Authorization",
+            Matcher::Exact(format!("Bearer {}", auth_token)),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "total_count": 1,
+                "labels": [
+                  { "id": 5, "name": "self-hosted", "type": "read-only" }
+                ]
+              }"#,
+        )
+        .expect(1)
+        .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let labels = repo_req.remove_runner_label(24, "gpu").await?;
+
+        m.assert();
+        assert_eq!(labels.total_count, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_all_runner_groups() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/orgs/octo-org/actions/runner-groups")
+            .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "total_count": 1,
+                "runner_groups": [
+                  {
+                    "id": 1,
+                    "name": "Default",
+                    "visibility": "all",
+                    "default": true,
+                    "runners_url": "https://api.github.com/orgs/octo-org/actions/runner-groups/1/runners",
+                    "inherited": false,
+                    "allows_public_repositories": true
+                  }
+                ]
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let expected_groups = RunnerGroupList {
+            total_count: 1,
+            runner_groups: vec![RunnerGroup {
+                id: 1,
+                name: "Default".into(),
+                visibility: "all".into(),
+                default: true,
+                runners_url: "https://api.github.com/orgs/octo-org/actions/runner-groups/1/runners"
+                    .into(),
+                inherited: false,
+                allows_public_repositories: true,
+            }],
+        };
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        let groups = org_req.get_all_runner_groups().await?;
+
+        m.assert();
+        assert_eq!(groups, expected_groups);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_runner_to_group() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("PUT", "/orgs/octo-org/actions/runner-groups/2/runners/24")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(204)
+            .expect(1)
+            .create();
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        org_req.add_runner_to_group(2, 24).await?;
+
+        m.assert();
+        Ok(())
+    }
+}
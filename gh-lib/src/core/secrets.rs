@@ -1,9 +1,11 @@
 #![cfg(feature = "secrets")]
 use super::repos::RepoRequest;
+#[cfg(feature = "secrets-ledger")]
+use super::secrets_ledger::SecretsLedger;
 #[cfg(feature = "secrets-save")]
-use crate::utils::http::HttpBody;
+use crate::utils::http::{HttpBody, HttpError, StatusCode};
 #[cfg(feature = "secrets-save")]
-use crate::utils::sealed_box::seal;
+pub use crate::utils::sealed_box::SecretEncryptor;
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -17,7 +19,18 @@ pub trait Secrets {
     async fn get_all_secrets(&self) -> Result<SecretListResponse>;
     async fn get_a_secret(&self, name: &str) -> Result<Secret>;
     #[cfg(feature = "secrets-save")]
+    async fn get_secret_encryptor(&self) -> Result<SecretEncryptor>;
+    #[cfg(feature = "secrets-save")]
     async fn save_secret(&self, name: &str, value: &str) -> Result<()>;
+    #[cfg(feature = "secrets-save")]
+    async fn save_secret_bytes(&self, name: &str, value: &[u8]) -> Result<()>;
+    #[cfg(feature = "secrets-ledger")]
+    async fn save_secret_with_ledger(
+        &self,
+        name: &str,
+        value: &str,
+        ledger: &mut SecretsLedger,
+    ) -> Result<()>;
     async fn delete_a_secret(&self, name: &str) -> Result<()>;
 }
 
@@ -35,11 +48,49 @@ impl Secrets for RepoRequest<'_> {
         get_a_secret(&self, &name).await
     }
 
+    #[cfg(feature = "secrets-save")]
+    async fn get_secret_encryptor(&self) -> Result<SecretEncryptor> {
+        if let Some(encryptor) = self.secret_encryptor.get() {
+            return Ok(encryptor);
+        }
+        let PublicKeyResponse { key_id, key } = self.get_public_key().await?;
+        let encryptor = SecretEncryptor::try_from(&key_id, &key)?;
+        self.secret_encryptor.set(encryptor.clone());
+        Ok(encryptor)
+    }
+
     #[cfg(feature = "secrets-save")]
     async fn save_secret(&self, name: &str, value: &str) -> Result<()> {
-        let public_key = self.get_public_key().await?;
-        let secret_save_req = SecretSaveRequest::from(name, value, public_key)?;
-        secret_save_req.make_api_call(&self).await
+        self.save_secret_bytes(name, value.as_bytes()).await
+    }
+
+    #[cfg(feature = "secrets-save")]
+    async fn save_secret_bytes(&self, name: &str, value: &[u8]) -> Result<()> {
+        let encryptor = self.get_secret_encryptor().await?;
+        let secret_save_req = SecretSaveRequest::from(name, value, &encryptor);
+        match secret_save_req.make_api_call(&self).await {
+            Err(err) if is_stale_key_error(&err) => {
+                // Public key got rotated since it was cached, retry once with a fresh key.
+                self.secret_encryptor.invalidate();
+                let encryptor = self.get_secret_encryptor().await?;
+                let secret_save_req = SecretSaveRequest::from(name, value, &encryptor);
+                secret_save_req.make_api_call(&self).await
+            }
+            result => result,
+        }
+    }
+
+    #[cfg(feature = "secrets-ledger")]
+    async fn save_secret_with_ledger(
+        &self,
+        name: &str,
+        value: &str,
+        ledger: &mut SecretsLedger,
+    ) -> Result<()> {
+        self.save_secret(name, value).await?;
+        let secret = self.get_a_secret(name).await?;
+        ledger.record(&self.repo.to_string(), &secret, value.as_bytes());
+        Ok(())
     }
 
     async fn delete_a_secret(&self, name: &str) -> Result<()> {
@@ -79,22 +130,16 @@ struct SecretSaveRequest {
     key: String,
     encrypted_value: String,
     key_id: String,
-    #[serde(skip_serializing)]
-    public_key: PublicKeyResponse,
 }
 
 #[cfg(feature = "secrets-save")]
 impl SecretSaveRequest {
-    fn from(key: &str, value: &str, public_key: PublicKeyResponse) -> Result<Self> {
-        let encrypted_value = seal(value, &public_key.key)?;
-        let key = key.into();
-        let key_id = public_key.key_id.to_owned();
-        Ok(SecretSaveRequest {
-            key,
-            encrypted_value,
-            key_id,
-            public_key,
-        })
+    fn from(key: &str, value: &[u8], encryptor: &SecretEncryptor) -> Self {
+        SecretSaveRequest {
+            key: key.into(),
+            encrypted_value: encryptor.encrypt(value),
+            key_id: encryptor.key_id().to_owned(),
+        }
     }
 
     async fn make_api_call(&self, params: &RepoRequest<'_>) -> Result<()> {
@@ -102,11 +147,27 @@ impl SecretSaveRequest {
     }
 }
 
+/// GitHub rejects a secret sealed with a rotated public key with an unprocessable entity or a
+/// bad request response mentioning the `key_id` or the `encrypted_value`, other validation
+/// errors such as an invalid name are not worth a retry.
+#[cfg(feature = "secrets-save")]
+fn is_stale_key_error(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<HttpError>() {
+        Some(HttpError { status, body }) => {
+            (*status == StatusCode::UNPROCESSABLE_ENTITY || *status == StatusCode::BAD_REQUEST)
+                && (body.contains("key_id") || body.contains("encrypted_value"))
+        }
+        None => false,
+    }
+}
+
 async fn get_from_gh<T>(path: &str, params: &RepoRequest<'_>) -> Result<T>
 where
     T: serde::de::DeserializeOwned,
 {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/{}", repo, path);
     let resp = http_client.get(&url).await?;
     let resp = resp.deserialize().await?;
@@ -131,7 +192,9 @@ async fn put_gh_secret(
     name: &str,
     secret_save_req: &SecretSaveRequest,
 ) -> Result<()> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/secrets/{}", repo, name);
     http_client
         .put(&url, HttpBody::try_from_serialize(&secret_save_req)?)
@@ -140,7 +203,9 @@ async fn put_gh_secret(
 }
 
 async fn delete_a_secret(params: &RepoRequest<'_>, name: &str) -> Result<()> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/secrets/{}", repo, name);
     http_client.delete(&url).await?;
     Ok(())
@@ -328,6 +393,211 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "secrets-save")]
+    fn generate_public_key_base64() -> String {
+        use sodiumoxide::crypto::box_::{curve25519xsalsa20poly1305::PublicKey, gen_keypair};
+
+        let (pk, _) = gen_keypair();
+        let PublicKey(pk_bytes) = pk;
+        base64::encode(pk_bytes)
+    }
+
+    #[cfg(feature = "secrets-save")]
+    #[tokio::test]
+    async fn save_secrets_reuses_public_key() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m1 = mock("GET", "/aslamplr/gh-cli/actions/secrets/public-key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{
+                "key_id": "012345678912345678",
+                "key": "{}"
+              }}"#,
+                generate_public_key_base64()
+            ))
+            .expect(1)
+            .create();
+
+        let m2 = mock(
+            "PUT",
+            Matcher::Regex(r"^/aslamplr/gh-cli/actions/secrets/BULK_\d$".to_string()),
+        )
+        .match_body(Matcher::Regex("012345678912345678".to_string()))
+        .with_status(201)
+        .expect(3)
+        .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req.save_secret("BULK_1", "SECRET").await?;
+        repo_req.save_secret("BULK_2", "SECRET").await?;
+        repo_req
+            .save_secret_bytes("BULK_3", &[0u8, 159, 146, 150])
+            .await?;
+
+        m1.assert();
+        m2.assert();
+        Ok(())
+    }
+
+    #[cfg(feature = "secrets-save")]
+    #[tokio::test]
+    async fn save_secret_retries_with_fresh_public_key() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m1 = mock("GET", "/aslamplr/gh-cli/actions/secrets/public-key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{
+                "key_id": "stale-key-id",
+                "key": "{}"
+              }}"#,
+                generate_public_key_base64()
+            ))
+            .expect(1)
+            .create();
+
+        let m2 = mock("GET", "/aslamplr/gh-cli/actions/secrets/public-key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{
+                "key_id": "fresh-key-id",
+                "key": "{}"
+              }}"#,
+                generate_public_key_base64()
+            ))
+            .expect(1)
+            .create();
+
+        let m3 = mock("PUT", "/aslamplr/gh-cli/actions/secrets/ROTATED")
+            .match_body(Matcher::Regex("stale-key-id".to_string()))
+            .with_status(422)
+            .with_body(r#"{"message": "Bad request - key_id is invalid"}"#)
+            .expect(1)
+            .create();
+
+        let m4 = mock("PUT", "/aslamplr/gh-cli/actions/secrets/ROTATED")
+            .match_body(Matcher::Regex("fresh-key-id".to_string()))
+            .with_status(201)
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req.save_secret("ROTATED", "SECRET").await?;
+
+        m1.assert();
+        m2.assert();
+        m3.assert();
+        m4.assert();
+        Ok(())
+    }
+
+    #[cfg(feature = "secrets-save")]
+    #[tokio::test]
+    async fn save_secret_does_not_retry_validation_errors() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m1 = mock("GET", "/aslamplr/gh-cli/actions/secrets/public-key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{
+                "key_id": "012345678912345678",
+                "key": "{}"
+              }}"#,
+                generate_public_key_base64()
+            ))
+            .expect(1)
+            .create();
+
+        let m2 = mock("PUT", "/aslamplr/gh-cli/actions/secrets/GITHUB_NAME")
+            .with_status(422)
+            .with_body(r#"{"message": "Secret names must not start with GITHUB_."}"#)
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let err = repo_req
+            .save_secret("GITHUB_NAME", "SECRET")
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("must not start with GITHUB_"));
+        m1.assert();
+        m2.assert();
+        Ok(())
+    }
+
+    #[cfg(feature = "secrets-ledger")]
+    #[tokio::test]
+    async fn save_secret_with_ledger() -> Result<()> {
+        let public_key_base64 = {
+            use sodiumoxide::crypto::box_::{curve25519xsalsa20poly1305::PublicKey, gen_keypair};
+
+            let (pk, _) = gen_keypair();
+            let PublicKey(pk_bytes) = pk;
+            base64::encode(pk_bytes)
+        };
+
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m1 = mock("GET", "/aslamplr/gh-cli/actions/secrets/public-key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{
+                "key_id": "012345678912345678",
+                "key": "{}"
+              }}"#,
+                public_key_base64
+            ))
+            .expect(1)
+            .create();
+
+        let m2 = mock("PUT", "/aslamplr/gh-cli/actions/secrets/LEDGER_TOKEN")
+            .with_status(201)
+            .expect(1)
+            .create();
+
+        let m3 = mock("GET", "/aslamplr/gh-cli/actions/secrets/LEDGER_TOKEN")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "name": "LEDGER_TOKEN",
+                    "created_at": "2019-08-10T14:59:22Z",
+                    "updated_at": "2020-01-10T14:59:22Z"
+                  }"#,
+            )
+            .expect(1)
+            .create();
+
+        let mut ledger = SecretsLedger::default();
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req
+            .save_secret_with_ledger("LEDGER_TOKEN", "SECRET", &mut ledger)
+            .await?;
+
+        m1.assert();
+        m2.assert();
+        m3.assert();
+        let entry = ledger
+            .entry(repo_addr, "LEDGER_TOKEN")
+            .ok_or_else(|| anyhow::anyhow!("ledger entry not recorded"))?;
+        assert_eq!(
+            entry.updated_at,
+            "2020-01-10T14:59:22Z".parse::<chrono::DateTime<chrono::Utc>>()?
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn delete_a_secret() -> Result<()> {
         let repo_addr = "aslamplr/gh-cli";
@@ -0,0 +1,315 @@
+#![cfg(feature = "secrets-ledger")]
+use super::secrets::Secret;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sodiumoxide::crypto::auth::hmacsha256;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Local record of the HMAC-SHA-256 digests of secret values pushed to GitHub.
+///
+/// GitHub never gives back secret values, the ledger remembers what was pushed and when,
+/// so that the secrets can later be verified against the remote `updated_at` and local sources.
+/// The digests are keyed with a random key of the ledger, a plain hash of a short secret would
+/// give it away to whoever tries the likely values.
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct SecretsLedger {
+    #[serde(serialize_with = "to_base64", deserialize_with = "from_base64")]
+    key: hmacsha256::Key,
+    entries: Vec<SecretsLedgerEntry>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct SecretsLedgerEntry {
+    pub repo: String,
+    pub name: String,
+    pub hmac_sha256: String,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SecretStatus {
+    /// Remote secret is the one recorded in the ledger and matches the local source if any.
+    UpToDate,
+    /// Remote secret was updated after it was recorded in the ledger.
+    ChangedOutOfBand,
+    /// Local source value differs from the value recorded in the ledger.
+    Stale,
+    /// Secret is recorded in the ledger but no longer exists on GitHub.
+    Missing,
+    /// Secret exists on GitHub but was never recorded in the ledger.
+    Untracked,
+}
+
+impl std::fmt::Display for SecretStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let status = match self {
+            Self::UpToDate => "up to date",
+            Self::ChangedOutOfBand => "changed out-of-band",
+            Self::Stale => "stale",
+            Self::Missing => "missing",
+            Self::Untracked => "untracked",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SecretVerification {
+    pub name: String,
+    pub status: SecretStatus,
+}
+
+impl Default for SecretsLedger {
+    /// Empty ledger, with a new random key.
+    fn default() -> Self {
+        let _ = sodiumoxide::init();
+        SecretsLedger {
+            key: hmacsha256::gen_key(),
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl SecretsLedger {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            let content = std::fs::read(path)?;
+            serde_json::from_slice(&content).map_err(|err| {
+                anyhow!(
+                    "Unable to read the secrets ledger {}, {}",
+                    path.display(),
+                    err
+                )
+            })
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Writes the ledger readable by its owner only, as it holds the key of the digests.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        use std::io::Write;
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(path)?;
+        // The mode only applies to new files
+        #[cfg(unix)]
+        file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+        file.write_all(&serde_json::to_vec_pretty(&self)?)?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[SecretsLedgerEntry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, repo: &str, name: &str) -> Option<&SecretsLedgerEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.repo == repo && e.name == name)
+    }
+
+    /// Records the digest of `value` for the just saved `secret`, replacing any earlier entry.
+    pub fn record(&mut self, repo: &str, secret: &Secret, value: &[u8]) {
+        let entry = SecretsLedgerEntry {
+            repo: repo.to_owned(),
+            name: secret.name.to_owned(),
+            hmac_sha256: self.digest(value),
+            updated_at: secret.updated_at,
+        };
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.repo == repo && e.name == secret.name)
+        {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Compares the remote `secrets` of `repo` and the optional `local_values` against the ledger.
+    pub fn verify(
+        &self,
+        repo: &str,
+        secrets: &[Secret],
+        local_values: &HashMap<String, String>,
+    ) -> Vec<SecretVerification> {
+        let mut verifications = secrets
+            .iter()
+            .map(|secret| {
+                let status = match self.entry(repo, &secret.name) {
+                    None => SecretStatus::Untracked,
+                    Some(entry) if entry.updated_at != secret.updated_at => {
+                        SecretStatus::ChangedOutOfBand
+                    }
+                    Some(entry) => match local_values.get(&secret.name) {
+                        Some(value) if self.digest(value.as_bytes()) != entry.hmac_sha256 => {
+                            SecretStatus::Stale
+                        }
+                        _ => SecretStatus::UpToDate,
+                    },
+                };
+                SecretVerification {
+                    name: secret.name.to_owned(),
+                    status,
+                }
+            })
+            .collect::<Vec<_>>();
+        verifications.extend(
+            self.entries
+                .iter()
+                .filter(|e| e.repo == repo && !secrets.iter().any(|s| s.name == e.name))
+                .map(|e| SecretVerification {
+                    name: e.name.to_owned(),
+                    status: SecretStatus::Missing,
+                }),
+        );
+        verifications
+    }
+
+    fn digest(&self, value: &[u8]) -> String {
+        hmacsha256::authenticate(value, &self.key)
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+fn to_base64<S>(key: &hmacsha256::Key, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&base64::encode(key))
+}
+
+fn from_base64<'de, D>(deserializer: D) -> std::result::Result<hmacsha256::Key, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let key = String::deserialize(deserializer)?;
+    base64::decode(&key)
+        .ok()
+        .and_then(|key| hmacsha256::Key::from_slice(&key))
+        .ok_or_else(|| serde::de::Error::custom("invalid key of the digests"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(name: &str, updated_at: &str) -> Result<Secret> {
+        Ok(Secret {
+            name: name.into(),
+            created_at: "2019-08-10T14:59:22Z".parse()?,
+            updated_at: updated_at.parse()?,
+        })
+    }
+
+    #[test]
+    fn record_replaces_existing_entry() -> Result<()> {
+        let mut ledger = SecretsLedger::default();
+        ledger.record(
+            "aslamplr/gh-cli",
+            &secret("GH_TOKEN", "2020-01-10T14:59:22Z")?,
+            b"first",
+        );
+        ledger.record(
+            "aslamplr/gh-cli",
+            &secret("GH_TOKEN", "2020-01-11T14:59:22Z")?,
+            b"second",
+        );
+
+        assert_eq!(ledger.entries().len(), 1);
+        let entry = ledger.entry("aslamplr/gh-cli", "GH_TOKEN").unwrap();
+        assert_eq!(entry.hmac_sha256, ledger.digest(b"second"));
+        // Keyed per ledger, the digest can't be matched against the hashes of guesses.
+        assert_ne!(
+            entry.hmac_sha256,
+            SecretsLedger::default().digest(b"second")
+        );
+        assert_eq!(
+            entry.updated_at,
+            "2020-01-11T14:59:22Z".parse::<chrono::DateTime<chrono::Utc>>()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn verify_secrets() -> Result<()> {
+        let repo = "aslamplr/gh-cli";
+        let mut ledger = SecretsLedger::default();
+        ledger.record(repo, &secret("UP_TO_DATE", "2020-01-10T14:59:22Z")?, b"a");
+        ledger.record(repo, &secret("CHANGED", "2020-01-10T14:59:22Z")?, b"b");
+        ledger.record(repo, &secret("STALE", "2020-01-10T14:59:22Z")?, b"c");
+        ledger.record(repo, &secret("MISSING", "2020-01-10T14:59:22Z")?, b"d");
+        ledger.record(
+            "octo-org/octo-repo",
+            &secret("OTHER", "2020-01-10T14:59:22Z")?,
+            b"e",
+        );
+
+        let secrets = vec![
+            secret("UP_TO_DATE", "2020-01-10T14:59:22Z")?,
+            secret("CHANGED", "2020-02-10T14:59:22Z")?,
+            secret("STALE", "2020-01-10T14:59:22Z")?,
+            secret("UNTRACKED", "2020-01-10T14:59:22Z")?,
+        ];
+        let local_values = vec![
+            ("UP_TO_DATE".to_owned(), "a".to_owned()),
+            ("STALE".to_owned(), "c-rotated".to_owned()),
+        ]
+        .into_iter()
+        .collect();
+
+        let verifications = ledger.verify(repo, &secrets, &local_values);
+
+        let expected = vec![
+            ("UP_TO_DATE", SecretStatus::UpToDate),
+            ("CHANGED", SecretStatus::ChangedOutOfBand),
+            ("STALE", SecretStatus::Stale),
+            ("UNTRACKED", SecretStatus::Untracked),
+            ("MISSING", SecretStatus::Missing),
+        ]
+        .into_iter()
+        .map(|(name, status)| SecretVerification {
+            name: name.into(),
+            status,
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(verifications, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn save_and_load() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("gh-lib-ledger-{}.json", std::process::id()));
+        let mut ledger = SecretsLedger::default();
+        ledger.record(
+            "aslamplr/gh-cli",
+            &secret("GH_TOKEN", "2020-01-10T14:59:22Z")?,
+            b"value",
+        );
+        ledger.save(&path)?;
+        #[cfg(unix)]
+        assert_eq!(
+            std::os::unix::fs::PermissionsExt::mode(&std::fs::metadata(&path)?.permissions())
+                & 0o777,
+            0o600
+        );
+        let loaded = SecretsLedger::load(&path)?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(loaded, ledger);
+        assert!(SecretsLedger::load(&path)?.entries().is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+#![cfg(feature = "users")]
+use crate::token::AuthToken;
+use crate::utils::http::HttpClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
+
+/// Scopes granting other scopes, eg. `admin:org` includes `write:org` which includes `read:org`.
+/// https://docs.github.com/en/developers/apps/scopes-for-oauth-apps#available-scopes
+const IMPLIED_SCOPES: &[(&str, &[&str])] = &[
+    (
+        "repo",
+        &[
+            "repo:status",
+            "repo_deployment",
+            "public_repo",
+            "repo:invite",
+            "security_events",
+        ],
+    ),
+    ("admin:org", &["write:org", "read:org"]),
+    ("write:org", &["read:org"]),
+    ("admin:public_key", &["write:public_key", "read:public_key"]),
+    ("write:public_key", &["read:public_key"]),
+    ("admin:repo_hook", &["write:repo_hook", "read:repo_hook"]),
+    ("write:repo_hook", &["read:repo_hook"]),
+    ("user", &["read:user", "user:email", "user:follow"]),
+    ("write:packages", &["read:packages"]),
+    ("admin:gpg_key", &["write:gpg_key", "read:gpg_key"]),
+    ("write:gpg_key", &["read:gpg_key"]),
+];
+
+/// Requests on behalf of the owner of the token.
+#[derive(Debug)]
+pub struct UserRequest {
+    pub(crate) http_client: HttpClient,
+}
+
+impl UserRequest {
+    pub fn try_from(auth_token: impl Into<AuthToken>) -> Result<Self> {
+        let http_client = HttpClient::new(auth_token)?;
+        Ok(UserRequest { http_client })
+    }
+}
+
+#[async_trait]
+pub trait Users {
+    async fn get_authenticated_user(&self) -> Result<AuthenticatedUser>;
+}
+
+#[async_trait]
+impl Users for UserRequest {
+    async fn get_authenticated_user(&self) -> Result<AuthenticatedUser> {
+        let UserRequest { http_client } = self;
+        let url = with_base_url!("user");
+        let resp = http_client.get(&url).await?;
+        let scopes = resp.get_header("X-OAuth-Scopes").map(|scopes| {
+            scopes
+                .split(',')
+                .map(str::trim)
+                .filter(|scope| !scope.is_empty())
+                .map(String::from)
+                .collect()
+        });
+        Ok(AuthenticatedUser {
+            user: resp.deserialize().await?,
+            scopes,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AuthenticatedUser {
+    pub user: User,
+    /// Scopes of classic tokens, `None` for fine-grained and GitHub App tokens which report none.
+    pub scopes: Option<Vec<String>>,
+}
+
+impl AuthenticatedUser {
+    /// The `required` scopes neither granted nor implied by a granted scope.
+    pub fn missing_scopes<'a>(&self, required: &[&'a str]) -> Vec<&'a str> {
+        let scopes = match &self.scopes {
+            Some(scopes) => scopes,
+            None => return Vec::new(),
+        };
+        required
+            .iter()
+            .filter(|&&required| {
+                !scopes.iter().any(|scope| {
+                    scope == required
+                        || IMPLIED_SCOPES.iter().any(|(granted, implied)| {
+                            scope == granted && implied.contains(&required)
+                        })
+                })
+            })
+            .copied()
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct User {
+    pub login: String,
+    pub id: u32,
+    pub name: Option<String>,
+    pub html_url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn get_authenticated_user() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/user")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("X-OAuth-Scopes", "repo, admin:org, gist")
+            .with_body(
+                r#"{
+                "login": "octocat",
+                "id": 1,
+                "name": "monalisa octocat",
+                "html_url": "https://github.com/octocat",
+                "type": "User"
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let user_req = UserRequest::try_from(auth_token)?;
+        let authenticated = user_req.get_authenticated_user().await?;
+
+        m.assert();
+        assert_eq!(
+            authenticated,
+            AuthenticatedUser {
+                user: User {
+                    login: "octocat".into(),
+                    id: 1,
+                    name: Some("monalisa octocat".into()),
+                    html_url: "https://github.com/octocat".into(),
+                },
+                scopes: Some(vec!["repo".into(), "admin:org".into(), "gist".into()]),
+            }
+        );
+        assert_eq!(
+            authenticated.missing_scopes(&["read:org", "public_repo", "workflow"]),
+            vec!["workflow"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,459 @@
+#![cfg(feature = "variables")]
+use super::orgs::OrgRequest;
+use super::repos::{EnvironmentRequest, RepoRequest};
+use crate::utils::http::{HttpBody, HttpClient};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(test))]
+const BASE_URL: &str = crate::BASE_URL;
+
+/// Org level variables are only visible to private repositories unless told otherwise,
+/// same as the default on github.com.
+const DEFAULT_ORG_VISIBILITY: &str = "private";
+
+#[async_trait]
+pub trait Variables {
+    async fn get_all_variables(&self) -> Result<VariableListResponse>;
+    async fn get_a_variable(&self, name: &str) -> Result<Variable>;
+    async fn create_variable(&self, name: &str, value: &str) -> Result<()>;
+    async fn update_variable(&self, name: &str, value: &str) -> Result<()>;
+    async fn delete_a_variable(&self, name: &str) -> Result<()>;
+}
+
+#[async_trait]
+impl Variables for RepoRequest<'_> {
+    async fn get_all_variables(&self) -> Result<VariableListResponse> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        get_all_variables(http_client, &format!("repos/{}/actions", repo)).await
+    }
+
+    async fn get_a_variable(&self, name: &str) -> Result<Variable> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        get_a_variable(http_client, &format!("repos/{}/actions", repo), name).await
+    }
+
+    async fn create_variable(&self, name: &str, value: &str) -> Result<()> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let variable = VariableSaveRequest::from(name, value, None);
+        create_variable(http_client, &format!("repos/{}/actions", repo), &variable).await
+    }
+
+    async fn update_variable(&self, name: &str, value: &str) -> Result<()> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        let variable = VariableSaveRequest::from(name, value, None);
+        update_variable(http_client, &format!("repos/{}/actions", repo), &variable).await
+    }
+
+    async fn delete_a_variable(&self, name: &str) -> Result<()> {
+        let RepoRequest {
+            repo, http_client, ..
+        } = self;
+        delete_a_variable(http_client, &format!("repos/{}/actions", repo), name).await
+    }
+}
+
+#[async_trait]
+impl Variables for EnvironmentRequest<'_> {
+    async fn get_all_variables(&self) -> Result<VariableListResponse> {
+        let path = environment_path(self);
+        get_all_variables(&self.http_client, &path).await
+    }
+
+    async fn get_a_variable(&self, name: &str) -> Result<Variable> {
+        let path = environment_path(self);
+        get_a_variable(&self.http_client, &path, name).await
+    }
+
+    async fn create_variable(&self, name: &str, value: &str) -> Result<()> {
+        let path = environment_path(self);
+        let variable = VariableSaveRequest::from(name, value, None);
+        create_variable(&self.http_client, &path, &variable).await
+    }
+
+    async fn update_variable(&self, name: &str, value: &str) -> Result<()> {
+        let path = environment_path(self);
+        let variable = VariableSaveRequest::from(name, value, None);
+        update_variable(&self.http_client, &path, &variable).await
+    }
+
+    async fn delete_a_variable(&self, name: &str) -> Result<()> {
+        let path = environment_path(self);
+        delete_a_variable(&self.http_client, &path, name).await
+    }
+}
+
+#[async_trait]
+impl Variables for OrgRequest<'_> {
+    async fn get_all_variables(&self) -> Result<VariableListResponse> {
+        let OrgRequest { org, http_client } = self;
+        get_all_variables(http_client, &format!("orgs/{}/actions", org)).await
+    }
+
+    async fn get_a_variable(&self, name: &str) -> Result<Variable> {
+        let OrgRequest { org, http_client } = self;
+        get_a_variable(http_client, &format!("orgs/{}/actions", org), name).await
+    }
+
+    async fn create_variable(&self, name: &str, value: &str) -> Result<()> {
+        let OrgRequest { org, http_client } = self;
+        let variable = VariableSaveRequest::from(name, value, Some(DEFAULT_ORG_VISIBILITY));
+        create_variable(http_client, &format!("orgs/{}/actions", org), &variable).await
+    }
+
+    async fn update_variable(&self, name: &str, value: &str) -> Result<()> {
+        let OrgRequest { org, http_client } = self;
+        let variable = VariableSaveRequest::from(name, value, None);
+        update_variable(http_client, &format!("orgs/{}/actions", org), &variable).await
+    }
+
+    async fn delete_a_variable(&self, name: &str) -> Result<()> {
+        let OrgRequest { org, http_client } = self;
+        delete_a_variable(http_client, &format!("orgs/{}/actions", org), name).await
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct Variable {
+    pub name: String,
+    pub value: String,
+    #[cfg(feature = "chrono")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[cfg(not(feature = "chrono"))]
+    pub created_at: String,
+    #[cfg(feature = "chrono")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    #[cfg(not(feature = "chrono"))]
+    pub updated_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected_repositories_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct VariableListResponse {
+    pub total_count: u32,
+    pub variables: Vec<Variable>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct VariableSaveRequest<'a> {
+    name: &'a str,
+    value: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visibility: Option<&'a str>,
+}
+
+impl<'a> VariableSaveRequest<'a> {
+    fn from(name: &'a str, value: &'a str, visibility: Option<&'a str>) -> Self {
+        VariableSaveRequest {
+            name,
+            value,
+            visibility,
+        }
+    }
+}
+
+fn environment_path(params: &EnvironmentRequest<'_>) -> String {
+    let EnvironmentRequest { repo, env_name, .. } = params;
+    format!("repos/{}/environments/{}", repo, env_name)
+}
+
+async fn get_all_variables(http_client: &HttpClient, path: &str) -> Result<VariableListResponse> {
+    let url = with_base_url!("{}/variables", path);
+    http_client.get(&url).await?.deserialize().await
+}
+
+async fn get_a_variable(http_client: &HttpClient, path: &str, name: &str) -> Result<Variable> {
+    let url = with_base_url!("{}/variables/{}", path, name);
+    http_client.get(&url).await?.deserialize().await
+}
+
+async fn create_variable(
+    http_client: &HttpClient,
+    path: &str,
+    variable: &VariableSaveRequest<'_>,
+) -> Result<()> {
+    let url = with_base_url!("{}/variables", path);
+    http_client
+        .post(&url, HttpBody::try_from_serialize(&variable)?)
+        .await?;
+    Ok(())
+}
+
+async fn update_variable(
+    http_client: &HttpClient,
+    path: &str,
+    variable: &VariableSaveRequest<'_>,
+) -> Result<()> {
+    let url = with_base_url!("{}/variables/{}", path, variable.name);
+    http_client
+        .patch(&url, HttpBody::try_from_serialize(&variable)?)
+        .await?;
+    Ok(())
+}
+
+async fn delete_a_variable(http_client: &HttpClient, path: &str, name: &str) -> Result<()> {
+    let url = with_base_url!("{}/variables/{}", path, name);
+    http_client.delete(&url).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+
+    #[tokio::test]
+    async fn get_all_variables() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/aslamplr/gh-cli/actions/variables")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "total_count": 2,
+                "variables": [
+                  {
+                    "name": "USERNAME",
+                    "value": "octocat",
+                    "created_at": "2019-08-10T14:59:22Z",
+                    "updated_at": "2020-01-10T14:59:22Z"
+                  },
+                  {
+                    "name": "EMAIL",
+                    "value": "octocat@github.com",
+                    "created_at": "2020-01-10T10:59:22Z",
+                    "updated_at": "2020-01-11T11:59:22Z"
+                  }
+                ]
+              }"#,
+            )
+            .expect(1)
+            .create();
+
+        let expected_variables = VariableListResponse {
+            total_count: 2,
+            variables: vec![
+                Variable {
+                    name: "USERNAME".into(),
+                    value: "octocat".into(),
+                    created_at: "2019-08-10T14:59:22Z".parse()?,
+                    updated_at: "2020-01-10T14:59:22Z".parse()?,
+                    visibility: None,
+                    selected_repositories_url: None,
+                },
+                Variable {
+                    name: "EMAIL".into(),
+                    value: "octocat@github.com".into(),
+                    created_at: "2020-01-10T10:59:22Z".parse()?,
+                    updated_at: "2020-01-11T11:59:22Z".parse()?,
+                    visibility: None,
+                    selected_repositories_url: None,
+                },
+            ],
+        };
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let variables = repo_req.get_all_variables().await?;
+
+        m.assert();
+        assert_eq!(variables, expected_variables);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_a_variable() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("GET", "/repos/aslamplr/gh-cli/actions/variables/USERNAME")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "name": "USERNAME",
+                    "value": "octocat",
+                    "created_at": "2019-08-10T14:59:22Z",
+                    "updated_at": "2020-01-10T14:59:22Z"
+                  }"#,
+            )
+            .expect(1)
+            .create();
+
+        let expected_variable = Variable {
+            name: "USERNAME".into(),
+            value: "octocat".into(),
+            created_at: "2019-08-10T14:59:22Z".parse()?,
+            updated_at: "2020-01-10T14:59:22Z".parse()?,
+            visibility: None,
+            selected_repositories_url: None,
+        };
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let variable = repo_req.get_a_variable("USERNAME").await?;
+
+        m.assert();
+        assert_eq!(variable, expected_variable);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_variable() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("POST", "/repos/aslamplr/gh-cli/actions/variables")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "USERNAME",
+                "value": "octocat"
+            })))
+            .with_status(201)
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req.create_variable("USERNAME", "octocat").await?;
+
+        m.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_variable() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("PATCH", "/repos/aslamplr/gh-cli/actions/variables/USERNAME")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "USERNAME",
+                "value": "monalisa"
+            })))
+            .with_status(204)
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req.update_variable("USERNAME", "monalisa").await?;
+
+        m.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_a_variable() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock(
+            "DELETE",
+            "/repos/aslamplr/gh-cli/actions/variables/USERNAME",
+        )
+        .match_header(
+            "Authorization",
+            Matcher::Exact(format!("Bearer {}", auth_token)),
+        )
+        .with_status(204)
+        .expect(1)
+        .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req.delete_a_variable("USERNAME").await?;
+
+        m.assert();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_all_environment_variables() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock(
+            "GET",
+            "/repos/aslamplr/gh-cli/environments/production/variables",
+        )
+        .match_header(
+            "Authorization",
+            Matcher::Exact(format!("Bearer {}", auth_token)),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "total_count": 1,
+                "variables": [
+                  {
+                    "name": "DEPLOY_URL",
+                    "value": "https://example.com",
+                    "created_at": "2019-08-10T14:59:22Z",
+                    "updated_at": "2020-01-10T14:59:22Z"
+                  }
+                ]
+              }"#,
+        )
+        .expect(1)
+        .create();
+
+        let env_req = EnvironmentRequest::try_from(repo_addr, "production", auth_token)?;
+        let variables = env_req.get_all_variables().await?;
+
+        m.assert();
+        assert_eq!(variables.total_count, 1);
+        assert_eq!(variables.variables[0].value, "https://example.com");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_org_variable() -> Result<()> {
+        let auth_token = "auth_secret_token";
+
+        let m = mock("POST", "/orgs/octo-org/actions/variables")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "USERNAME",
+                "value": "octocat",
+                "visibility": "private"
+            })))
+            .with_status(201)
+            .expect(1)
+            .create();
+
+        let org_req = OrgRequest::try_from("octo-org", auth_token)?;
+        org_req.create_variable("USERNAME", "octocat").await?;
+
+        m.assert();
+        Ok(())
+    }
+}
@@ -7,9 +7,13 @@ use serde::{Deserialize, Serialize};
 #[cfg(not(test))]
 const BASE_URL: &str = "https://api.github.com/repos";
 
+const PER_PAGE: usize = 100;
+
 #[async_trait]
 pub trait WorkflowJobs {
     async fn get_workflow_run_jobs(&self, run_id: u32) -> Result<WorkflowRunJobList>;
+    /// Jobs of every attempt of the run, not only of the latest one, walking all the pages.
+    async fn get_all_workflow_run_jobs(&self, run_id: u32) -> Result<Vec<WorkflowRunJob>>;
     async fn get_a_workflow_run_job(&self, job_id: u32) -> Result<WorkflowRunJob>;
     async fn get_job_logs_url(&self, job_id: u32) -> Result<String>;
 }
@@ -20,6 +24,10 @@ impl WorkflowJobs for RepoRequest<'_> {
         get_workflow_run_jobs(&self, run_id).await
     }
 
+    async fn get_all_workflow_run_jobs(&self, run_id: u32) -> Result<Vec<WorkflowRunJob>> {
+        get_all_workflow_run_jobs(&self, run_id).await
+    }
+
     async fn get_a_workflow_run_job(&self, job_id: u32) -> Result<WorkflowRunJob> {
         get_a_workflow_run_job(&self, job_id).await
     }
@@ -57,6 +65,9 @@ pub struct WorkflowRunJob {
     pub name: String,
     pub steps: Vec<WorkflowRunJobStep>,
     pub check_run_url: String,
+    /// Attempt of the run the job belongs to, missing from older GitHub Enterprise Servers.
+    #[serde(default)]
+    pub run_attempt: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -79,15 +90,47 @@ async fn get_workflow_run_jobs(
     params: &RepoRequest<'_>,
     run_id: u32,
 ) -> Result<WorkflowRunJobList> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/runs/{}/jobs", repo, run_id);
     let resp = http_client.get(&url).await?;
     let resp = resp.deserialize().await?;
     Ok(resp)
 }
 
+async fn get_all_workflow_run_jobs(
+    params: &RepoRequest<'_>,
+    run_id: u32,
+) -> Result<Vec<WorkflowRunJob>> {
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
+    let mut jobs = Vec::new();
+    let mut page = 1;
+    loop {
+        let url = with_base_url!(
+            "{}/actions/runs/{}/jobs?filter=all&per_page={}&page={}",
+            repo,
+            run_id,
+            PER_PAGE,
+            page
+        );
+        let resp: WorkflowRunJobList = http_client.get(&url).await?.deserialize().await?;
+        let fetched = resp.jobs.len();
+        jobs.extend(resp.jobs);
+        if fetched < PER_PAGE || jobs.len() >= resp.total_count as usize {
+            break;
+        }
+        page += 1;
+    }
+    Ok(jobs)
+}
+
 async fn get_a_workflow_run_job(params: &RepoRequest<'_>, job_id: u32) -> Result<WorkflowRunJob> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/jobs/{}", repo, job_id);
     let resp = http_client.get(&url).await?;
     let resp = resp.deserialize().await?;
@@ -95,7 +138,9 @@ async fn get_a_workflow_run_job(params: &RepoRequest<'_>, job_id: u32) -> Result
 }
 
 async fn get_job_logs_url(params: &RepoRequest<'_>, job_id: u32) -> Result<String> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/jobs/{}/logs", repo, job_id);
     let resp = http_client.get(&url).await?;
     let resp = resp.get_header("Location");
@@ -197,6 +242,7 @@ mod tests {
                 ],
                 check_run_url:
                     "https://api.github.com/repos/octo-org/octo-repo/check-runs/399444496".into(),
+                run_attempt: None,
             }],
         };
 
@@ -208,6 +254,83 @@ mod tests {
         Ok(())
     }
 
+    fn job_json(id: usize, run_attempt: u32, conclusion: &str) -> String {
+        format!(
+            r#"{{
+                "id": {id},
+                "run_id": 30433642,
+                "run_url": "https://api.github.com/repos/octo-org/octo-repo/actions/runs/30433642",
+                "node_id": "MDEyOldvcmtmbG93IEpvYjM5OTQ0NDQ5Ng==",
+                "head_sha": "f83a356604ae3c5d03e1b46ef4d1ca77d64a90b0",
+                "url": "https://api.github.com/repos/octo-org/octo-repo/actions/jobs/{id}",
+                "html_url": "https://github.com/octo-org/octo-repo/runs/{id}",
+                "status": "completed",
+                "conclusion": "{conclusion}",
+                "started_at": "2020-01-20T17:42:40Z",
+                "completed_at": "2020-01-20T17:44:39Z",
+                "name": "test ({id})",
+                "steps": [],
+                "check_run_url": "https://api.github.com/repos/octo-org/octo-repo/check-runs/{id}",
+                "run_attempt": {run_attempt}
+            }}"#,
+            id = id,
+            run_attempt = run_attempt,
+            conclusion = conclusion
+        )
+    }
+
+    #[tokio::test]
+    async fn get_all_workflow_run_jobs_of_all_attempts() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let first_page = (1..=PER_PAGE)
+            .map(|id| job_json(id, 1, "failure"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let m1 = mock("GET", "/aslamplr/gh-cli/actions/runs/30433642/jobs")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("filter".into(), "all".into()),
+                Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{ "total_count": 101, "jobs": [{}] }}"#,
+                first_page
+            ))
+            .expect(1)
+            .create();
+        let m2 = mock("GET", "/aslamplr/gh-cli/actions/runs/30433642/jobs")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("filter".into(), "all".into()),
+                Matcher::UrlEncoded("page".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{ "total_count": 101, "jobs": [{}] }}"#,
+                job_json(101, 2, "success")
+            ))
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let jobs = repo_req.get_all_workflow_run_jobs(30433642).await?;
+
+        m1.assert();
+        m2.assert();
+        assert_eq!(jobs.len(), 101);
+        assert_eq!(jobs[0].run_attempt, Some(1));
+        assert_eq!(jobs[100].run_attempt, Some(2));
+        assert_eq!(jobs[100].conclusion, "success");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn get_a_workflow_run_job() -> Result<()> {
         let repo_addr = "aslamplr/gh-cli";
@@ -289,6 +412,7 @@ mod tests {
             ],
             check_run_url: "https://api.github.com/repos/octo-org/octo-repo/check-runs/399444496"
                 .into(),
+            run_attempt: None,
         };
 
         let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
@@ -25,9 +25,18 @@ pub trait WorkflowRuns {
     async fn get_a_workflow_run(&self, run_id: u32) -> Result<WorkflowRun>;
     async fn rerun_a_workflow(&self, run_id: u32) -> Result<()>;
     async fn cancel_a_workflow_run(&self, run_id: u32) -> Result<()>;
+    async fn delete_workflow_run(&self, run_id: u32) -> Result<()>;
     async fn get_run_logs_url(&self, run_id: u32) -> Result<String>;
     async fn delete_run_logs(&self, run_id: u32) -> Result<()>;
     async fn get_workflow_run_usage(&self, run_id: u32) -> Result<WorkflowRunUsage>;
+    async fn get_pending_deployments(&self, run_id: u32) -> Result<Vec<PendingDeployment>>;
+    async fn review_pending_deployments(
+        &self,
+        run_id: u32,
+        environment_ids: &[u32],
+        state: DeploymentReviewState,
+        comment: &str,
+    ) -> Result<()>;
 }
 
 #[async_trait]
@@ -67,6 +76,10 @@ impl WorkflowRuns for RepoRequest<'_> {
         cancel_a_workflow_run(&self, run_id).await
     }
 
+    async fn delete_workflow_run(&self, run_id: u32) -> Result<()> {
+        delete_workflow_run(&self, run_id).await
+    }
+
     async fn get_run_logs_url(&self, run_id: u32) -> Result<String> {
         get_run_logs_url(&self, run_id).await
     }
@@ -78,13 +91,37 @@ impl WorkflowRuns for RepoRequest<'_> {
     async fn get_workflow_run_usage(&self, run_id: u32) -> Result<WorkflowRunUsage> {
         get_workflow_run_usage(&self, run_id).await
     }
+
+    async fn get_pending_deployments(&self, run_id: u32) -> Result<Vec<PendingDeployment>> {
+        get_pending_deployments(&self, run_id).await
+    }
+
+    async fn review_pending_deployments(
+        &self,
+        run_id: u32,
+        environment_ids: &[u32],
+        state: DeploymentReviewState,
+        comment: &str,
+    ) -> Result<()> {
+        let review = PendingDeploymentReview {
+            environment_ids,
+            state,
+            comment,
+        };
+        review_pending_deployments(&self, run_id, &review).await
+    }
 }
 
+#[derive(Debug, Default, Clone)]
 pub struct WorkflowRunQueryParams<'a> {
     pub actor: Option<&'a str>,
     pub branch: Option<&'a str>,
     pub event: Option<&'a str>,
     pub status: Option<&'a str>,
+    /// Date range of the run creation, eg. `<2020-01-01` or `2020-01-01..2020-02-01`.
+    pub created: Option<&'a str>,
+    pub per_page: Option<u32>,
+    pub page: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -242,20 +279,91 @@ pub struct WorkflowRunUsage {
     pub run_duration_ms: u32,
 }
 
-platform_usage!(UBUNTU, MACOS, WINDOWS,);
+platform_usage!(
+    #[serde(default)]
+    UBUNTU,
+    #[serde(default)]
+    MACOS,
+    #[serde(default)]
+    WINDOWS,
+);
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 pub struct WorkflowRunUsageTiming {
     pub total_ms: u32,
     pub jobs: u32,
 }
 
+/// Deployment of a run waiting on the protection rules of an environment.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PendingDeployment {
+    pub environment: PendingDeploymentEnvironment,
+    pub wait_timer: u32,
+    #[cfg(feature = "chrono")]
+    pub wait_timer_started_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[cfg(not(feature = "chrono"))]
+    pub wait_timer_started_at: Option<String>,
+    pub current_user_can_approve: bool,
+    pub reviewers: Vec<DeploymentReviewer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PendingDeploymentEnvironment {
+    pub id: u32,
+    pub node_id: String,
+    pub name: String,
+    pub url: String,
+    pub html_url: String,
+}
+
+/// Required reviewer of an environment, either a `User` or a `Team`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DeploymentReviewer {
+    #[serde(rename = "type")]
+    pub reviewer_type: String,
+    pub reviewer: DeploymentReviewerAccount,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DeploymentReviewerAccount {
+    pub id: u32,
+    /// Set for users.
+    pub login: Option<String>,
+    /// Set for teams.
+    pub slug: Option<String>,
+}
+
+impl DeploymentReviewerAccount {
+    pub fn display_name(&self) -> &str {
+        self.login
+            .as_deref()
+            .or(self.slug.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentReviewState {
+    Approved,
+    Rejected,
+}
+
+#[derive(Serialize, Debug)]
+struct PendingDeploymentReview<'a> {
+    environment_ids: &'a [u32],
+    state: DeploymentReviewState,
+    comment: &'a str,
+}
+
 async fn get_workflow_runs(
     params: &RepoRequest<'_>,
     workflow_id: Option<u32>,
     filter: Option<&WorkflowRunQueryParams<'_>>,
 ) -> Result<WorkflowRunList> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = if let Some(workflow_id) = workflow_id {
         with_base_url!("{}/actions/workflows/{}/runs", repo, workflow_id)
     } else {
@@ -276,6 +384,15 @@ async fn get_workflow_runs(
             if let Some(event) = filter.event {
                 serializer.append_pair("event", event);
             }
+            if let Some(created) = filter.created {
+                serializer.append_pair("created", created);
+            }
+            if let Some(per_page) = filter.per_page {
+                serializer.append_pair("per_page", &per_page.to_string());
+            }
+            if let Some(page) = filter.page {
+                serializer.append_pair("page", &page.to_string());
+            }
             serializer.finish()
         };
         format!("{}?{}", url, query)
@@ -288,7 +405,9 @@ async fn get_workflow_runs(
 }
 
 async fn get_a_workflow_run(params: &RepoRequest<'_>, run_id: u32) -> Result<WorkflowRun> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/runs/{}", repo, run_id);
     let resp = http_client.get(&url).await?;
     let resp = resp.deserialize().await?;
@@ -296,21 +415,36 @@ async fn get_a_workflow_run(params: &RepoRequest<'_>, run_id: u32) -> Result<Wor
 }
 
 async fn rerun_a_workflow(params: &RepoRequest<'_>, run_id: u32) -> Result<()> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/runs/{}/rerun", repo, run_id);
     http_client.post(&url, HttpBody::empty()).await?;
     Ok(())
 }
 
 async fn cancel_a_workflow_run(params: &RepoRequest<'_>, run_id: u32) -> Result<()> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/runs/{}/cancel", repo, run_id);
     http_client.post(&url, HttpBody::empty()).await?;
     Ok(())
 }
 
+async fn delete_workflow_run(params: &RepoRequest<'_>, run_id: u32) -> Result<()> {
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
+    let url = with_base_url!("{}/actions/runs/{}", repo, run_id);
+    http_client.delete(&url).await?;
+    Ok(())
+}
+
 async fn get_run_logs_url(params: &RepoRequest<'_>, run_id: u32) -> Result<String> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/runs/{}/logs", repo, run_id);
     let resp = http_client.get(&url).await?;
     let resp = resp.get_header("Location");
@@ -318,20 +452,52 @@ async fn get_run_logs_url(params: &RepoRequest<'_>, run_id: u32) -> Result<Strin
 }
 
 async fn delete_run_logs(params: &RepoRequest<'_>, run_id: u32) -> Result<()> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/runs/{}/logs", repo, run_id);
     http_client.delete(&url).await?;
     Ok(())
 }
 
 async fn get_workflow_run_usage(params: &RepoRequest<'_>, run_id: u32) -> Result<WorkflowRunUsage> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/runs/{}/timing", repo, run_id);
     let resp = http_client.get(&url).await?;
     let resp = resp.deserialize().await?;
     Ok(resp)
 }
 
+async fn get_pending_deployments(
+    params: &RepoRequest<'_>,
+    run_id: u32,
+) -> Result<Vec<PendingDeployment>> {
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
+    let url = with_base_url!("{}/actions/runs/{}/pending_deployments", repo, run_id);
+    let resp = http_client.get(&url).await?;
+    let resp = resp.deserialize().await?;
+    Ok(resp)
+}
+
+async fn review_pending_deployments(
+    params: &RepoRequest<'_>,
+    run_id: u32,
+    review: &PendingDeploymentReview<'_>,
+) -> Result<()> {
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
+    let url = with_base_url!("{}/actions/runs/{}/pending_deployments", repo, run_id);
+    http_client
+        .post(&url, HttpBody::try_from_serialize(review)?)
+        .await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -791,6 +957,7 @@ mod tests {
                     branch: Some("master"),
                     event: Some("push"),
                     status: Some("success"),
+                    ..Default::default()
                 },
             )
             .await?;
@@ -816,6 +983,7 @@ mod tests {
                 branch: Some("master"),
                 event: Some("push"),
                 status: Some("success"),
+                ..Default::default()
             })
             .await?;
 
@@ -1067,6 +1235,28 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn delete_workflow_run() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock("DELETE", "/aslamplr/gh-cli/actions/runs/30433642")
+            .match_header(
+                "Authorization",
+                Matcher::Exact(format!("Bearer {}", auth_token)),
+            )
+            .with_status(204)
+            .expect(1)
+            .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let run = repo_req.delete_workflow_run(30433642).await;
+
+        m.assert();
+        assert!(&run.is_ok());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn get_run_logs_url() -> Result<()> {
         let repo_addr = "aslamplr/gh-cli";
@@ -1171,4 +1361,125 @@ mod tests {
         assert_eq!(usage, expected_usage);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn get_pending_deployments() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = create_basic_mock_http(
+            "/aslamplr/gh-cli/actions/runs/30433642/pending_deployments",
+            auth_token,
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[
+              {
+                "environment": {
+                  "id": 161088068,
+                  "node_id": "MDExOkVudmlyb25tZW50MTYxMDg4MDY4",
+                  "name": "production",
+                  "url": "https://api.github.com/repos/aslamplr/gh-cli/environments/production",
+                  "html_url": "https://github.com/aslamplr/gh-cli/deployments/activity_log?environments_filter=production"
+                },
+                "wait_timer": 30,
+                "wait_timer_started_at": "2020-11-23T22:00:40Z",
+                "current_user_can_approve": true,
+                "reviewers": [
+                  {
+                    "type": "User",
+                    "reviewer": { "login": "octocat", "id": 1 }
+                  },
+                  {
+                    "type": "Team",
+                    "reviewer": { "id": 1, "slug": "justice-league", "name": "Justice League" }
+                  }
+                ]
+              }
+            ]"#,
+        )
+        .expect(1)
+        .create();
+
+        let expected_deployments = vec![PendingDeployment {
+            environment: PendingDeploymentEnvironment {
+                id: 161088068,
+                node_id: "MDExOkVudmlyb25tZW50MTYxMDg4MDY4".into(),
+                name: "production".into(),
+                url: "https://api.github.com/repos/aslamplr/gh-cli/environments/production".into(),
+                html_url: "https://github.com/aslamplr/gh-cli/deployments/activity_log?environments_filter=production".into(),
+            },
+            wait_timer: 30,
+            wait_timer_started_at: Some("2020-11-23T22:00:40Z".parse()?),
+            current_user_can_approve: true,
+            reviewers: vec![
+                DeploymentReviewer {
+                    reviewer_type: "User".into(),
+                    reviewer: DeploymentReviewerAccount {
+                        id: 1,
+                        login: Some("octocat".into()),
+                        slug: None,
+                    },
+                },
+                DeploymentReviewer {
+                    reviewer_type: "Team".into(),
+                    reviewer: DeploymentReviewerAccount {
+                        id: 1,
+                        login: None,
+                        slug: Some("justice-league".into()),
+                    },
+                },
+            ],
+        }];
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        let deployments = repo_req.get_pending_deployments(30433642).await?;
+
+        m.assert();
+        assert_eq!(deployments, expected_deployments);
+        assert_eq!(
+            deployments[0].reviewers[1].reviewer.display_name(),
+            "justice-league"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn review_pending_deployments() -> Result<()> {
+        let repo_addr = "aslamplr/gh-cli";
+        let auth_token = "auth_secret_token";
+
+        let m = mock(
+            "POST",
+            "/aslamplr/gh-cli/actions/runs/30433642/pending_deployments",
+        )
+        .match_header(
+            "Authorization",
+            Matcher::Exact(format!("Bearer {}", auth_token)),
+        )
+        .match_body(Matcher::Json(serde_json::json!({
+            "environment_ids": [161088068],
+            "state": "rejected",
+            "comment": "Not before the freeze is over"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .expect(1)
+        .create();
+
+        let repo_req = RepoRequest::try_from(repo_addr, auth_token)?;
+        repo_req
+            .review_pending_deployments(
+                30433642,
+                &[161088068],
+                DeploymentReviewState::Rejected,
+                "Not before the freeze is over",
+            )
+            .await?;
+
+        m.assert();
+        Ok(())
+    }
 }
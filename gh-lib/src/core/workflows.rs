@@ -3,6 +3,7 @@ use super::repos::RepoRequest;
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use url::form_urlencoded::byte_serialize;
 
 #[cfg(not(test))]
 const BASE_URL: &str = "https://api.github.com/repos";
@@ -55,6 +56,33 @@ pub struct Workflow {
     pub badge_url: String,
 }
 
+impl Workflow {
+    /// Status badge of the runs on `branch`, `None` is the default branch.
+    pub fn branch_badge_url(&self, branch: Option<&str>) -> String {
+        match branch {
+            Some(branch) => format!("{}?branch={}", self.badge_url, encode(branch)),
+            None => self.badge_url.to_owned(),
+        }
+    }
+
+    /// Actions page listing the runs of the workflow, the link target of its badge.
+    pub fn runs_page_url(&self, branch: Option<&str>) -> String {
+        let repo_url = self
+            .badge_url
+            .find("/workflows/")
+            .map_or(self.badge_url.as_str(), |i| &self.badge_url[..i]);
+        let mut query = format!("workflow:\"{}\"", self.name);
+        if let Some(branch) = branch {
+            query.push_str(&format!(" branch:{}", branch));
+        }
+        format!("{}/actions?query={}", repo_url, encode(&query))
+    }
+}
+
+fn encode(value: &str) -> String {
+    byte_serialize(value.as_bytes()).collect()
+}
+
 macro_rules! platform_usage {
     (
         $(
@@ -86,7 +114,9 @@ pub struct WorkflowUsageTiming {
 }
 
 async fn get_all_workflows(params: &RepoRequest<'_>) -> Result<WorkflowList> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/workflows", repo);
     let resp = http_client.get(&url).await?;
     let resp = resp.deserialize().await?;
@@ -94,7 +124,9 @@ async fn get_all_workflows(params: &RepoRequest<'_>) -> Result<WorkflowList> {
 }
 
 async fn get_a_workflow(params: &RepoRequest<'_>, workflow_id: u32) -> Result<Workflow> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/workflows/{}", repo, workflow_id);
     let resp = http_client.get(&url).await?;
     let resp = resp.deserialize().await?;
@@ -102,7 +134,9 @@ async fn get_a_workflow(params: &RepoRequest<'_>, workflow_id: u32) -> Result<Wo
 }
 
 async fn get_workflow_usage(params: &RepoRequest<'_>, workflow_id: u32) -> Result<WorkflowUsage> {
-    let RepoRequest { repo, http_client } = params;
+    let RepoRequest {
+        repo, http_client, ..
+    } = params;
     let url = with_base_url!("{}/actions/workflows/{}/timing", repo, workflow_id);
     let resp = http_client.get(&url).await?;
     // eprintln!("body: {:?}", resp.body().await);
@@ -296,4 +330,33 @@ mod tests {
         assert_eq!(usage, expected_usage);
         Ok(())
     }
+
+    #[test]
+    fn workflow_badge() -> Result<()> {
+        let workflow: Workflow = serde_json::from_str(
+            r#"{
+              "id": 161335,
+              "node_id": "MDg6V29ya2Zsb3cxNjEzMzU=",
+              "name": "CI",
+              "path": ".github/workflows/blank.yml",
+              "state": "active",
+              "created_at": "2020-01-08T23:48:37.000-08:00",
+              "updated_at": "2020-01-08T23:50:21.000-08:00",
+              "url": "https://api.github.com/repos/octo-org/octo-repo/actions/workflows/161335",
+              "html_url": "https://github.com/octo-org/octo-repo/blob/master/.github/workflows/161335",
+              "badge_url": "https://github.com/octo-org/octo-repo/workflows/CI/badge.svg"
+            }"#,
+        )?;
+
+        assert_eq!(
+            workflow.branch_badge_url(Some("release/v1")),
+            "https://github.com/octo-org/octo-repo/workflows/CI/badge.svg?branch=release%2Fv1"
+        );
+        assert_eq!(workflow.branch_badge_url(None), workflow.badge_url);
+        assert_eq!(
+            workflow.runs_page_url(Some("main")),
+            "https://github.com/octo-org/octo-repo/actions?query=workflow%3A%22CI%22+branch%3Amain"
+        );
+        Ok(())
+    }
 }
@@ -1,6 +1,26 @@
 #[cfg(not(test))]
 pub const BASE_URL: &str = "https://api.github.com";
 
+#[cfg(any(feature = "graphql-api", feature = "http-api"))]
+static API_URL: once_cell::sync::Lazy<std::sync::RwLock<Option<String>>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+/// Sends the following requests to `api_url` instead of `BASE_URL`, eg. the
+/// `https://github.example.com/api/v3` root of a GitHub Enterprise Server.
+#[cfg(any(feature = "graphql-api", feature = "http-api"))]
+pub fn set_api_url(api_url: &str) {
+    *API_URL.write().unwrap() = Some(api_url.trim_end_matches('/').to_owned());
+}
+
+/// `base_url` of a module, rooted at the API URL set with `set_api_url`.
+#[cfg(all(not(test), any(feature = "graphql-api", feature = "http-api")))]
+pub(crate) fn api_url(base_url: &str) -> std::borrow::Cow<'_, str> {
+    match API_URL.read().unwrap().as_deref() {
+        Some(api_url) => base_url.replacen(BASE_URL, api_url, 1).into(),
+        None => base_url.into(),
+    }
+}
+
 #[cfg(any(feature = "graphql-api", feature = "http-api"))]
 macro_rules! with_base_url {
   ($($arg:tt)*) => ({
@@ -10,12 +30,22 @@ macro_rules! with_base_url {
       #[cfg(test)]
       let url = format!("{}/{}", &mockito::server_url(), format!($($arg)*));
       #[cfg(not(test))]
-      let url = format!("{}/{}", BASE_URL, format!($($arg)*));
+      let url = format!("{}/{}", crate::api_url(BASE_URL), format!($($arg)*));
 
       url
   })
 }
 
+#[cfg(feature = "analytics")]
+pub mod analytics;
 pub mod core;
 mod graphql;
+#[cfg(feature = "timeline")]
+pub mod timeline;
+#[cfg(any(feature = "graphql-api", feature = "http-api"))]
+pub mod token;
 mod utils;
+#[cfg(feature = "http-api")]
+pub use utils::http::{HttpError, StatusCode};
+#[cfg(feature = "workflow-file")]
+pub mod workflow_file;
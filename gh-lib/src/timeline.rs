@@ -0,0 +1,308 @@
+#![cfg(feature = "timeline")]
+//! Gantt-style timeline of the jobs and steps of a workflow run.
+//!
+//! Bars are aligned on the wall clock, starting at the earliest job start. The API does not
+//! expose the `needs` of a job, so the critical path is inferred from the timing: walking back
+//! from the job that finished last, the predecessor of a job is the one that completed last
+//! before it started.
+use crate::analytics::{secs_between, JobRecord};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarKind {
+    Job,
+    Step,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bar {
+    pub name: String,
+    pub kind: BarKind,
+    pub conclusion: String,
+    /// Seconds since the start of the timeline.
+    pub start_secs: u64,
+    pub duration_secs: u64,
+    pub critical: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    pub started_at: Option<DateTime<Utc>>,
+    pub duration_secs: u64,
+    /// Jobs in the order they started, each followed by its steps.
+    pub bars: Vec<Bar>,
+}
+
+impl Timeline {
+    pub fn from(jobs: &[JobRecord]) -> Self {
+        let started_at = jobs.iter().map(|j| j.started_at).min();
+        let completed_at = jobs.iter().map(|j| j.completed_at).max();
+        let (started_at, duration_secs) = match (started_at, completed_at) {
+            (Some(started_at), Some(completed_at)) => {
+                (Some(started_at), secs_between(started_at, completed_at))
+            }
+            _ => (None, 0),
+        };
+        let critical = critical_path(jobs);
+
+        let mut order = (0..jobs.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| (jobs[i].started_at, jobs[i].completed_at));
+
+        let mut bars = Vec::new();
+        for i in order {
+            let job = &jobs[i];
+            let offset = |time| started_at.map_or(0, |started_at| secs_between(started_at, time));
+            bars.push(Bar {
+                name: job.name.to_owned(),
+                kind: BarKind::Job,
+                conclusion: job.conclusion.to_owned(),
+                start_secs: offset(job.started_at),
+                duration_secs: secs_between(job.started_at, job.completed_at),
+                critical: critical.contains(&i),
+            });
+            bars.extend(job.steps.iter().map(|step| Bar {
+                name: step.name.to_owned(),
+                kind: BarKind::Step,
+                conclusion: step.conclusion.to_owned(),
+                start_secs: offset(step.started_at),
+                duration_secs: secs_between(step.started_at, step.completed_at),
+                critical: false,
+            }));
+        }
+
+        Timeline {
+            started_at,
+            duration_secs,
+            bars,
+        }
+    }
+
+    /// Standalone SVG image of the timeline.
+    pub fn to_svg(&self, title: &str) -> String {
+        const LABEL_WIDTH: u64 = 260;
+        const CHART_WIDTH: u64 = 720;
+        const ROW_HEIGHT: u64 = 22;
+        const HEADER_HEIGHT: u64 = 30;
+
+        let width = LABEL_WIDTH + CHART_WIDTH + 20;
+        let height = HEADER_HEIGHT + ROW_HEIGHT * self.bars.len() as u64 + 10;
+        let scale = |secs: u64| secs * CHART_WIDTH / self.duration_secs.max(1);
+
+        let mut svg = format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12">
+<title>{title}</title>
+<rect width="{width}" height="{height}" fill="#ffffff"/>
+<text x="10" y="18" font-weight="bold">{title}</text>
+<text x="{end}" y="18" text-anchor="end">{duration}</text>
+"##,
+            width = width,
+            height = height,
+            title = xml_escape(title),
+            end = LABEL_WIDTH + CHART_WIDTH,
+            duration = format_duration(self.duration_secs),
+        );
+        for (row, bar) in self.bars.iter().enumerate() {
+            let y = HEADER_HEIGHT + ROW_HEIGHT * row as u64;
+            let (indent, bar_height, weight) = match bar.kind {
+                BarKind::Job => (10, ROW_HEIGHT - 6, "bold"),
+                BarKind::Step => (26, ROW_HEIGHT - 12, "normal"),
+            };
+            let stroke = if bar.critical {
+                r##" stroke="#8250df" stroke-width="2""##
+            } else {
+                ""
+            };
+            svg.push_str(&format!(
+                r#"<text x="{x}" y="{text_y}" font-weight="{weight}">{name}</text>
+<rect x="{bar_x}" y="{bar_y}" width="{bar_width}" height="{bar_height}" rx="2" fill="{fill}"{stroke}><title>{name}: {conclusion} in {duration}</title></rect>
+"#,
+                x = indent,
+                text_y = y + ROW_HEIGHT / 2 + 4,
+                weight = weight,
+                name = xml_escape(&bar.name),
+                bar_x = LABEL_WIDTH + scale(bar.start_secs),
+                bar_y = y + (ROW_HEIGHT - bar_height) / 2,
+                bar_width = scale(bar.duration_secs).max(1),
+                bar_height = bar_height,
+                fill = conclusion_color(&bar.conclusion),
+                stroke = stroke,
+                conclusion = xml_escape(&bar.conclusion),
+                duration = format_duration(bar.duration_secs),
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// HTML page embedding the SVG image and a legend of the colors.
+    pub fn to_html(&self, title: &str) -> String {
+        let legend = ["success", "failure", "cancelled", "skipped"]
+            .iter()
+            .map(|conclusion| {
+                format!(
+                    r#"<span><i style="background:{}"></i>{}</span>"#,
+                    conclusion_color(conclusion),
+                    conclusion
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; }}
+.legend span {{ margin-right: 1em; }}
+.legend i {{ display: inline-block; width: 12px; height: 12px; margin-right: 4px; border-radius: 2px; }}
+</style>
+</head>
+<body>
+<div class="legend">
+{legend}
+<span><i style="border: 2px solid #8250df"></i>critical path</span>
+</div>
+{svg}</body>
+</html>
+"##,
+            title = xml_escape(title),
+            legend = legend,
+            svg = self.to_svg(title),
+        )
+    }
+}
+
+/// Indices of the jobs on the critical path, in the order they ran.
+pub fn critical_path(jobs: &[JobRecord]) -> Vec<usize> {
+    let last = jobs
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, job)| job.completed_at)
+        .map(|(i, _)| i);
+    let mut path = Vec::new();
+    let mut current = last;
+    while let Some(i) = current {
+        path.push(i);
+        current = jobs
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| job.completed_at <= jobs[i].started_at)
+            .filter(|(j, _)| !path.contains(j))
+            .max_by_key(|(_, job)| job.completed_at)
+            .map(|(j, _)| j);
+    }
+    path.reverse();
+    path
+}
+
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+fn conclusion_color(conclusion: &str) -> &'static str {
+    match conclusion {
+        "success" => "#2da44e",
+        "failure" | "timed_out" => "#cf222e",
+        "cancelled" | "skipped" | "neutral" => "#8c959f",
+        _ => "#bf8700",
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::StepRecord;
+
+    fn time(minute: u32) -> DateTime<Utc> {
+        format!("2020-01-22T19:{:02}:00Z", minute).parse().unwrap()
+    }
+
+    fn job(name: &str, conclusion: &str, start: u32, end: u32) -> JobRecord {
+        JobRecord {
+            name: name.into(),
+            attempt: 1,
+            conclusion: conclusion.into(),
+            started_at: time(start),
+            completed_at: time(end),
+            steps: vec![StepRecord {
+                name: format!("Run {}", name),
+                conclusion: conclusion.into(),
+                started_at: time(start),
+                completed_at: time(end),
+            }],
+        }
+    }
+
+    #[test]
+    fn critical_path_follows_latest_predecessor() {
+        let jobs = vec![
+            job("test", "success", 3, 9),
+            job("lint", "success", 1, 2),
+            job("build", "success", 1, 3),
+            job("deploy", "failure", 9, 12),
+        ];
+        assert_eq!(critical_path(&jobs), vec![2, 0, 3]);
+        assert_eq!(critical_path(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn timeline_bars() {
+        let jobs = vec![
+            job("test", "success", 3, 9),
+            job("build & lint", "success", 1, 3),
+        ];
+        let timeline = Timeline::from(&jobs);
+
+        assert_eq!(timeline.started_at, Some(time(1)));
+        assert_eq!(timeline.duration_secs, 480);
+        assert_eq!(
+            timeline.bars[..2],
+            [
+                Bar {
+                    name: "build & lint".into(),
+                    kind: BarKind::Job,
+                    conclusion: "success".into(),
+                    start_secs: 0,
+                    duration_secs: 120,
+                    critical: true,
+                },
+                Bar {
+                    name: "Run build & lint".into(),
+                    kind: BarKind::Step,
+                    conclusion: "success".into(),
+                    start_secs: 0,
+                    duration_secs: 120,
+                    critical: false,
+                },
+            ]
+        );
+        assert_eq!(timeline.bars[2].start_secs, 120);
+
+        let svg = timeline.to_svg("CI #42");
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect ").count(), 5);
+        assert!(svg.contains(">build &amp; lint</text>"));
+        assert!(timeline.to_html("CI #42").contains(&svg));
+    }
+
+    #[test]
+    fn duration_format() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(125), "2m 5s");
+        assert_eq!(format_duration(7325), "2h 2m");
+    }
+}
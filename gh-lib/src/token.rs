@@ -0,0 +1,105 @@
+//! Tokens authorizing the requests, either fixed or handed out by a `TokenProvider` before every
+//! request so that short-lived ones, such as GitHub App installation tokens, are refreshed in time.
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait TokenProvider: Send + Sync {
+    /// Token for the next request, refreshed by the provider when about to expire.
+    async fn token(&self) -> Result<String>;
+}
+
+#[async_trait]
+impl TokenProvider for String {
+    async fn token(&self) -> Result<String> {
+        Ok(self.clone())
+    }
+}
+
+/// Token of the requests, cheap to clone and shared between the clients of a provider.
+#[derive(Clone)]
+pub struct AuthToken {
+    provider: Arc<dyn TokenProvider>,
+}
+
+impl AuthToken {
+    pub fn from_provider<P>(provider: P) -> Self
+    where
+        P: TokenProvider + 'static,
+    {
+        AuthToken {
+            provider: Arc::new(provider),
+        }
+    }
+
+    pub async fn get(&self) -> Result<String> {
+        self.provider.token().await
+    }
+}
+
+impl From<&str> for AuthToken {
+    fn from(auth_token: &str) -> Self {
+        AuthToken::from_provider(auth_token.to_owned())
+    }
+}
+
+impl From<String> for AuthToken {
+    fn from(auth_token: String) -> Self {
+        AuthToken::from_provider(auth_token)
+    }
+}
+
+impl From<Arc<dyn TokenProvider>> for AuthToken {
+    fn from(provider: Arc<dyn TokenProvider>) -> Self {
+        AuthToken { provider }
+    }
+}
+
+impl std::fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("AuthToken(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::http::HttpClient;
+    use mockito::mock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Hands out a new token every time.
+    struct RotatingTokens(AtomicUsize);
+
+    #[async_trait]
+    impl TokenProvider for RotatingTokens {
+        async fn token(&self) -> Result<String> {
+            Ok(format!("token_{}", self.0.fetch_add(1, Ordering::SeqCst)))
+        }
+    }
+
+    #[tokio::test]
+    async fn token_of_each_request() -> Result<()> {
+        let first = mock("GET", "/rate_limit")
+            .match_header("Authorization", "Bearer token_0")
+            .with_status(200)
+            .expect(1)
+            .create();
+        let second = mock("GET", "/rate_limit")
+            .match_header("Authorization", "Bearer token_1")
+            .with_status(200)
+            .expect(1)
+            .create();
+
+        let auth_token = AuthToken::from_provider(RotatingTokens(AtomicUsize::new(0)));
+        let http_client = HttpClient::new(auth_token)?;
+        let url = with_base_url!("rate_limit");
+        http_client.get(&url).await?;
+        http_client.get(&url).await?;
+
+        first.assert();
+        second.assert();
+        Ok(())
+    }
+}
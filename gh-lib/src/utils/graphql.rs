@@ -16,5 +16,8 @@ where
 {
     let body = HttpBody::try_from_serialize(&query)?;
     let url = with_base_url!("graphql");
+    // The GraphQL API of GitHub Enterprise Server is at `/api/graphql` rather than `/api/v3/graphql`.
+    #[cfg(not(test))]
+    let url = url.replace("/api/v3/graphql", "/api/graphql");
     http_client.post(&url, body).await?.deserialize().await
 }
@@ -1,27 +1,34 @@
 #![cfg(any(feature = "graphql-api", feature = "http-api"))]
-use anyhow::{anyhow, Result};
+use crate::token::AuthToken;
+use anyhow::Result;
 use reqwest::{header, Body, Client, Request, RequestBuilder, Response};
 pub use reqwest::{Method as HttpMethod, StatusCode};
 
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
-fn create_https_client(auth_token: &str) -> Result<Client> {
-    let headers = {
-        let mut headers = header::HeaderMap::new();
-        headers.insert(
-            header::AUTHORIZATION,
-            header::HeaderValue::from_str(&format!("Bearer {}", auth_token))?,
-        );
-        headers
-    };
+fn create_https_client() -> Result<Client> {
     reqwest::ClientBuilder::new()
         .user_agent(APP_USER_AGENT)
-        .default_headers(headers)
         .redirect(reqwest::redirect::Policy::none())
         .build()
         .map_err(|e| anyhow::anyhow!("An error occured while creating the client, {:?}", e))
 }
 
+/// Non-success response from the API, callers may downcast the `anyhow::Error` to inspect it.
+#[derive(Debug)]
+pub struct HttpError {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for HttpError {}
+
 pub struct HttpBody {
     inner: Option<Body>,
 }
@@ -88,34 +95,53 @@ impl HttpResponse {
 
 pub struct HttpRequest {
     client: Client,
+    auth_token: AuthToken,
     request: Request,
 }
 
 impl HttpRequest {
-    pub fn from(client: Client, request: Request) -> Self {
-        HttpRequest { client, request }
+    pub fn from(client: Client, auth_token: AuthToken, request: Request) -> Self {
+        HttpRequest {
+            client,
+            auth_token,
+            request,
+        }
     }
 
     pub async fn call(self) -> Result<HttpResponse> {
-        let res = self.client.execute(self.request).await?;
+        let HttpRequest {
+            client,
+            auth_token,
+            mut request,
+        } = self;
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            header::HeaderValue::from_str(&format!("Bearer {}", auth_token.get().await?))?,
+        );
+        let res = client.execute(request).await?;
         let status = res.status();
         if status.is_redirection() || status.is_success() {
             Ok(HttpResponse::from(res))
         } else {
             let body = res.text().await?;
-            Err(anyhow!("[{}] {}", status, body))
+            Err(HttpError { status, body }.into())
         }
     }
 }
 
 pub struct HttpRequestBuilder {
     client: Client,
+    auth_token: AuthToken,
     builder: RequestBuilder,
 }
 
 impl HttpRequestBuilder {
-    pub fn from(client: Client, builder: RequestBuilder) -> Self {
-        Self { client, builder }
+    pub fn from(client: Client, auth_token: AuthToken, builder: RequestBuilder) -> Self {
+        Self {
+            client,
+            auth_token,
+            builder,
+        }
     }
 
     pub fn header(self, key: &str, value: &str) -> Self {
@@ -129,7 +155,7 @@ impl HttpRequestBuilder {
             None => self.builder,
         };
         let request = req_builder.build()?;
-        Ok(HttpRequest::from(self.client, request))
+        Ok(HttpRequest::from(self.client, self.auth_token, request))
     }
 
     pub async fn call(self) -> Result<HttpResponse> {
@@ -140,19 +166,21 @@ impl HttpRequestBuilder {
 #[derive(Debug)]
 pub struct HttpClient {
     inner: Client,
+    auth_token: AuthToken,
 }
 
 impl HttpClient {
-    pub fn new(auth_token: &str) -> Result<Self> {
+    pub fn new(auth_token: impl Into<AuthToken>) -> Result<Self> {
         Ok(Self {
-            inner: create_https_client(auth_token)?,
+            inner: create_https_client()?,
+            auth_token: auth_token.into(),
         })
     }
 
     pub fn request(&self, url: &str, method: HttpMethod) -> HttpRequestBuilder {
         let client = self.inner.clone();
         let builder = client.request(method, url);
-        HttpRequestBuilder::from(client, builder)
+        HttpRequestBuilder::from(client, self.auth_token.clone(), builder)
     }
 
     pub async fn get(&self, url: &str) -> Result<HttpResponse> {
@@ -170,7 +198,7 @@ impl HttpClient {
         self.request(&url, HttpMethod::PUT).body(body)?.call().await
     }
 
-    pub async fn _patch(&self, url: &str, body: HttpBody) -> Result<HttpResponse> {
+    pub async fn patch(&self, url: &str, body: HttpBody) -> Result<HttpResponse> {
         self.request(&url, HttpMethod::PATCH)
             .body(body)?
             .call()
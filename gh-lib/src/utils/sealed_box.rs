@@ -1,20 +1,65 @@
 #![cfg(feature = "secrets-save")]
 use anyhow::{anyhow, Result};
 use sodiumoxide::crypto::{box_::curve25519xsalsa20poly1305::PublicKey, sealedbox};
+use std::sync::Mutex;
 
-pub fn seal(message: &str, public_key_base64: &str) -> Result<String> {
+fn decode_public_key(public_key_base64: &str) -> Result<PublicKey> {
     let public_key = base64::decode(public_key_base64)?;
-    let public_key = PublicKey::from_slice(&public_key)
-        .ok_or_else(|| anyhow!("unable to create public key object"))?;
+    PublicKey::from_slice(&public_key).ok_or_else(|| anyhow!("unable to create public key object"))
+}
+
+/// Decoded GitHub Actions public key along with its `key_id`, ready to encrypt secret values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecretEncryptor {
+    key_id: String,
+    public_key: PublicKey,
+}
+
+impl SecretEncryptor {
+    pub fn try_from(key_id: &str, public_key_base64: &str) -> Result<Self> {
+        Ok(SecretEncryptor {
+            key_id: key_id.to_owned(),
+            public_key: decode_public_key(public_key_base64)?,
+        })
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
 
-    let sealed_box = sealedbox::seal(message.as_bytes(), &public_key);
-    let sealed_box_base64 = base64::encode(&sealed_box);
-    Ok(sealed_box_base64)
+    /// Seals `message` with the public key, returns the base64 encoded sealed box.
+    pub fn encrypt(&self, message: &[u8]) -> String {
+        base64::encode(sealedbox::seal(message, &self.public_key))
+    }
+}
+
+/// Holds the last known `SecretEncryptor`, shared by every secret write of a request.
+#[derive(Debug, Default)]
+pub struct SecretEncryptorCache {
+    inner: Mutex<Option<SecretEncryptor>>,
+}
+
+impl SecretEncryptorCache {
+    pub fn get(&self) -> Option<SecretEncryptor> {
+        self.inner.lock().ok().and_then(|cached| cached.clone())
+    }
+
+    pub fn set(&self, encryptor: SecretEncryptor) {
+        if let Ok(mut cached) = self.inner.lock() {
+            *cached = Some(encryptor);
+        }
+    }
+
+    pub fn invalidate(&self) {
+        if let Ok(mut cached) = self.inner.lock() {
+            *cached = None;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::seal;
+    use super::{SecretEncryptor, SecretEncryptorCache};
 
     use anyhow::{anyhow, Result};
     use base64;
@@ -29,7 +74,8 @@ mod tests {
         let (pk, sk) = gen_keypair();
         let PublicKey(pk_bytes) = pk;
         let public_key_base64 = base64::encode(pk_bytes);
-        let sealed_box_base64 = seal(&message, &public_key_base64)?;
+        let encryptor = SecretEncryptor::try_from("012345678912345678", &public_key_base64)?;
+        let sealed_box_base64 = encryptor.encrypt(message.as_bytes());
         let c = base64::decode(&sealed_box_base64)?;
         let opened_box_content = sealedbox::open(c.as_slice(), &pk, &sk)
             .or_else(|()| Err(anyhow!("Something went wrong with opening the sealed box!")))?;
@@ -37,4 +83,32 @@ mod tests {
         assert_eq!(&message, &opened_box_content);
         Ok(())
     }
+
+    #[test]
+    fn test_secret_encryptor_binary_message() -> Result<()> {
+        let message = [0u8, 159, 146, 150, 255, 10];
+        let (pk, sk) = gen_keypair();
+        let PublicKey(pk_bytes) = pk;
+        let encryptor = SecretEncryptor::try_from("012345678912345678", &base64::encode(pk_bytes))?;
+        let c = base64::decode(&encryptor.encrypt(&message))?;
+        let opened_box_content = sealedbox::open(c.as_slice(), &pk, &sk)
+            .map_err(|()| anyhow!("Something went wrong with opening the sealed box!"))?;
+        assert_eq!(encryptor.key_id(), "012345678912345678");
+        assert_eq!(&message[..], &opened_box_content[..]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_secret_encryptor_cache() -> Result<()> {
+        let (pk, _) = gen_keypair();
+        let PublicKey(pk_bytes) = pk;
+        let encryptor = SecretEncryptor::try_from("012345678912345678", &base64::encode(pk_bytes))?;
+        let cache = SecretEncryptorCache::default();
+        assert_eq!(cache.get(), None);
+        cache.set(encryptor.clone());
+        assert_eq!(cache.get(), Some(encryptor));
+        cache.invalidate();
+        assert_eq!(cache.get(), None);
+        Ok(())
+    }
 }
@@ -0,0 +1,1222 @@
+#![cfg(feature = "workflow-file")]
+//! Offline parsing and linting of workflow files in `.github/workflows`.
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Directory of the workflow files, relative to the repository root as in `Workflow::path`.
+pub const WORKFLOWS_DIR: &str = ".github/workflows";
+
+/// Contexts available in `${{ }}` expressions.
+const CONTEXTS: [&str; 12] = [
+    "github", "env", "vars", "job", "jobs", "steps", "runner", "secrets", "strategy", "matrix",
+    "needs", "inputs",
+];
+
+/// Owners of the actions maintained by GitHub, which are not required to be pinned.
+const FIRST_PARTY_OWNERS: [&str; 2] = ["actions", "github"];
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WorkflowFile {
+    pub name: Option<String>,
+    pub run_name: Option<String>,
+    pub on: Triggers,
+    #[serde(default)]
+    pub env: Mapping,
+    pub permissions: Option<Value>,
+    pub defaults: Option<Value>,
+    pub concurrency: Option<Value>,
+    pub jobs: BTreeMap<String, Job>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Triggers {
+    Event(String),
+    Events(Vec<String>),
+    Configured(BTreeMap<String, Option<TriggerConfig>>),
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum TriggerConfig {
+    Schedule(Vec<Schedule>),
+    Filter(Box<Trigger>),
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Schedule {
+    pub cron: String,
+}
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Trigger {
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub types: Vec<String>,
+    #[serde(default)]
+    pub branches: Vec<String>,
+    #[serde(default)]
+    pub branches_ignore: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub tags_ignore: Vec<String>,
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub paths_ignore: Vec<String>,
+    /// Workflows triggering a `workflow_run`.
+    #[serde(default)]
+    pub workflows: Vec<String>,
+    /// Inputs of a `workflow_dispatch` or `workflow_call`.
+    #[serde(default)]
+    pub inputs: BTreeMap<String, Input>,
+    pub outputs: Option<Value>,
+    pub secrets: Option<Value>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Input {
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    pub default: Option<Value>,
+    #[serde(rename = "type")]
+    pub input_type: Option<String>,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(rename = "deprecationMessage")]
+    pub deprecation_message: Option<String>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Job {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub needs: Vec<String>,
+    pub runs_on: Option<Value>,
+    #[serde(rename = "if")]
+    pub condition: Option<Value>,
+    pub strategy: Option<Strategy>,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+    /// Reusable workflow called by the job.
+    pub uses: Option<String>,
+    pub with: Option<Mapping>,
+    pub secrets: Option<Value>,
+    pub env: Option<Mapping>,
+    pub outputs: Option<Mapping>,
+    pub permissions: Option<Value>,
+    pub environment: Option<Value>,
+    pub concurrency: Option<Value>,
+    pub container: Option<Value>,
+    pub services: Option<Mapping>,
+    pub defaults: Option<Value>,
+    pub timeout_minutes: Option<Value>,
+    pub continue_on_error: Option<Value>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Strategy {
+    pub matrix: Option<MatrixDefinition>,
+    pub fail_fast: Option<Value>,
+    pub max_parallel: Option<Value>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum MatrixDefinition {
+    Matrix(Matrix),
+    /// Matrix computed at run time, eg. `${{ fromJson(needs.setup.outputs.matrix) }}`.
+    Expression(String),
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Matrix {
+    #[serde(default)]
+    pub include: Vec<Mapping>,
+    #[serde(default)]
+    pub exclude: Vec<Mapping>,
+    /// Dimensions in the order they are written, which is the order of the job name suffixes.
+    #[serde(flatten)]
+    pub dimensions: Mapping,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Step {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    #[serde(rename = "if")]
+    pub condition: Option<Value>,
+    pub uses: Option<String>,
+    pub run: Option<String>,
+    pub shell: Option<String>,
+    pub with: Option<Mapping>,
+    pub env: Option<Mapping>,
+    pub working_directory: Option<String>,
+    pub continue_on_error: Option<Value>,
+    pub timeout_minutes: Option<Value>,
+}
+
+impl WorkflowFile {
+    pub fn parse(source: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(source)?)
+    }
+
+    /// `uses` references of the jobs and steps along with their location.
+    pub fn uses(&self) -> Vec<(String, &str)> {
+        let mut uses = Vec::new();
+        for (job_id, job) in &self.jobs {
+            if let Some(job_uses) = &job.uses {
+                uses.push((format!("jobs.{}", job_id), job_uses.as_str()));
+            }
+            for (i, step) in job.steps.iter().enumerate() {
+                if let Some(step_uses) = &step.uses {
+                    uses.push((format!("jobs.{}.steps[{}]", job_id, i), step_uses.as_str()));
+                }
+            }
+        }
+        uses
+    }
+}
+
+impl Triggers {
+    pub fn events(&self) -> Vec<&str> {
+        match self {
+            Triggers::Event(event) => vec![event],
+            Triggers::Events(events) => events.iter().map(String::as_str).collect(),
+            Triggers::Configured(events) => events.keys().map(String::as_str).collect(),
+        }
+    }
+
+    /// Inputs of the `workflow_dispatch` and `workflow_call` triggers.
+    pub fn inputs(&self) -> BTreeMap<&str, &Input> {
+        let mut inputs = BTreeMap::new();
+        if let Triggers::Configured(events) = self {
+            for event in &["workflow_dispatch", "workflow_call"] {
+                if let Some(Some(TriggerConfig::Filter(trigger))) = events.get(*event) {
+                    inputs.extend(trigger.inputs.iter().map(|(k, v)| (k.as_str(), v)));
+                }
+            }
+        }
+        inputs
+    }
+}
+
+/// A job of the workflow run as GitHub names it, with its matrix combination.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedJob {
+    pub name: String,
+    pub matrix: Mapping,
+}
+
+impl Job {
+    /// Jobs created from the matrix of the job, a job without a matrix creates one job.
+    pub fn expand(&self, job_id: &str) -> Result<Vec<ExpectedJob>> {
+        let name = self.name.as_deref().unwrap_or(job_id);
+        let combinations = match &self.strategy {
+            Some(Strategy {
+                matrix: Some(MatrixDefinition::Matrix(matrix)),
+                ..
+            }) => matrix.expand()?,
+            Some(Strategy {
+                matrix: Some(MatrixDefinition::Expression(expression)),
+                ..
+            }) => {
+                return Err(anyhow!(
+                    "matrix of job `{}` is computed at run time: {}",
+                    job_id,
+                    expression
+                ))
+            }
+            _ => {
+                return Ok(vec![ExpectedJob {
+                    name: name.to_owned(),
+                    matrix: Mapping::new(),
+                }])
+            }
+        };
+        Ok(combinations
+            .into_iter()
+            .map(|matrix| ExpectedJob {
+                name: matrix_job_name(name, &matrix),
+                matrix,
+            })
+            .collect())
+    }
+
+    /// Maximum number of matrix jobs running at the same time, `None` when unlimited.
+    pub fn max_parallel(&self) -> Option<u64> {
+        match self.strategy.as_ref()?.max_parallel.as_ref()? {
+            Value::Number(max_parallel) => max_parallel.as_u64(),
+            _ => None,
+        }
+    }
+}
+
+impl Matrix {
+    /// Combinations of the matrix the way GitHub expands them.
+    ///
+    /// The cartesian product of the dimensions is filtered by `exclude`, an exclusion matching
+    /// the combinations having all of its values. Each `include` then extends the combinations
+    /// it does not overwrite an original value of, values added by a previous `include` can be
+    /// overwritten. An `include` which extends no combination is added as a combination of its
+    /// own.
+    pub fn expand(&self) -> Result<Vec<Mapping>> {
+        let mut combinations = if self.dimensions.is_empty() {
+            Vec::new()
+        } else {
+            vec![Mapping::new()]
+        };
+        for (key, values) in &self.dimensions {
+            let values = match values {
+                Value::Sequence(values) => values,
+                _ => {
+                    return Err(anyhow!(
+                        "matrix dimension `{}` is not a list",
+                        serde_yaml::to_string(key)?.trim_start_matches("---").trim()
+                    ))
+                }
+            };
+            combinations = combinations
+                .iter()
+                .flat_map(|combination| {
+                    values.iter().map(move |value| {
+                        let mut combination = combination.clone();
+                        combination.insert(key.clone(), value.clone());
+                        combination
+                    })
+                })
+                .collect();
+        }
+
+        combinations.retain(|combination| {
+            !self
+                .exclude
+                .iter()
+                .any(|exclude| is_partial_match(exclude, combination))
+        });
+
+        let original = combinations.len();
+        for include in &self.include {
+            let mut extended = false;
+            for combination in &mut combinations[..original] {
+                let overwrites_original = include.iter().any(|(key, value)| {
+                    self.dimensions.contains_key(key) && combination.get(key) != Some(value)
+                });
+                if !overwrites_original {
+                    for (key, value) in include {
+                        combination.insert(key.clone(), value.clone());
+                    }
+                    extended = true;
+                }
+            }
+            if !extended {
+                combinations.push(include.clone());
+            }
+        }
+        Ok(combinations)
+    }
+}
+
+/// Whether all the values of `partial` are in `mapping`, nested mappings matching partially too.
+fn is_partial_match(partial: &Mapping, mapping: &Mapping) -> bool {
+    partial
+        .iter()
+        .all(|(key, value)| match (value, mapping.get(key)) {
+            (Value::Mapping(partial), Some(Value::Mapping(mapping))) => {
+                is_partial_match(partial, mapping)
+            }
+            (value, Some(other)) => value == other,
+            (_, None) => false,
+        })
+}
+
+/// Name of a matrix job, a name with `${{ matrix.<key> }}` expressions is used as is.
+fn matrix_job_name(name: &str, matrix: &Mapping) -> String {
+    if matrix.is_empty() {
+        return name.to_owned();
+    }
+    if name.contains("${{") {
+        let mut expanded = String::new();
+        let mut rest = name;
+        while let Some(start) = rest.find("${{") {
+            let end = match rest[start..].find("}}") {
+                Some(end) => start + end + 2,
+                None => break,
+            };
+            let expression = rest[start + 3..end - 2].trim();
+            expanded.push_str(&rest[..start]);
+            match expression
+                .strip_prefix("matrix.")
+                .and_then(|key| matrix.get(&Value::String(key.to_owned())))
+            {
+                Some(value) => expanded.push_str(&format_matrix_value(value)),
+                None => expanded.push_str(&rest[start..end]),
+            }
+            rest = &rest[end..];
+        }
+        expanded.push_str(rest);
+        return expanded;
+    }
+    let values = matrix
+        .iter()
+        .map(|(_, value)| format_matrix_value(value))
+        .collect::<Vec<_>>();
+    format!("{} ({})", name, values.join(", "))
+}
+
+/// Matrix value as it appears in job names.
+pub fn format_matrix_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.to_owned(),
+        Value::Number(value) => value.to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Null => String::new(),
+        value => serde_json::to_string(value).unwrap_or_default(),
+    }
+}
+
+/// Expected jobs matched against the job names of a workflow run.
+#[derive(Debug, Default, PartialEq)]
+pub struct JobComparison {
+    pub matched: Vec<String>,
+    /// Expected jobs the run does not have.
+    pub missing: Vec<String>,
+    /// Jobs of the run which are not expected.
+    pub unexpected: Vec<String>,
+}
+
+impl JobComparison {
+    pub fn is_match(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+pub fn compare_jobs<'a>(
+    expected: &[ExpectedJob],
+    actual: impl IntoIterator<Item = &'a str>,
+) -> JobComparison {
+    let mut unexpected = actual.into_iter().map(str::to_owned).collect::<Vec<_>>();
+    let mut comparison = JobComparison::default();
+    for job in expected {
+        match unexpected.iter().position(|name| *name == job.name) {
+            Some(i) => comparison.matched.push(unexpected.remove(i)),
+            None => comparison.missing.push(job.name.to_owned()),
+        }
+    }
+    comparison.unexpected = unexpected;
+    comparison
+}
+
+#[cfg(feature = "workflows")]
+pub fn compare_run_jobs(
+    expected: &[ExpectedJob],
+    actual: &[crate::core::workflow_jobs::WorkflowRunJob],
+) -> JobComparison {
+    compare_jobs(expected, actual.iter().map(|job| job.name.as_str()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActionRef {
+    /// Action or reusable workflow in the same repository, eg. `./.github/actions/setup`.
+    Local(String),
+    /// Docker Hub or registry image, eg. `docker://alpine:3.8`.
+    Docker(String),
+    Repository {
+        owner: String,
+        repo: String,
+        path: Option<String>,
+        git_ref: String,
+    },
+}
+
+impl ActionRef {
+    pub fn parse(uses: &str) -> Result<Self> {
+        if uses.starts_with("./") {
+            return Ok(ActionRef::Local(uses.to_owned()));
+        }
+        if let Some(image) = uses.strip_prefix("docker://") {
+            return Ok(ActionRef::Docker(image.to_owned()));
+        }
+        let invalid = || anyhow!("`{}` is not in the form owner/repo@ref", uses);
+        let (action, git_ref) = match uses.rfind('@') {
+            Some(at) => (&uses[..at], &uses[at + 1..]),
+            None => return Err(invalid()),
+        };
+        let mut parts = action.splitn(3, '/');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(owner), Some(repo), path) if !owner.is_empty() && !repo.is_empty() => {
+                Ok(ActionRef::Repository {
+                    owner: owner.to_owned(),
+                    repo: repo.to_owned(),
+                    path: path.map(str::to_owned),
+                    git_ref: git_ref.to_owned(),
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Whether the reference points at a full length commit SHA.
+    pub fn is_pinned(&self) -> bool {
+        match self {
+            ActionRef::Repository { git_ref, .. } => is_commit_sha(git_ref),
+            ActionRef::Docker(image) => image.contains("@sha256:"),
+            ActionRef::Local(_) => true,
+        }
+    }
+
+    pub fn is_third_party(&self) -> bool {
+        match self {
+            ActionRef::Repository { owner, .. } => !FIRST_PARTY_OWNERS.contains(&owner.as_str()),
+            ActionRef::Docker(_) => true,
+            ActionRef::Local(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for ActionRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ActionRef::Local(path) => write!(f, "{}", path),
+            ActionRef::Docker(image) => write!(f, "docker://{}", image),
+            ActionRef::Repository {
+                owner,
+                repo,
+                path,
+                git_ref,
+            } => match path {
+                Some(path) => write!(f, "{}/{}/{}@{}", owner, repo, path, git_ref),
+                None => write!(f, "{}/{}@{}", owner, repo, git_ref),
+            },
+        }
+    }
+}
+
+/// A `uses:` line found in the source of a workflow file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsesLine {
+    /// Zero based line index.
+    pub line: usize,
+    pub uses: String,
+    /// Trailing comment without the `#`, starting with the tag of a pinned action.
+    pub comment: Option<String>,
+}
+
+impl UsesLine {
+    /// Tag of a pinned action, the first word of the comment after its commit SHA.
+    pub fn pinned_tag(&self) -> Option<&str> {
+        if !self.is_pinned() {
+            return None;
+        }
+        self.comment.as_deref()?.split_whitespace().next()
+    }
+
+    /// Comment kept when the line is rewritten, the one after the tag of a pinned action.
+    fn note(&self) -> Option<&str> {
+        let comment = self.comment.as_deref()?.trim();
+        let note = if self.is_pinned() {
+            comment.split_once(char::is_whitespace)?.1.trim_start()
+        } else {
+            comment
+        };
+        Some(note).filter(|note| !note.is_empty())
+    }
+
+    fn is_pinned(&self) -> bool {
+        self.uses
+            .rsplit_once('@')
+            .is_some_and(|(_, git_ref)| is_commit_sha(git_ref))
+    }
+}
+
+/// Byte ranges of the value and the trailing comment of a `uses:` line, quotes excluded.
+fn uses_spans(line: &str) -> Option<(usize, usize, Option<usize>)> {
+    let content = line.trim_end_matches('\r');
+    let mut rest = content.trim_start();
+    if let Some(item) = rest.strip_prefix('-') {
+        rest = item.trim_start();
+    }
+    rest = rest.strip_prefix("uses:")?;
+    let value = rest.trim_start();
+    let mut start = content.len() - value.len();
+    let end = match value.chars().next() {
+        Some(quote) if quote == '"' || quote == '\'' => {
+            start += 1;
+            start + value[1..].find(quote)?
+        }
+        Some(_) => start + value.find(char::is_whitespace).unwrap_or(value.len()),
+        None => return None,
+    };
+    let comment = content[end..].find('#').map(|i| end + i);
+    Some((start, end, comment))
+}
+
+/// `uses:` references of a workflow source, found line by line so they can be rewritten in place.
+pub fn find_uses(source: &str) -> Vec<UsesLine> {
+    source
+        .split('\n')
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let (start, end, comment) = uses_spans(line)?;
+            Some(UsesLine {
+                line: i,
+                uses: line[start..end].to_owned(),
+                comment: comment.map(|c| line[c + 1..].trim().to_owned()),
+            })
+        })
+        .collect()
+}
+
+/// Rewrites the `uses:` lines `rewrite` returns a new value and tag for, everything else in the
+/// source, including the quoting of the value and the comment following the tag, is kept as is.
+pub fn rewrite_uses<F>(source: &str, mut rewrite: F) -> String
+where
+    F: FnMut(&UsesLine) -> Option<(String, String)>,
+{
+    let uses = find_uses(source);
+    source
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            let found = uses.iter().find(|uses| uses.line == i);
+            let replacement = found.and_then(&mut rewrite);
+            match (found.zip(replacement), uses_spans(line)) {
+                (Some((found, (value, tag))), Some((start, end, _))) => {
+                    let comment = match found.note() {
+                        Some(note) => format!("{} {}", tag, note),
+                        None => tag,
+                    };
+                    let quote = &line[end..]
+                        .chars()
+                        .next()
+                        .filter(|c| *c == '"' || *c == '\'');
+                    let line_end = if line.ends_with('\r') { "\r" } else { "" };
+                    format!(
+                        "{}{}{} # {}{}",
+                        &line[..start],
+                        value,
+                        quote.map(String::from).unwrap_or_default(),
+                        comment,
+                        line_end
+                    )
+                }
+                _ => line.to_owned(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn is_commit_sha(git_ref: &str) -> bool {
+    git_ref.len() == 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LintKind {
+    Schema,
+    CyclicNeeds,
+    UndefinedJob,
+    UnknownContext,
+    UnpinnedAction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    pub kind: LintKind,
+    /// Path to the offending value, eg. `jobs.build.steps[2]`.
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self {
+            LintKind::Schema => "schema",
+            LintKind::CyclicNeeds => "cyclic-needs",
+            LintKind::UndefinedJob => "undefined-job",
+            LintKind::UnknownContext => "unknown-context",
+            LintKind::UnpinnedAction => "unpinned-action",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.location.is_empty() {
+            write!(f, "{} [{}]", self.message, self.kind)
+        } else {
+            write!(f, "{}: {} [{}]", self.location, self.message, self.kind)
+        }
+    }
+}
+
+impl LintIssue {
+    fn new(kind: LintKind, location: impl Into<String>, message: impl Into<String>) -> Self {
+        LintIssue {
+            kind,
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Lints the source of a workflow file, an invalid schema stops the other checks.
+pub fn lint(source: &str) -> Vec<LintIssue> {
+    let workflow = match WorkflowFile::parse(source) {
+        Ok(workflow) => workflow,
+        Err(err) => return vec![LintIssue::new(LintKind::Schema, "", err.to_string())],
+    };
+    let mut issues = Vec::new();
+
+    if workflow.jobs.is_empty() {
+        issues.push(LintIssue::new(LintKind::Schema, "jobs", "no jobs defined"));
+    }
+    for (job_id, job) in &workflow.jobs {
+        let location = format!("jobs.{}", job_id);
+        if job.steps.is_empty() == job.uses.is_none() {
+            issues.push(LintIssue::new(
+                LintKind::Schema,
+                &location,
+                "a job must have either `steps` or `uses`",
+            ));
+        }
+        for need in &job.needs {
+            if !workflow.jobs.contains_key(need) {
+                issues.push(LintIssue::new(
+                    LintKind::UndefinedJob,
+                    format!("{}.needs", location),
+                    format!("job `{}` is not defined", need),
+                ));
+            }
+        }
+        for (i, step) in job.steps.iter().enumerate() {
+            if step.uses.is_some() == step.run.is_some() {
+                issues.push(LintIssue::new(
+                    LintKind::Schema,
+                    format!("{}.steps[{}]", location, i),
+                    "a step must have either `uses` or `run`",
+                ));
+            }
+        }
+    }
+
+    if let Some(cycle) = find_needs_cycle(&workflow.jobs) {
+        issues.push(LintIssue::new(
+            LintKind::CyclicNeeds,
+            format!("jobs.{}.needs", cycle[0]),
+            format!("jobs depend on each other: {}", cycle.join(" -> ")),
+        ));
+    }
+
+    for (location, uses) in workflow.uses() {
+        match ActionRef::parse(uses) {
+            Ok(action) if action.is_third_party() && !action.is_pinned() => {
+                issues.push(LintIssue::new(
+                    LintKind::UnpinnedAction,
+                    location,
+                    format!("`{}` is not pinned to a commit SHA", uses),
+                ))
+            }
+            Ok(_) => {}
+            Err(err) => issues.push(LintIssue::new(LintKind::Schema, location, err.to_string())),
+        }
+    }
+
+    if let Ok(document) = serde_yaml::from_str::<Value>(source) {
+        check_expressions(&document, "", false, &mut issues);
+    }
+    issues
+}
+
+/// First cycle in the `needs` of the jobs, starting and ending with the same job.
+fn find_needs_cycle(jobs: &BTreeMap<String, Job>) -> Option<Vec<&str>> {
+    fn visit<'a>(
+        job_id: &'a str,
+        jobs: &'a BTreeMap<String, Job>,
+        done: &mut Vec<&'a str>,
+        stack: &mut Vec<&'a str>,
+    ) -> Option<Vec<&'a str>> {
+        if let Some(start) = stack.iter().position(|id| *id == job_id) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(job_id);
+            return Some(cycle);
+        }
+        if done.contains(&job_id) {
+            return None;
+        }
+        stack.push(job_id);
+        for need in jobs.get(job_id).map_or(&[][..], |job| &job.needs[..]) {
+            if let Some(cycle) = visit(need, jobs, done, stack) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        done.push(job_id);
+        None
+    }
+
+    let mut done = Vec::new();
+    jobs.keys()
+        .find_map(|job_id| visit(job_id, jobs, &mut done, &mut Vec::new()))
+}
+
+fn check_expressions(value: &Value, location: &str, condition: bool, issues: &mut Vec<LintIssue>) {
+    match value {
+        Value::String(text) => {
+            let expressions = if condition && !text.contains("${{") {
+                vec![text.as_str()]
+            } else {
+                embedded_expressions(text)
+            };
+            for context in expressions.into_iter().flat_map(expression_contexts) {
+                if !CONTEXTS.contains(&context) {
+                    issues.push(LintIssue::new(
+                        LintKind::UnknownContext,
+                        location,
+                        format!("unknown expression context `{}`", context),
+                    ));
+                }
+            }
+        }
+        Value::Sequence(values) => {
+            for (i, value) in values.iter().enumerate() {
+                check_expressions(value, &format!("{}[{}]", location, i), false, issues);
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let key = match key {
+                    Value::String(key) => key.to_owned(),
+                    _ => serde_yaml::to_string(key).unwrap_or_default(),
+                };
+                let child = if location.is_empty() {
+                    key.to_owned()
+                } else {
+                    format!("{}.{}", location, key)
+                };
+                check_expressions(value, &child, key == "if", issues);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Bodies of the `${{ }}` expressions within a string.
+fn embedded_expressions(text: &str) -> Vec<&str> {
+    let mut expressions = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${{") {
+        rest = &rest[start + 3..];
+        match rest.find("}}") {
+            Some(end) => {
+                expressions.push(&rest[..end]);
+                rest = &rest[end + 2..];
+            }
+            None => break,
+        }
+    }
+    expressions
+}
+
+/// Names of the contexts referenced by an expression, skipping literals and function calls.
+fn expression_contexts(expression: &str) -> Vec<&str> {
+    let mut contexts = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '\'' {
+            // String literal, a quote is escaped by doubling it.
+            while let Some((_, c)) = chars.next() {
+                if c == '\'' && chars.peek().map(|(_, c)| *c) != Some('\'') {
+                    break;
+                } else if c == '\'' {
+                    chars.next();
+                }
+            }
+        } else if c.is_ascii_digit() {
+            while let Some((_, c)) = chars.peek() {
+                if c.is_ascii_alphanumeric() || *c == '.' {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = start + c.len_utf8();
+            while let Some((i, c)) = chars.peek() {
+                if c.is_ascii_alphanumeric() || *c == '_' || *c == '-' {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let word = &expression[start..end];
+            let after_dot = expression[..start].trim_end().ends_with('.');
+            let is_call = expression[end..].trim_start().starts_with('(');
+            let is_literal = ["true", "false", "null", "NaN", "Infinity"].contains(&word);
+            if !after_dot && !is_call && !is_literal {
+                contexts.push(word);
+            }
+        }
+    }
+    contexts
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"
+name: CI
+on:
+  push:
+    branches: [ main ]
+  schedule:
+    - cron: '0 4 * * 1'
+  workflow_dispatch:
+    inputs:
+      debug:
+        description: Enable debug logs
+        type: boolean
+        default: false
+jobs:
+  build:
+    runs-on: ${{ matrix.os }}
+    strategy:
+      matrix:
+        os: [ubuntu-latest, macos-latest]
+        rust: [stable]
+    steps:
+      - uses: actions/checkout@v2
+      - uses: actions-rs/toolchain@b2417cde72dcf67f306c0ae8e0828a81bf0b189f
+        with:
+          toolchain: ${{ matrix.rust }}
+      - run: cargo build
+  deploy:
+    needs: build
+    if: github.ref == 'refs/heads/main' && inputs.debug != true
+    uses: ./.github/workflows/deploy.yml
+"#;
+
+    #[test]
+    fn parse_workflow_file() -> Result<()> {
+        let workflow = WorkflowFile::parse(WORKFLOW)?;
+
+        assert_eq!(workflow.name.as_deref(), Some("CI"));
+        assert_eq!(
+            workflow.on.events(),
+            vec!["push", "schedule", "workflow_dispatch"]
+        );
+        assert_eq!(
+            workflow.on.inputs()["debug"].input_type.as_deref(),
+            Some("boolean")
+        );
+        assert_eq!(workflow.jobs["deploy"].needs, vec!["build"]);
+        let matrix = match &workflow.jobs["build"].strategy {
+            Some(Strategy {
+                matrix: Some(MatrixDefinition::Matrix(matrix)),
+                ..
+            }) => matrix,
+            strategy => panic!("unexpected strategy {:?}", strategy),
+        };
+        assert_eq!(
+            matrix.dimensions.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            vec!["os", "rust"]
+        );
+        assert_eq!(
+            workflow.uses(),
+            vec![
+                ("jobs.build.steps[0]".to_owned(), "actions/checkout@v2"),
+                (
+                    "jobs.build.steps[1]".to_owned(),
+                    "actions-rs/toolchain@b2417cde72dcf67f306c0ae8e0828a81bf0b189f"
+                ),
+                ("jobs.deploy".to_owned(), "./.github/workflows/deploy.yml"),
+            ]
+        );
+        assert!(lint(WORKFLOW).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_action_ref() -> Result<()> {
+        let action = ActionRef::parse("octo-org/monorepo/actions/setup@v1.2")?;
+        assert_eq!(
+            action,
+            ActionRef::Repository {
+                owner: "octo-org".into(),
+                repo: "monorepo".into(),
+                path: Some("actions/setup".into()),
+                git_ref: "v1.2".into(),
+            }
+        );
+        assert!(action.is_third_party());
+        assert!(!action.is_pinned());
+        assert!(!ActionRef::parse("actions/checkout@v2")?.is_third_party());
+        assert!(ActionRef::parse("docker://alpine:3.8")?.is_third_party());
+        assert!(ActionRef::parse("octo-org/action").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn lint_workflow_file() {
+        let issues = lint(
+            r#"
+on: push
+jobs:
+  build:
+    needs: [test, package]
+    runs-on: ubuntu-latest
+    steps:
+      - uses: octo-org/setup@v1
+      - run: echo ${{ secret.TOKEN }} ${{ format('{0}', env.NAME) }}
+        if: success() && steps.setup.outputs.ok == 'yes'
+  test:
+    needs: build
+    runs-on: ubuntu-latest
+    steps:
+      - name: no action
+"#,
+        );
+        let issues = issues
+            .iter()
+            .map(|i| (i.kind, i.location.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            vec![
+                (LintKind::UndefinedJob, "jobs.build.needs"),
+                (LintKind::Schema, "jobs.test.steps[0]"),
+                (LintKind::CyclicNeeds, "jobs.build.needs"),
+                (LintKind::UnpinnedAction, "jobs.build.steps[0]"),
+                (LintKind::UnknownContext, "jobs.build.steps[1].run"),
+            ]
+        );
+
+        let issues = lint("on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    step: []\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, LintKind::Schema);
+        assert!(issues[0].message.contains("unknown field `step`"));
+    }
+
+    #[test]
+    fn contexts_of_expression() {
+        assert_eq!(
+            expression_contexts(
+                "contains(github.event.head_commit.message, 'it''s [skip ci]') || matrix.os == 'x'"
+            ),
+            vec!["github", "matrix"]
+        );
+        assert_eq!(
+            expression_contexts("1.5 > 0x1F && !false"),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn expand_matrix_include() -> Result<()> {
+        let workflow = WorkflowFile::parse(
+            r#"
+on: push
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    strategy:
+      max-parallel: 2
+      matrix:
+        fruit: [apple, pear]
+        animal: [cat, dog]
+        include:
+          - color: green
+          - color: pink
+            animal: cat
+          - fruit: apple
+            shape: circle
+          - fruit: banana
+          - fruit: banana
+            animal: cat
+    steps:
+      - run: echo
+"#,
+        )?;
+        let job = &workflow.jobs["build"];
+        let names = job
+            .expand("build")?
+            .into_iter()
+            .map(|job| job.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec![
+                "build (apple, cat, pink, circle)",
+                "build (apple, dog, green, circle)",
+                "build (pear, cat, pink)",
+                "build (pear, dog, green)",
+                "build (banana)",
+                "build (banana, cat)",
+            ]
+        );
+        assert_eq!(job.max_parallel(), Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn expand_matrix_exclude() -> Result<()> {
+        let workflow = WorkflowFile::parse(
+            r#"
+on: push
+jobs:
+  test:
+    name: Test on ${{ matrix.os }} with node ${{ matrix.version }}
+    runs-on: ${{ matrix.os }}
+    strategy:
+      matrix:
+        os: [macos-latest, windows-latest]
+        version: [12, 14, 16]
+        environment: [staging, production]
+        exclude:
+          - os: macos-latest
+            version: 12
+            environment: production
+          - os: windows-latest
+            version: 16
+    steps:
+      - run: echo
+"#,
+        )?;
+        let jobs = workflow.jobs["test"].expand("test")?;
+
+        assert_eq!(jobs.len(), 9);
+        assert_eq!(jobs[0].name, "Test on macos-latest with node 12");
+        assert_eq!(workflow.jobs["test"].max_parallel(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn compare_expected_jobs() {
+        let expected = ["lint", "build (stable)", "build (nightly)"]
+            .iter()
+            .map(|name| ExpectedJob {
+                name: name.to_string(),
+                matrix: Mapping::new(),
+            })
+            .collect::<Vec<_>>();
+
+        let comparison = compare_jobs(&expected, vec!["build (stable)", "lint", "deploy"]);
+
+        assert_eq!(
+            comparison,
+            JobComparison {
+                matched: vec!["lint".into(), "build (stable)".into()],
+                missing: vec!["build (nightly)".into()],
+                unexpected: vec!["deploy".into()],
+            }
+        );
+        assert!(!comparison.is_match());
+    }
+
+    #[test]
+    fn rewrite_uses_lines() {
+        let source = r#"jobs:
+  build:
+    steps:
+      - uses: actions/checkout@v2 # checkout
+      - name: Toolchain
+        uses: 'actions-rs/toolchain@v1'
+        # uses: commented/out@v1
+"#;
+        assert_eq!(
+            find_uses(source),
+            vec![
+                UsesLine {
+                    line: 3,
+                    uses: "actions/checkout@v2".into(),
+                    comment: Some("checkout".into()),
+                },
+                UsesLine {
+                    line: 5,
+                    uses: "actions-rs/toolchain@v1".into(),
+                    comment: None,
+                },
+            ]
+        );
+
+        let rewritten = rewrite_uses(source, |uses| {
+            if uses.uses.starts_with("actions-rs/") {
+                Some((
+                    "actions-rs/toolchain@b2417cde72dcf67f306c0ae8e0828a81bf0b189f".into(),
+                    "v1".into(),
+                ))
+            } else {
+                None
+            }
+        });
+        assert_eq!(
+            rewritten,
+            source.replace(
+                "uses: 'actions-rs/toolchain@v1'",
+                "uses: 'actions-rs/toolchain@b2417cde72dcf67f306c0ae8e0828a81bf0b189f' # v1"
+            )
+        );
+
+        let crlf = "    - uses: octo-org/action@v1\r\n";
+        assert_eq!(
+            rewrite_uses(crlf, |_| Some(("octo-org/action@0123".into(), "v1".into()))),
+            "    - uses: octo-org/action@0123 # v1\r\n"
+        );
+    }
+
+    #[test]
+    fn rewrite_uses_keeps_notes() {
+        let old_sha = "5a4ac9002d0be2fb38bd78e4b4dbde5606d7042f";
+        let new_sha = "ec3a7ce113134d7a93b817d10a8272cb61118579";
+        let source = format!(
+            "- uses: actions/checkout@v2 # checkout\n- uses: actions/cache@{} # v1 restore deps\n- uses: actions/setup-node@{} # v1\n",
+            old_sha, old_sha
+        );
+        let uses = find_uses(&source);
+        assert_eq!(uses[0].pinned_tag(), None);
+        assert_eq!(uses[1].pinned_tag(), Some("v1"));
+        assert_eq!(uses[2].pinned_tag(), Some("v1"));
+
+        let rewritten = rewrite_uses(&source, |uses| {
+            let (action, _) = uses.uses.split_once('@').unwrap();
+            Some((format!("{}@{}", action, new_sha), "v2".into()))
+        });
+        assert_eq!(
+            rewritten,
+            format!(
+                "- uses: actions/checkout@{} # v2 checkout\n- uses: actions/cache@{} # v2 restore deps\n- uses: actions/setup-node@{} # v2\n",
+                new_sha, new_sha, new_sha
+            )
+        );
+    }
+}